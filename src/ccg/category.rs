@@ -2,7 +2,58 @@
 
 use std::fmt;
 use std::hash::{Hash, Hasher};
-use crate::common::{FeatureStructure, FeatureValue};
+use crate::common::{Bindings, FeatureStructure, FeatureValue};
+
+/// Slash modalities controlling which combinators a functor takes part in
+///
+/// Following Baldridge and Kruijff, every slash permits application. `⋆`
+/// permits nothing else, `⋄` also permits order-preserving (harmonic)
+/// composition, `×` permits crossed composition, and the unmarked `·`
+/// permits every combinator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SlashModality {
+    /// Application only (`⋆`)
+    Star,
+    /// Application and harmonic composition (`⋄`)
+    Diamond,
+    /// Application and crossed composition (`×`)
+    Cross,
+    /// Every combinator (`·`)
+    #[default]
+    Dot,
+}
+
+impl SlashModality {
+    /// Check whether the slash permits composition, crossed or harmonic
+    pub fn permits_composition(&self, crossed: bool) -> bool {
+        match self {
+            SlashModality::Star => false,
+            SlashModality::Diamond => !crossed,
+            SlashModality::Cross => crossed,
+            SlashModality::Dot => true,
+        }
+    }
+    
+    /// Check whether a slash of this modality can fill a slot of modality `slot`
+    ///
+    /// A slash fills a slot when it permits every combinator the slot does,
+    /// so `·` fills any slot and any slash fills a `⋆` slot.
+    pub fn fills(&self, slot: SlashModality) -> bool {
+        *self == slot || slot == SlashModality::Star || *self == SlashModality::Dot
+    }
+}
+
+impl fmt::Display for SlashModality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SlashModality::Star => write!(f, "⋆"),
+            SlashModality::Diamond => write!(f, "⋄"),
+            SlashModality::Cross => write!(f, "×"),
+            // The permissive slash is left unmarked
+            SlashModality::Dot => Ok(()),
+        }
+    }
+}
 
 /// The core syntactic category types in CCG, enhanced with morphosyntactic features
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -10,9 +61,9 @@ pub enum CCGCategory {
     /// Atomic categories like S, NP, N
     Atomic(String, FeatureStructure),
     /// Forward slash category (X/Y)
-    Forward(Box<CCGCategory>, Box<CCGCategory>),
+    Forward(Box<CCGCategory>, Box<CCGCategory>, SlashModality),
     /// Backward slash category (X\Y)
-    Backward(Box<CCGCategory>, Box<CCGCategory>),
+    Backward(Box<CCGCategory>, Box<CCGCategory>, SlashModality),
 }
 
 // Manual implementation of Hash for CCGCategory
@@ -26,17 +77,19 @@ impl Hash for CCGCategory {
                 // We don't hash features since FeatureStructure doesn't implement Hash
                 // This means two categories with same name but different features will hash the same
             }
-            CCGCategory::Forward(x, y) => {
+            CCGCategory::Forward(x, y, modality) => {
                 // Hash discriminant and both subcategories
                 1.hash(state);  // Discriminant for Forward
                 x.hash(state);
                 y.hash(state);
+                modality.hash(state);
             }
-            CCGCategory::Backward(x, y) => {
+            CCGCategory::Backward(x, y, modality) => {
                 // Hash discriminant and both subcategories
                 2.hash(state);  // Discriminant for Backward
                 x.hash(state);
                 y.hash(state);
+                modality.hash(state);
             }
         }
     }
@@ -52,18 +105,18 @@ impl fmt::Display for CCGCategory {
                 }
                 Ok(())
             }
-            CCGCategory::Forward(x, y) => {
+            CCGCategory::Forward(x, y, modality) => {
                 if matches!(**x, CCGCategory::Atomic(_, _)) {
-                    write!(f, "{}/{}", x, y)
+                    write!(f, "{}/{}{}", x, modality, y)
                 } else {
-                    write!(f, "({})/{}", x, y)
+                    write!(f, "({})/{}{}", x, modality, y)
                 }
             }
-            CCGCategory::Backward(x, y) => {
+            CCGCategory::Backward(x, y, modality) => {
                 if matches!(**x, CCGCategory::Atomic(_, _)) {
-                    write!(f, "{}\\{}", x, y)
+                    write!(f, "{}\\{}{}", x, modality, y)
                 } else {
-                    write!(f, "({})\\{}", x, y)
+                    write!(f, "({})\\{}{}", x, modality, y)
                 }
             }
         }
@@ -83,12 +136,30 @@ impl CCGCategory {
     
     /// Create a forward slash category (X/Y)
     pub fn forward(left: CCGCategory, right: CCGCategory) -> Self {
-        CCGCategory::Forward(Box::new(left), Box::new(right))
+        Self::forward_with_modality(left, right, SlashModality::default())
     }
 
     /// Create a backward slash category (X\Y)
     pub fn backward(left: CCGCategory, right: CCGCategory) -> Self {
-        CCGCategory::Backward(Box::new(left), Box::new(right))
+        Self::backward_with_modality(left, right, SlashModality::default())
+    }
+    
+    /// Create a forward slash category with a slash modality
+    pub fn forward_with_modality(left: CCGCategory, right: CCGCategory, modality: SlashModality) -> Self {
+        CCGCategory::Forward(Box::new(left), Box::new(right), modality)
+    }
+
+    /// Create a backward slash category with a slash modality
+    pub fn backward_with_modality(left: CCGCategory, right: CCGCategory, modality: SlashModality) -> Self {
+        CCGCategory::Backward(Box::new(left), Box::new(right), modality)
+    }
+    
+    /// Get the modality of the outermost slash, or `None` for an atomic category
+    pub fn modality(&self) -> Option<SlashModality> {
+        match self {
+            CCGCategory::Atomic(..) => None,
+            CCGCategory::Forward(_, _, modality) | CCGCategory::Backward(_, _, modality) => Some(*modality),
+        }
     }
     
     /// Convenience method for creating S category
@@ -105,6 +176,11 @@ impl CCGCategory {
     pub fn n() -> Self {
         Self::atomic("N")
     }
+
+    /// Convenience method for creating the CONJ category of coordinators
+    pub fn conj() -> Self {
+        Self::atomic("CONJ")
+    }
     
     /// Create a noun with number feature
     pub fn n_with_number(number: &str) -> Self {
@@ -137,8 +213,71 @@ impl CCGCategory {
         }
     }
     
+    /// Add features to the atomic category this category ultimately yields
+    ///
+    /// Returns `None` if the features conflict with those already there.
+    pub fn with_result_features(&self, features: &FeatureStructure) -> Option<CCGCategory> {
+        match self {
+            CCGCategory::Atomic(name, own) => {
+                own.unify(features).map(|unified| CCGCategory::Atomic(name.clone(), unified))
+            },
+            CCGCategory::Forward(x, y, modality) => {
+                x.with_result_features(features).map(|x| CCGCategory::forward_with_modality(x, (**y).clone(), *modality))
+            },
+            CCGCategory::Backward(x, y, modality) => {
+                x.with_result_features(features).map(|x| CCGCategory::backward_with_modality(x, (**y).clone(), *modality))
+            },
+        }
+    }
+    
+    /// Get the atom at the end of the category's chain of results
+    ///
+    /// This is `S` for `(S\NP)/NP`. Categories that unify share their result
+    /// atom, as do a category and the results of composing into it.
+    pub fn result_atom(&self) -> &str {
+        match self {
+            CCGCategory::Atomic(name, _) => name,
+            CCGCategory::Forward(result, _, _) | CCGCategory::Backward(result, _, _) => result.result_atom(),
+        }
+    }
+    
+    /// Get the result atom of the outermost argument, or `None` for an atomic category
+    pub fn arg_atom(&self) -> Option<&str> {
+        match self {
+            CCGCategory::Atomic(..) => None,
+            CCGCategory::Forward(_, argument, _) | CCGCategory::Backward(_, argument, _) => Some(argument.result_atom()),
+        }
+    }
+    
+    /// Get the slash-nesting depth of the category
+    ///
+    /// Atomic categories have depth 0, `S\NP` has depth 1 and `(S\NP)/NP`
+    /// and `S/(S\NP)` have depth 2.
+    pub fn depth(&self) -> usize {
+        match self {
+            CCGCategory::Atomic(..) => 0,
+            CCGCategory::Forward(result, argument, _) | CCGCategory::Backward(result, argument, _) => {
+                1 + result.depth().max(argument.depth())
+            },
+        }
+    }
+    
     /// Unify this category with another
+    ///
+    /// A feature missing from one atomic category is taken as underspecified,
+    /// so `S` unifies with `S[dcl]`, while conflicting values do not unify.
     pub fn unify(&self, other: &CCGCategory) -> Option<CCGCategory> {
+        self.unify_with_bindings(other, &mut Bindings::new())
+    }
+    
+    /// Unify with another category, binding feature variables consistently
+    ///
+    /// A variable bound in one part of the category is respected everywhere
+    /// else, and the bindings can be applied to related categories afterwards
+    /// with [`CCGCategory::apply_bindings`]. A slash of `other` unifies with
+    /// one of `self` whose slot its modality fills, and the modality of
+    /// `self` is kept.
+    pub fn unify_with_bindings(&self, other: &CCGCategory, bindings: &mut Bindings) -> Option<CCGCategory> {
         match (self, other) {
             (CCGCategory::Atomic(s1, f1), CCGCategory::Atomic(s2, f2)) => {
                 if s1 != s2 {
@@ -146,31 +285,39 @@ impl CCGCategory {
                 }
                 
                 // Unify feature structures
-                if let Some(unified_features) = f1.unify(f2) {
-                    Some(CCGCategory::Atomic(s1.clone(), unified_features))
-                } else {
-                    None
-                }
+                f1.unify_with_bindings(f2, bindings)
+                    .map(|unified_features| CCGCategory::Atomic(s1.clone(), unified_features))
             }
-            (CCGCategory::Forward(x1, y1), CCGCategory::Forward(x2, y2)) => {
+            (CCGCategory::Forward(x1, y1, m1), CCGCategory::Forward(x2, y2, m2)) if m2.fills(*m1) => {
                 // Recursively unify components
-                if let (Some(unified_x), Some(unified_y)) = (x1.unify(x2), y1.unify(y2)) {
-                    Some(CCGCategory::Forward(Box::new(unified_x), Box::new(unified_y)))
-                } else {
-                    None
-                }
+                let unified_x = x1.unify_with_bindings(x2, bindings)?;
+                let unified_y = y1.unify_with_bindings(y2, bindings)?;
+                Some(CCGCategory::forward_with_modality(unified_x, unified_y, *m1).apply_bindings(bindings))
             }
-            (CCGCategory::Backward(x1, y1), CCGCategory::Backward(x2, y2)) => {
+            (CCGCategory::Backward(x1, y1, m1), CCGCategory::Backward(x2, y2, m2)) if m2.fills(*m1) => {
                 // Recursively unify components
-                if let (Some(unified_x), Some(unified_y)) = (x1.unify(x2), y1.unify(y2)) {
-                    Some(CCGCategory::Backward(Box::new(unified_x), Box::new(unified_y)))
-                } else {
-                    None
-                }
+                let unified_x = x1.unify_with_bindings(x2, bindings)?;
+                let unified_y = y1.unify_with_bindings(y2, bindings)?;
+                Some(CCGCategory::backward_with_modality(unified_x, unified_y, *m1).apply_bindings(bindings))
             }
             _ => None, // Different category types don't unify
         }
     }
+    
+    /// Replace every bound feature variable in the category by its value
+    pub fn apply_bindings(&self, bindings: &Bindings) -> CCGCategory {
+        match self {
+            CCGCategory::Atomic(name, features) => {
+                CCGCategory::Atomic(name.clone(), features.apply_bindings(bindings))
+            },
+            CCGCategory::Forward(x, y, modality) => {
+                CCGCategory::forward_with_modality(x.apply_bindings(bindings), y.apply_bindings(bindings), *modality)
+            },
+            CCGCategory::Backward(x, y, modality) => {
+                CCGCategory::backward_with_modality(x.apply_bindings(bindings), y.apply_bindings(bindings), *modality)
+            },
+        }
+    }
 }
 
 #[cfg(test)]
@@ -236,4 +383,20 @@ mod tests {
         let unified2 = complex1.unify(&complex3);
         assert!(unified2.is_none());
     }
+    
+    #[test]
+    fn test_modality_unification() {
+        let s = CCGCategory::s();
+        let np = CCGCategory::np();
+        let slash = |modality| CCGCategory::backward_with_modality(s.clone(), np.clone(), modality);
+        
+        // A more permissive slash fills a more restrictive slot
+        assert_eq!(slash(SlashModality::Star).unify(&slash(SlashModality::Dot)), Some(slash(SlashModality::Star)));
+        assert!(slash(SlashModality::Diamond).unify(&slash(SlashModality::Dot)).is_some());
+        assert!(slash(SlashModality::Star).unify(&slash(SlashModality::Cross)).is_some());
+        
+        // but not the other way round, nor across harmonic and crossed slashes
+        assert!(slash(SlashModality::Dot).unify(&slash(SlashModality::Star)).is_none());
+        assert!(slash(SlashModality::Diamond).unify(&slash(SlashModality::Cross)).is_none());
+    }
 }
\ No newline at end of file
@@ -4,10 +4,12 @@ pub mod category;
 pub mod parser;
 pub mod rules;
 pub mod node;
+pub mod supertag;
 
-pub use category::CCGCategory;
-pub use parser::{CCGParser, CCGParserConfig};
+pub use category::{CCGCategory, SlashModality};
+pub use parser::{CCGParser, CCGParserConfig, TypeRaisingMode};
 pub use node::CCGNode;
+pub use supertag::SupertagModel;
 
 use crate::common::Category as CategoryTrait;
 
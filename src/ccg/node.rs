@@ -2,10 +2,11 @@
 
 use std::fmt;
 use crate::ccg::category::CCGCategory;
-use crate::common::ParseNode;
+use crate::common::{ParseNode, Rebuild};
+use crate::common::export::{format_conll, ConllToken, ToConll};
 
 /// A parse tree node for CCG parsing
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct CCGNode {
     /// The syntactic category
     pub category: CCGCategory,
@@ -15,6 +16,35 @@ pub struct CCGNode {
     pub children: Vec<CCGNode>,
     /// The rule used to derive this node
     pub rule: Option<String>,
+    /// The index of the first word covered
+    pub start: usize,
+    /// One past the index of the last word covered
+    pub end: usize,
+}
+
+/// A dependency between word positions, with whether the head projects its argument
+type Dependency = (usize, usize, String, bool);
+
+/// The head of a constituent during dependency extraction
+#[derive(Debug, Clone, Copy)]
+enum Head {
+    /// A word of the input
+    Word(usize),
+    /// The head of another constituent, once a projecting slot is filled
+    Bound(usize),
+    /// Not yet known, falling back to a word if it is never filled
+    Open(usize),
+}
+
+/// An argument slot of a category still waiting to be filled
+#[derive(Debug, Clone)]
+enum Slot {
+    /// Argument `index` of the lexical category of the word at `word`,
+    /// whose head becomes the head `projects` if it is set
+    Argument { word: usize, functor: String, index: usize, projects: Option<usize> },
+    /// The argument of a type-raised constituent with its own head and
+    /// open slots, which fills a slot of the consuming functor
+    Raised { head: usize, slots: Vec<Slot> },
 }
 
 impl CCGNode {
@@ -25,17 +55,264 @@ impl CCGNode {
             word: Some(word.to_string()),
             children: vec![],
             rule: None,
+            start: 0,
+            end: 1,
         }
     }
+    
+    /// Create an empty node standing in for an elided word before a word index
+    ///
+    /// The node covers no words, so it adds nothing to the yield or the span
+    /// of the constituents containing it.
+    pub fn gap(category: CCGCategory, position: usize) -> Self {
+        CCGNode {
+            category,
+            word: None,
+            children: vec![],
+            rule: None,
+            start: position,
+            end: position,
+        }
+    }
+    
+    /// Place a leaf at a word index of the input
+    pub fn at_position(mut self, position: usize) -> Self {
+        self.start = position;
+        self.end = position + 1;
+        self
+    }
 
     /// Create a new internal node
     pub fn internal(category: CCGCategory, children: Vec<CCGNode>, rule: &str) -> Self {
+        let start = children.iter().map(|child| child.start).min().unwrap_or(0);
+        let end = children.iter().map(|child| child.end).max().unwrap_or(start);
         CCGNode {
             category,
             word: None,
             children,
             rule: Some(rule.to_string()),
+            start,
+            end,
+        }
+    }
+    
+    /// Get the span of words covered, as `[start, end)` indices
+    pub fn span(&self) -> (usize, usize) {
+        (self.start, self.end)
+    }
+    
+    /// Render the tree as a bracketing with each category's span
+    ///
+    /// Each node is written `(category[start,end] ...)`, with the word in
+    /// place of the children for a leaf.
+    pub fn to_bracketed_with_spans(&self) -> String {
+        let label = format!("{}[{},{}]", self.category, self.start, self.end);
+        match &self.word {
+            Some(word) => format!("({} {})", label, word),
+            None => {
+                let children: Vec<String> = self.children.iter()
+                    .map(CCGNode::to_bracketed_with_spans)
+                    .collect();
+                format!("({} {})", label, children.join(" "))
+            },
+        }
+    }
+    
+    /// Render the derivation in the textbook layout with a bar for each rule
+    ///
+    /// The words are on the bottom row with their categories above. Each
+    /// combination draws a bar over its children labelled with the rule,
+    /// and the derived category is centred over the bar.
+    pub fn to_derivation_display(&self) -> String {
+        self.derivation_block().0.iter()
+            .rev()
+            .map(|line| line.trim_end())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+    
+    /// Lay out a subtree as lines padded to a common width, from the words up
+    fn derivation_block(&self) -> (Vec<String>, usize) {
+        let category = self.category.to_string();
+        
+        if self.is_leaf() {
+            let word = self.word.clone().unwrap_or_default();
+            let width = word.chars().count().max(category.chars().count());
+            return (vec![pad(&word, width), pad(&category, width)], width);
+        }
+        
+        let blocks: Vec<(Vec<String>, usize)> = self.children.iter()
+            .map(CCGNode::derivation_block)
+            .collect();
+        let height = blocks.iter().map(|(lines, _)| lines.len()).max().unwrap_or(0);
+        let children_width = blocks.iter().map(|(_, width)| width).sum::<usize>()
+            + 2 * blocks.len().saturating_sub(1);
+        
+        // Place the children side by side, leaving shallower ones blank above
+        let mut lines: Vec<String> = (0..height)
+            .map(|row| {
+                blocks.iter()
+                    .map(|(block, width)| block.get(row).cloned().unwrap_or_else(|| " ".repeat(*width)))
+                    .collect::<Vec<_>>()
+                    .join("  ")
+            })
+            .collect();
+        
+        let rule = self.rule.as_deref().unwrap_or("");
+        let bar_width = children_width.max(category.chars().count());
+        let width = (bar_width + rule.chars().count()).max(children_width);
+        let offset = (bar_width - category.chars().count()) / 2;
+        
+        lines.push(format!("{}{}", "-".repeat(bar_width), rule));
+        lines.push(format!("{}{}", " ".repeat(offset), category));
+        (lines.iter().map(|line| pad(line, width)).collect(), width)
+    }
+    
+    /// Extract the predicate-argument dependencies of a derivation
+    ///
+    /// Each dependency is `(head, dependent, relation)`, where the indices are
+    /// word positions and the relation names the argument slot of the head's
+    /// lexical category in CCGbank style, numbered from the innermost
+    /// argument: `(S\NP)/NP.1` is the subject of a transitive verb and
+    /// `(S\NP)/NP.2` its object. Modifiers `X|X` and determiners `NP|N`
+    /// take the head of their argument, so a noun phrase is headed by its noun.
+    /// Dependencies are resolved through composition and type-raising, so a
+    /// composed verb still heads its object.
+    pub fn dependencies(&self) -> Vec<(usize, usize, String)> {
+        self.labelled_dependencies().into_iter()
+            .map(|(head, dependent, relation, _)| (head, dependent, relation))
+            .collect()
+    }
+    
+    /// Extract the dependencies, marking those whose head projects its argument's head
+    fn labelled_dependencies(&self) -> Vec<Dependency> {
+        let mut heads = Vec::new();
+        let mut unresolved = Vec::new();
+        self.collect_dependencies(&mut heads, &mut unresolved);
+        
+        let resolve = |mut head: usize| loop {
+            match heads[head] {
+                Head::Word(word) | Head::Open(word) => return word,
+                Head::Bound(other) => head = other,
+            }
+        };
+        
+        let mut dependencies: Vec<Dependency> = unresolved.into_iter()
+            .map(|(word, dependent, relation, projects)| (word, resolve(dependent), relation, projects))
+            .collect();
+        dependencies.sort();
+        dependencies
+    }
+    
+    /// Collect the dependencies below this node, returning its head and open slots
+    fn collect_dependencies(
+        &self,
+        heads: &mut Vec<Head>,
+        dependencies: &mut Vec<Dependency>,
+    ) -> (usize, Vec<Slot>) {
+        if self.is_leaf() {
+            let functor = self.category.to_string();
+            let mut arguments = Vec::new();
+            let mut category = &self.category;
+            while let CCGCategory::Forward(result, argument, _) | CCGCategory::Backward(result, argument, _) = category {
+                let projects = result.unify(argument).is_some()
+                    || (result.unify(&CCGCategory::np()).is_some() && argument.unify(&CCGCategory::n()).is_some());
+                arguments.push(projects);
+                category = result;
+            }
+            
+            let head = heads.len();
+            heads.push(if arguments.contains(&true) { Head::Open(self.start) } else { Head::Word(self.start) });
+            
+            // Slots are consumed outermost first but numbered from the innermost
+            let arity = arguments.len();
+            let slots = arguments.into_iter().enumerate()
+                .map(|(position, projects)| Slot::Argument {
+                    word: self.start,
+                    functor: functor.clone(),
+                    index: arity - position,
+                    projects: projects.then_some(head),
+                })
+                .collect();
+            return (head, slots);
         }
+        
+        let mut analyses: Vec<(usize, Vec<Slot>)> = self.children.iter()
+            .map(|child| child.collect_dependencies(heads, dependencies))
+            .collect();
+        let rule = self.rule.as_deref().unwrap_or("");
+        
+        // Which child is the argument, and how many of its slots pass on to the result
+        let (argument, order) = match rule {
+            ">" => (1, 0),
+            "<" => (0, 0),
+            ">T" | "<T" => {
+                let (head, slots) = analyses.remove(0);
+                return (head, vec![Slot::Raised { head, slots }]);
+            },
+            _ => {
+                let composition = rule.strip_prefix(">B").map(|n| (1, n))
+                    .or_else(|| rule.strip_prefix("<B").map(|n| (0, n)))
+                    .map(|(argument, n)| (argument, n.parse().unwrap_or(1)));
+                match composition {
+                    Some(composition) if analyses.len() == 2 => composition,
+                    // Other rules are headed by their first child
+                    _ if !analyses.is_empty() => return analyses.remove(0),
+                    _ => {
+                        heads.push(Head::Word(self.start));
+                        return (heads.len() - 1, Vec::new());
+                    },
+                }
+            },
+        };
+        
+        let argument = analyses.remove(argument);
+        let functor = analyses.remove(0);
+        fill_slot(functor, argument, order, heads, dependencies)
+    }
+}
+
+/// Pad a line with spaces to a width in characters
+fn pad(line: &str, width: usize) -> String {
+    format!("{:<width$}", line, width = width)
+}
+
+/// Fill the first slot of a functor with an argument, passing on `order` of the argument's slots
+fn fill_slot(
+    functor: (usize, Vec<Slot>),
+    argument: (usize, Vec<Slot>),
+    order: usize,
+    heads: &mut [Head],
+    dependencies: &mut Vec<Dependency>,
+) -> (usize, Vec<Slot>) {
+    let (functor_head, mut functor_slots) = functor;
+    let (argument_head, mut argument_slots) = argument;
+    let order = order.min(argument_slots.len());
+    
+    if functor_slots.is_empty() {
+        return (functor_head, functor_slots);
+    }
+    
+    match functor_slots.remove(0) {
+        Slot::Argument { word, functor, index, projects } => {
+            dependencies.push((word, argument_head, format!("{}.{}", functor, index), projects.is_some()));
+            if let Some(head) = projects {
+                heads[head] = Head::Bound(argument_head);
+            }
+            argument_slots.truncate(order);
+            argument_slots.extend(functor_slots);
+            (functor_head, argument_slots)
+        },
+        Slot::Raised { head, slots } => {
+            // The argument consumes the raised constituent with the first slot it keeps open
+            if order == argument_slots.len() {
+                return (argument_head, argument_slots);
+            }
+            let consumer = argument_slots.split_off(order);
+            let (head, rest) = fill_slot((argument_head, consumer), (head, slots), 0, heads, dependencies);
+            argument_slots.extend(rest);
+            (head, argument_slots)
+        },
     }
 }
 
@@ -60,6 +337,49 @@ impl fmt::Display for CCGNode {
     }
 }
 
+impl ToConll for CCGNode {
+    /// Export the words with their lexical categories and dependencies
+    ///
+    /// A word that fills slots of several heads is attached to the first of
+    /// them, and a word filling none is the root. Modifiers and determiners
+    /// attach to the word they modify rather than heading it.
+    fn to_conll(&self) -> String {
+        fn collect_leaves<'a>(node: &'a CCGNode, leaves: &mut Vec<&'a CCGNode>) {
+            if node.is_leaf() {
+                leaves.push(node);
+            }
+            for child in &node.children {
+                collect_leaves(child, leaves);
+            }
+        }
+        
+        let mut leaves = Vec::new();
+        collect_leaves(self, &mut leaves);
+        let dependencies: Vec<(usize, usize, String)> = self.labelled_dependencies().into_iter()
+            .map(|(head, dependent, relation, projects)| {
+                if projects { (dependent, head, relation) } else { (head, dependent, relation) }
+            })
+            .collect();
+        
+        let tokens: Vec<ConllToken> = leaves.iter()
+            .map(|leaf| {
+                let (head, relation) = dependencies.iter()
+                    .find(|(_, dependent, _)| *dependent == leaf.start)
+                    .map(|(head, _, relation)| (head + 1 - self.start, relation.clone()))
+                    .unwrap_or((0, "root".to_string()));
+                ConllToken {
+                    form: leaf.word.clone().unwrap_or_default(),
+                    category: leaf.category.to_string(),
+                    head,
+                    relation,
+                }
+            })
+            .collect();
+        
+        format_conll(&tokens)
+    }
+}
+
 impl ParseNode for CCGNode {
     type Cat = CCGCategory;
     
@@ -80,6 +400,12 @@ impl ParseNode for CCGNode {
     }
 }
 
+impl Rebuild for CCGNode {
+    fn with_children(&self, children: Vec<Self>) -> Self {
+        CCGNode { children, ..self.clone() }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -100,6 +426,16 @@ mod tests {
         assert_eq!(np_node.rule, Some(">".to_string()));
     }
     
+    #[test]
+    fn test_spans() {
+        let det = CCGNode::leaf("the", CCGCategory::forward(CCGCategory::np(), CCGCategory::n())).at_position(2);
+        let noun = CCGNode::leaf("cat", CCGCategory::n()).at_position(3);
+        let np = CCGNode::internal(CCGCategory::np(), vec![det, noun], ">");
+        
+        assert_eq!(np.span(), (2, 4));
+        assert_eq!(np.to_bracketed_with_spans(), "(NP[2,4] (NP/N[2,3] the) (N[3,4] cat))");
+    }
+    
     #[test]
     fn test_parsenode_trait() {
         let np = CCGCategory::np();
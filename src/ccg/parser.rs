@@ -1,10 +1,25 @@
 //! CCG parser implementation
 
 use std::any::Any;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::sync::Arc;
 use crate::ccg::category::CCGCategory;
 use crate::ccg::node::CCGNode;
 use crate::ccg::rules::*;
-use crate::common::{Lexicon, AtomicTypeRegistry, FeatureRegistry, FeatureStructure, FeatureValue, Parser};
+use crate::ccg::supertag::SupertagModel;
+use crate::common::{Lexicon, AtomicTypeRegistry, Bindings, Error, FeatureRegistry, FeatureStructure, FeatureValue, Forest, Morphology, ParseOutcome, Parser, SimpleTokenizer, StepBudget, StepKind, TraceHandle, Tokenizer, Tracer};
+
+/// When the parser applies type-raising
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeRaisingMode {
+    /// Raise every category in every chart cell
+    Eager,
+    /// Raise a category only while combining it with an adjacent cell
+    Lazy,
+    /// Raise a category only to an argument of a functor in the sentence
+    ArgumentOnly,
+}
 
 /// Configuration options for the CCG parser
 #[derive(Debug, Clone)]
@@ -15,10 +30,23 @@ pub struct CCGParserConfig {
     pub enable_type_raising: bool,
     /// Target categories for type-raising (S, NP, etc.)
     pub type_raising_targets: Vec<CCGCategory>,
+    /// Which categories are type-raised
+    pub type_raising_mode: TypeRaisingMode,
     /// Whether to enforce feature unification
     pub enforce_feature_unification: bool,
     /// Whether to use morphosyntactic features
     pub use_morphosyntax: bool,
+    /// Whether coordinations may gap the functor of their right conjunct
+    pub enable_gapping: bool,
+    /// Only build derivations in Eisner normal form, avoiding spurious ambiguity
+    pub normal_form_only: bool,
+    /// Skip adjacent categories whose atoms show that no binary rule can combine them
+    pub prefilter_combinations: bool,
+    /// The deepest slash nesting a derived category may have, if bounded
+    pub max_category_depth: Option<usize>,
+    /// Match categories by unification even without morphosyntax, so that a
+    /// featureless atomic category accepts featured ones of the same name
+    pub underspecified_atomic_unify: bool,
 }
 
 impl Default for CCGParserConfig {
@@ -27,9 +55,42 @@ impl Default for CCGParserConfig {
             max_composition_order: 2,
             enable_type_raising: true,
             type_raising_targets: vec![CCGCategory::s()],
+            type_raising_mode: TypeRaisingMode::ArgumentOnly,
             enforce_feature_unification: false,
             use_morphosyntax: false,
+            enable_gapping: false,
+            normal_form_only: false,
+            prefilter_combinations: true,
+            max_category_depth: None,
+            underspecified_atomic_unify: false,
+        }
+    }
+}
+
+/// The analyses of a sentence prefix in incremental parsing
+#[derive(Debug, Clone)]
+pub struct IncrementalState {
+    /// The number of words integrated so far
+    pub position: usize,
+    /// The alternative stacks of partial constituents, bottom first
+    pub stacks: Vec<Vec<CCGNode>>,
+}
+
+impl IncrementalState {
+    /// Check if some analysis combines the whole prefix into one constituent
+    pub fn is_connected(&self) -> bool {
+        self.stacks.iter().any(|stack| stack.len() == 1)
+    }
+    
+    /// Get the distinct categories of the prefix under its connected analyses
+    pub fn prefix_categories(&self) -> Vec<&CCGCategory> {
+        let mut categories: Vec<&CCGCategory> = Vec::new();
+        for stack in self.stacks.iter().filter(|stack| stack.len() == 1) {
+            if !categories.contains(&&stack[0].category) {
+                categories.push(&stack[0].category);
+            }
         }
+        categories
     }
 }
 
@@ -50,7 +111,15 @@ pub struct CCGParser {
     pub atomic_types: AtomicTypeRegistry,
     pub feature_registry: FeatureRegistry,
     pub config: CCGParserConfig,
+    /// Analyzer consulted for words missing from the lexicon
+    pub morphology: Option<Box<dyn Morphology>>,
+    /// Tokenizer splitting sentences into words
+    pub tokenizer: Arc<dyn Tokenizer>,
     rules: Vec<Box<dyn RuleObj>>,
+    /// The number of binary rule applications attempted so far
+    rule_attempts: Cell<usize>,
+    /// The tracer told about each node added to the chart
+    tracer: TraceHandle,
 }
 
 impl CCGParser {
@@ -77,7 +146,11 @@ impl CCGParser {
             atomic_types: AtomicTypeRegistry::new(),
             feature_registry: FeatureRegistry::new(),
             config,
+            morphology: None,
+            tokenizer: Arc::new(SimpleTokenizer::default()),
             rules,
+            rule_attempts: Cell::new(0),
+            tracer: TraceHandle::default(),
         }
     }
     
@@ -98,6 +171,16 @@ impl CCGParser {
         parser
     }
     
+    /// Get the number of binary rule applications attempted since the parser was created
+    pub fn rule_attempts(&self) -> usize {
+        self.rule_attempts.get()
+    }
+    
+    /// Attach a tracer told about each lexical entry and rule application added to the chart
+    pub fn set_tracer(&mut self, tracer: Box<dyn Tracer>) {
+        self.tracer.set(tracer);
+    }
+    
     /// Register a new atomic type
     pub fn register_atomic_type(&mut self, type_name: &str) {
         self.atomic_types.register(type_name);
@@ -154,7 +237,7 @@ impl CCGParser {
                 }
                 
                 // Check if all features are valid
-                for (feature_name, _) in &features.features {
+                for feature_name in features.features.keys() {
                     if !self.feature_registry.is_feature_registered(feature_name) {
                         eprintln!("Unregistered feature: {}", feature_name);
                         return false;
@@ -163,36 +246,352 @@ impl CCGParser {
                 
                 true
             },
-            CCGCategory::Forward(left, right) => {
+            CCGCategory::Forward(left, right, _) => {
                 self.validate_category(left) && self.validate_category(right)
             },
-            CCGCategory::Backward(left, right) => {
+            CCGCategory::Backward(left, right, _) => {
                 self.validate_category(left) && self.validate_category(right)
             },
         }
     }
     
+    /// Create the leaf nodes for each token of a sentence from its lexical entries
+    ///
+    /// Multi-word entries of the lexicon form a single token, whose leaves
+    /// span all the words of the sentence it covers.
+    fn lexical_nodes(&self, sentence: &str) -> Result<Vec<Vec<CCGNode>>, Error> {
+        let tokens = self.lexicon.group_phrases_with_spans(&self.tokenizer.tokenize(sentence));
+        let mut lexical = Vec::with_capacity(tokens.len());
+        for (word, (start, end)) in &tokens {
+            let mut categories = self.lexicon.get_categories(word);
+            if categories.is_empty() {
+                categories = self.morphological_categories(word);
+            }
+            
+            if categories.is_empty() {
+                return Err(Error::UnknownWord { word: word.to_string(), position: *start });
+            }
+            
+            lexical.push(categories.into_iter()
+                .map(|category| CCGNode { start: *start, end: *end, ..CCGNode::leaf(word, category) })
+                .collect());
+        }
+        
+        Ok(lexical)
+    }
+    
+    /// Get categories for an unknown word from the lexical entries of its lemmas
+    ///
+    /// Inflectional features are added to the result category when feature
+    /// unification is in use; otherwise the lemma's categories are used as is.
+    fn morphological_categories(&self, word: &str) -> Vec<CCGCategory> {
+        let morphology = match &self.morphology {
+            Some(morphology) => morphology,
+            None => return Vec::new(),
+        };
+        let use_features = self.config.use_morphosyntax && self.config.enforce_feature_unification;
+        
+        let mut categories = Vec::new();
+        for (lemma, features) in morphology.analyze(word) {
+            for category in self.lexicon.get_categories(&lemma) {
+                let category = if use_features {
+                    match category.with_result_features(&features) {
+                        Some(category) => category,
+                        None => continue,
+                    }
+                } else {
+                    category
+                };
+                if !categories.contains(&category) {
+                    categories.push(category);
+                }
+            }
+        }
+        categories
+    }
+    
     /// Parse a sentence using the CKY algorithm with CCG combinatory rules
     fn parse_internal(&self, sentence: &str) -> Option<CCGNode> {
-        let words: Vec<&str> = sentence.split_whitespace().collect();
-        let n = words.len();
+        self.parse_result(sentence)
+            .map_err(|error| eprintln!("{}", error))
+            .ok()
+    }
+    
+    /// Parse a sentence, returning every complete parse in the chart
+    fn parse_all_internal(&self, sentence: &str) -> Vec<CCGNode> {
+        let lexical = match self.lexical_nodes(sentence) {
+            Ok(lexical) => lexical,
+            Err(_) => return Vec::new(),
+        };
         
-        // Initialize the chart for CKY parsing
-        let mut chart = vec![vec![vec![]; n + 1]; n + 1];
+        let n = lexical.len();
+        let chart = self.fill_chart(lexical);
+        chart[0][n].iter()
+            .filter(|node| matches!(&node.category, CCGCategory::Atomic(s, _) if s == "S"))
+            .cloned()
+            .collect()
+    }
+    
+    /// Parse tokens tagged with their candidate categories, bypassing the lexicon
+    ///
+    /// Each token seeds its chart cell with the supplied categories, e.g.
+    /// the output of an external supertagger. Categories with unregistered
+    /// atomic types or features are skipped with a warning; a token left
+    /// without categories fails the parse.
+    pub fn parse_tagged(&self, tokens: &[(&str, Vec<CCGCategory>)]) -> Option<CCGNode> {
+        if tokens.is_empty() {
+            return None;
+        }
         
-        // Fill in the lexical entries (diagonal)
-        for i in 0..n {
-            let word = words[i];
-            let categories = self.lexicon.get_categories(word);
+        let mut lexical = Vec::with_capacity(tokens.len());
+        for (position, (word, categories)) in tokens.iter().enumerate() {
+            let nodes: Vec<CCGNode> = categories.iter()
+                .filter(|category| {
+                    let valid = self.validate_category(category);
+                    if !valid {
+                        eprintln!("Warning: Supertag {} for '{}' contains unregistered atomic types.", category, word);
+                    }
+                    valid
+                })
+                .map(|category| CCGNode::leaf(word, category.clone()).at_position(position))
+                .collect();
             
-            if categories.is_empty() {
-                eprintln!("Unknown word: {}", word);
+            if nodes.is_empty() {
+                eprintln!("No supertags for: {}", word);
+                return None;
+            }
+            lexical.push(nodes);
+        }
+        
+        let chart = self.fill_chart(lexical);
+        Self::find_complete(&chart[0][tokens.len()]).cloned()
+    }
+    
+    /// Parse a sentence strictly left to right, returning the analyses of each prefix
+    ///
+    /// Each word is shifted onto a stack of partial constituents, and the
+    /// top two constituents may then be combined by application or
+    /// harmonic composition, and no other rule, as long as they can. Every way of reducing is kept, so
+    /// the state after each word holds all stacks reachable for its prefix.
+    /// There is one state per word or multi-word entry; an unknown word
+    /// gives no states.
+    pub fn parse_incremental(&self, sentence: &str) -> Vec<IncrementalState> {
+        let lexical = match self.lexical_nodes(sentence) {
+            Ok(lexical) => lexical,
+            Err(error) => {
+                eprintln!("{}", error);
+                return Vec::new();
+            },
+        };
+        
+        let mut states = Vec::with_capacity(lexical.len());
+        let mut stacks: Vec<Vec<CCGNode>> = vec![Vec::new()];
+        for nodes in lexical {
+            let position = nodes[0].end;
+            let mut pending: Vec<Vec<CCGNode>> = stacks.iter()
+                .flat_map(|stack| nodes.iter().map(move |node| {
+                    let mut shifted = stack.clone();
+                    shifted.push(node.clone());
+                    shifted
+                }))
+                .collect();
+            
+            let mut reachable: Vec<Vec<CCGNode>> = Vec::new();
+            while let Some(mut stack) = pending.pop() {
+                if reachable.contains(&stack) {
+                    continue;
+                }
+                
+                if stack.len() >= 2 {
+                    let right = stack.pop().unwrap();
+                    let left = stack.pop().unwrap();
+                    for node in self.reductions(&left, &right) {
+                        let mut reduced = stack.clone();
+                        reduced.push(node);
+                        pending.push(reduced);
+                    }
+                    stack.push(left);
+                    stack.push(right);
+                }
+                reachable.push(stack);
+            }
+            
+            stacks = reachable;
+            states.push(IncrementalState { position, stacks: stacks.clone() });
+        }
+        
+        states
+    }
+    
+    /// Combine two adjacent constituents by application or harmonic composition
+    fn reductions(&self, left: &CCGNode, right: &CCGNode) -> Vec<CCGNode> {
+        let rules: [&dyn CCGRule; 4] = [&ForwardApplication, &BackwardApplication, &ForwardComposition, &BackwardComposition];
+        rules.iter()
+            .filter_map(|rule| if self.unifies_features() {
+                rule.apply_with_registry(left, right, &self.feature_registry)
+            } else {
+                rule.apply(left, right, false)
+            })
+            .filter(|node| self.within_depth(&node.category))
+            .collect()
+    }
+    
+    /// Parse a sentence while searching jointly over supertag assignments
+    ///
+    /// Tag sequences are extended left to right with a beam of the given
+    /// width. Each entry is scored by the sum of log-probabilities assigned
+    /// by the tag model, less one for every constituent beyond the first
+    /// needed to cover its prefix in the chart, so that sequences the grammar
+    /// cannot combine give way to ones it can. The highest scoring sequence
+    /// that admits a complete parse is returned with its parse tree.
+    pub fn parse_joint(&self, words: &[&str], tag_model: &dyn SupertagModel, beam: usize) -> Option<(Vec<CCGCategory>, CCGNode)> {
+        if words.is_empty() || beam == 0 {
+            return None;
+        }
+        
+        let mut hypotheses: Vec<(Vec<CCGCategory>, f64, f64)> = vec![(Vec::new(), 0.0, 0.0)];
+        
+        for position in 0..words.len() {
+            let candidates = tag_model.candidates(words, position);
+            let mut extended = Vec::new();
+            
+            for (tags, score, _) in &hypotheses {
+                for (category, probability) in &candidates {
+                    if *probability <= 0.0 {
+                        continue;
+                    }
+                    
+                    let mut tags = tags.clone();
+                    tags.push(category.clone());
+                    let score = score + probability.ln();
+                    
+                    let chart = self.fill_chart(Self::tagged_leaves(words, &tags));
+                    let joint = score - (Self::fragments(&chart) - 1) as f64;
+                    extended.push((tags, score, joint));
+                }
+            }
+            
+            if extended.is_empty() {
+                eprintln!("No supertags for: {}", words[position]);
                 return None;
             }
             
-            for category in categories {
-                chart[i][i + 1].push(CCGNode::leaf(word, category));
+            extended.sort_by(|a, b| b.2.total_cmp(&a.2));
+            extended.truncate(beam);
+            hypotheses = extended;
+        }
+        
+        // Return the best-scoring tag sequence that yields a complete parse
+        for (tags, _, _) in hypotheses {
+            let chart = self.fill_chart(Self::tagged_leaves(words, &tags));
+            if let Some(node) = Self::find_complete(&chart[0][words.len()]) {
+                let node = node.clone();
+                return Some((tags, node));
+            }
+        }
+        
+        None
+    }
+    
+    /// Create the lexical nodes for a prefix of the words with one tag each
+    fn tagged_leaves(words: &[&str], tags: &[CCGCategory]) -> Vec<Vec<CCGNode>> {
+        words.iter()
+            .zip(tags)
+            .enumerate()
+            .map(|(position, (word, category))| vec![CCGNode::leaf(word, category.clone()).at_position(position)])
+            .collect()
+    }
+    
+    /// Get the fewest chart constituents that together cover the whole input
+    fn fragments(chart: &[Vec<Vec<CCGNode>>]) -> usize {
+        let n = chart.len() - 1;
+        let mut fewest = vec![usize::MAX; n + 1];
+        fewest[0] = 0;
+        
+        for end in 1..=n {
+            for start in 0..end {
+                if fewest[start] != usize::MAX && !chart[start][end].is_empty() {
+                    fewest[end] = fewest[end].min(fewest[start] + 1);
+                }
+            }
+        }
+        
+        fewest[n]
+    }
+    
+    /// Parse a sentence into a forest scored by a supertag model
+    ///
+    /// Every candidate category with a positive probability is tried, and
+    /// each lexical node scores the log-probability of its category, so
+    /// [`Forest::kbest`] returns the most probable parses first.
+    pub fn parse_scored_forest(&self, words: &[&str], tag_model: &dyn SupertagModel) -> Option<Forest<CCGNode>> {
+        if words.is_empty() {
+            return None;
+        }
+        
+        let candidates: Vec<Vec<(CCGCategory, f64)>> = (0..words.len())
+            .map(|position| tag_model.candidates(words, position).into_iter()
+                .filter(|(_, probability)| *probability > 0.0)
+                .collect())
+            .collect();
+        
+        let lexical = words.iter()
+            .zip(&candidates)
+            .enumerate()
+            .map(|(position, (word, tags))| tags.iter()
+                .map(|(category, _)| CCGNode::leaf(word, category.clone()).at_position(position))
+                .collect())
+            .collect();
+        
+        let chart = self.fill_chart(lexical);
+        let trees: Vec<CCGNode> = chart[0][words.len()].iter()
+            .filter(|node| matches!(&node.category, CCGCategory::Atomic(s, _) if s == "S"))
+            .cloned()
+            .collect();
+        if trees.is_empty() {
+            return None;
+        }
+        
+        Some(Forest::from_scored_trees(&trees, |node| {
+            if !node.children.is_empty() {
+                return 0.0;
+            }
+            candidates[node.start].iter()
+                .find(|(category, _)| *category == node.category)
+                .map_or(0.0, |(_, probability)| probability.ln())
+        }))
+    }
+    
+    /// Fill a CKY chart from the lexical nodes for each word
+    fn fill_chart(&self, lexical: Vec<Vec<CCGNode>>) -> Vec<Vec<Vec<CCGNode>>> {
+        self.fill_chart_within(lexical, usize::MAX).0
+    }
+    
+    /// Fill a CKY chart, combining at most `max_steps` pairs of cells
+    ///
+    /// Returns the chart and whether the budget ran out before it was full.
+    fn fill_chart_within(&self, lexical: Vec<Vec<CCGNode>>, max_steps: usize) -> (Vec<Vec<Vec<CCGNode>>>, bool) {
+        let n = lexical.len();
+        let mut steps = 0;
+        let lazy = self.config.enable_type_raising && self.config.type_raising_mode == TypeRaisingMode::Lazy;
+        
+        // The functors a type-raised category may take as its argument
+        let functors = (self.config.type_raising_mode == TypeRaisingMode::ArgumentOnly).then(|| {
+            let mut functors = Vec::new();
+            for node in lexical.iter().flatten() {
+                collect_functors(&node.category, &mut functors);
             }
+            functors
+        });
+        
+        // Initialize the chart for CKY parsing
+        let mut chart = vec![vec![vec![]; n + 1]; n + 1];
+        
+        // Fill in the lexical entries (diagonal)
+        for (i, nodes) in lexical.into_iter().enumerate() {
+            chart[i][i + 1] = nodes;
+            self.add_type_raised(&mut chart[i][i + 1], functors.as_deref());
+            self.trace_nodes(&chart[i][i + 1]);
         }
         
         // Fill in the chart using CCG combinatory rules
@@ -201,117 +600,298 @@ impl CCGParser {
                 let end = start + span;
                 
                 for split in (start + 1)..end {
+                    if steps == max_steps {
+                        return (chart, true);
+                    }
+                    steps += 1;
+                    
                     // For each pair of adjacent cells in the chart
-                    let mut new_nodes = Vec::new();
+                    let lefts = &chart[start][split];
+                    let rights = &chart[split][end];
+                    let mut new_nodes = self.combine_cells(lefts, rights);
                     
-                    for left in &chart[start][split] {
-                        for right in &chart[split][end] {
-                            // Apply all available rules
-                            for rule in &self.rules {
-                                if let Some(node) = rule.apply(
-                                    left, 
-                                    right, 
-                                    self.config.use_morphosyntax && self.config.enforce_feature_unification
-                                ) {
-                                    new_nodes.push(node);
-                                }
-                            }
-                            
-                            // Try generalized composition if needed
-                            if self.config.max_composition_order > 1 {
-                                if let Some(node) = self.compose_forward_generalized(
-                                    left, 
-                                    right, 
-                                    self.config.max_composition_order
-                                ) {
-                                    new_nodes.push(node);
-                                }
-                                
-                                if let Some(node) = self.compose_backward_generalized(
-                                    left,
-                                    right,
-                                    self.config.max_composition_order
-                                ) {
-                                    new_nodes.push(node);
-                                }
-                            }
-                        }
+                    // Raise a category only to combine it with its neighbour
+                    if lazy {
+                        let raised = self.type_raised(lefts);
+                        let raised_lefts: Vec<CCGNode> = raised.iter().filter(|node| node.rule.as_deref() == Some(">T")).cloned().collect();
+                        new_nodes.extend(self.combine_cells(&raised_lefts, rights));
+                        
+                        let raised = self.type_raised(rights);
+                        let raised_rights: Vec<CCGNode> = raised.iter().filter(|node| node.rule.as_deref() == Some("<T")).cloned().collect();
+                        new_nodes.extend(self.combine_cells(lefts, &raised_rights));
                     }
                     
                     chart[start][end].extend(new_nodes);
                 }
+                
+                if self.config.enable_gapping {
+                    let gapped = self.gapped_coordinations(&chart, start, end);
+                    chart[start][end].extend(gapped);
+                }
+                
+                self.add_type_raised(&mut chart[start][end], functors.as_deref());
+                self.trace_nodes(&chart[start][end]);
             }
         }
         
-        // Find a complete parse (category S spanning the whole sentence)
-        for node in &chart[0][n] {
-            if let CCGCategory::Atomic(s, _) = &node.category {
-                if s == "S" {
-                    return Some(node.clone());
+        (chart, false)
+    }
+    
+    /// Report the nodes of a chart cell to the tracer
+    fn trace_nodes(&self, nodes: &[CCGNode]) {
+        for node in nodes {
+            match &node.word {
+                Some(word) if node.children.is_empty() => {
+                    self.tracer.step(StepKind::Lexical, || format!("{} := {}", word, node.category));
+                },
+                _ => self.tracer.step(StepKind::Rule, || {
+                    let children: Vec<String> = node.children.iter().map(|child| child.category.to_string()).collect();
+                    format!("{} => {} ({}) over {}..{}", children.join(" + "), node.category, node.rule.as_deref().unwrap_or("?"), node.start, node.end)
+                }),
+            }
+        }
+    }
+    
+    /// Check whether categories are matched by unification rather than equality
+    fn unifies_features(&self) -> bool {
+        (self.config.use_morphosyntax && self.config.enforce_feature_unification)
+            || self.config.underspecified_atomic_unify
+    }
+    
+    /// Apply the binary rules to every pair of nodes from two adjacent cells
+    fn combine_cells(&self, lefts: &[CCGNode], rights: &[CCGNode]) -> Vec<CCGNode> {
+        let use_features = self.unifies_features();
+        let admissible = |node: &CCGNode| {
+            (!self.config.normal_form_only || is_normal_form(node)) && self.within_depth(&node.category)
+        };
+        let index = self.config.prefilter_combinations.then(|| AdjacencyIndex::new(rights));
+        let mut new_nodes = Vec::new();
+        
+        for left in lefts {
+            let candidates = match &index {
+                Some(index) => index.candidates(left),
+                None => (0..rights.len()).collect(),
+            };
+            
+            for right in candidates.into_iter().map(|i| &rights[i]) {
+                // Apply all available binary rules
+                for rule in self.rules.iter().filter(|rule| rule.arity() == 2) {
+                    self.rule_attempts.set(self.rule_attempts.get() + 1);
+                    let node = if use_features {
+                        rule.apply_with_registry(left, right, &self.feature_registry)
+                    } else {
+                        rule.apply(left, right, false)
+                    };
+                    if let Some(node) = node.filter(admissible) {
+                        new_nodes.push(node);
+                    }
+                }
+                
+                // Try generalized composition if needed
+                if self.config.max_composition_order > 1 {
+                    self.rule_attempts.set(self.rule_attempts.get() + 2);
+                    if let Some(node) = self.compose_forward_generalized(
+                        left, 
+                        right, 
+                        self.config.max_composition_order
+                    ).filter(admissible) {
+                        new_nodes.push(node);
+                    }
+                    
+                    if let Some(node) = self.compose_backward_generalized(
+                        left,
+                        right,
+                        self.config.max_composition_order
+                    ).filter(admissible) {
+                        new_nodes.push(node);
+                    }
                 }
             }
         }
         
-        // No complete parse found
-        eprintln!("No complete parse found for: {}", sentence);
-        if !chart[0][n].is_empty() {
-            eprintln!("Partial parses:");
-            for (i, node) in chart[0][n].iter().enumerate() {
-                eprintln!("Parse {}: {}", i + 1, node.category);
+        new_nodes
+    }
+    
+    /// Build gapped coordinations spanning `start..end`
+    ///
+    /// The left conjunct is decomposed into one of its lexical functors and
+    /// the remaining arguments. An empty node of the functor's category fills
+    /// the same offset in the right conjunct, which must then derive the same
+    /// category as the left conjunct ("John likes tea and Mary [likes] coffee").
+    fn gapped_coordinations(&self, chart: &[Vec<Vec<CCGNode>>], start: usize, end: usize) -> Vec<CCGNode> {
+        let mut results = Vec::new();
+        
+        for conj_pos in (start + 1)..end.saturating_sub(1) {
+            let conj_nodes: Vec<&CCGNode> = chart[conj_pos][conj_pos + 1].iter()
+                .filter(|node| node.word.is_some() && node.category == CCGCategory::conj())
+                .collect();
+            
+            for conj in conj_nodes {
+                for left in chart[start][conj_pos].iter().filter(|node| matches!(node.category, CCGCategory::Atomic(..))) {
+                    let mut leaves = Vec::new();
+                    collect_leaves(left, &mut leaves);
+                    
+                    for (offset, functor) in leaves.iter().enumerate() {
+                        if matches!(functor.category, CCGCategory::Atomic(..)) || offset > end - conj_pos - 1 {
+                            continue;
+                        }
+                        
+                        // The right conjunct's words with a gap for the functor
+                        let mut lexical: Vec<Vec<CCGNode>> = ((conj_pos + 1)..end)
+                            .map(|i| chart[i][i + 1].iter().filter(|node| node.word.is_some()).cloned().collect())
+                            .collect();
+                        let site = match lexical.get(offset) {
+                            Some(nodes) => nodes.first().map(|node| node.start),
+                            None => lexical.last().and_then(|nodes| nodes.first()).map(|node| node.end),
+                        };
+                        lexical.insert(offset, vec![CCGNode::gap(functor.category.clone(), site.unwrap_or_default())]);
+                        
+                        let right_chart = self.fill_chart(lexical);
+                        let size = right_chart.len() - 1;
+                        
+                        if let Some(right) = right_chart[0][size].iter().find(|node| node.category == left.category) {
+                            results.push(CCGNode::internal(
+                                left.category.clone(),
+                                vec![left.clone(), conj.clone(), right.clone()],
+                                "<Φgap>",
+                            ));
+                        }
+                    }
+                }
             }
         }
         
-        None
+        results
+    }
+    
+    /// Add the type-raised versions of the nodes in a chart cell
+    ///
+    /// Type-raising is unary, so it applies to the nodes of a single span
+    /// rather than to pairs of adjacent spans. Given the functors of the
+    /// sentence, a raised category is only kept if its argument is one of them.
+    fn add_type_raised(&self, cell: &mut Vec<CCGNode>, functors: Option<&[CCGCategory]>) {
+        if !self.config.enable_type_raising || self.config.type_raising_mode == TypeRaisingMode::Lazy {
+            return;
+        }
+        
+        let raised = self.type_raised(cell).into_iter().filter(|node| match (functors, &node.category) {
+            (Some(functors), CCGCategory::Forward(_, argument, _) | CCGCategory::Backward(_, argument, _)) => {
+                functors.iter().any(|functor| functor.unify(argument).is_some())
+            },
+            _ => true,
+        });
+        
+        cell.extend(raised.collect::<Vec<_>>());
+    }
+    
+    /// Get the type-raised versions of a set of nodes
+    fn type_raised(&self, nodes: &[CCGNode]) -> Vec<CCGNode> {
+        let mut raised = Vec::new();
+        for node in nodes {
+            // Do not raise an already raised category
+            if matches!(node.rule.as_deref(), Some(">T") | Some("<T")) {
+                continue;
+            }
+            
+            for rule in self.rules.iter().filter(|rule| rule.arity() == 1) {
+                let raised_node = if self.unifies_features() {
+                    rule.apply_with_registry(node, node, &self.feature_registry)
+                } else {
+                    rule.apply(node, node, false)
+                };
+                if let Some(raised_node) = raised_node.filter(|raised| self.within_depth(&raised.category)) {
+                    raised.push(raised_node);
+                }
+            }
+        }
+        raised
+    }
+    
+    /// Check that a derived category is no deeper than the configured bound
+    fn within_depth(&self, category: &CCGCategory) -> bool {
+        self.config.max_category_depth.is_none_or(|max| category.depth() <= max)
+    }
+    
+    /// Find a node with the atomic category S among the nodes of a cell
+    fn find_complete(cell: &[CCGNode]) -> Option<&CCGNode> {
+        cell.iter().find(|node| matches!(&node.category, CCGCategory::Atomic(s, _) if s == "S"))
     }
     
     /// Forward generalized composition (order n): X/Y Y... => X...
-    /// Only the first slash needs to match (Y argument type)
+    /// Only the first slash needs to match (Y argument type). The composition
+    /// is crossed if the slash next to Y is backward, and both that slash and
+    /// the functor's must permit the kind of composition.
     fn compose_forward_generalized(&self, left: &CCGNode, right: &CCGNode, max_order: usize) -> Option<CCGNode> {
         // Basic check for forward slash in the left category
-        if let CCGCategory::Forward(x, y) = &left.category {
-            let _matches = if self.config.use_morphosyntax && self.config.enforce_feature_unification {
+        if let CCGCategory::Forward(x, y, modality) = &left.category {
+            let _matches = if self.unifies_features() {
                 // Try to unify the argument category with the right-hand category's main type
                 match &right.category {
-                    CCGCategory::Forward(right_result, _) => y.unify(right_result).is_some(),
-                    CCGCategory::Backward(right_result, _) => y.unify(right_result).is_some(),
+                    CCGCategory::Forward(right_result, _, _) => y.unify(right_result).is_some(),
+                    CCGCategory::Backward(right_result, _, _) => y.unify(right_result).is_some(),
                     _ => y.unify(&right.category).is_some(),
                 }
             } else {
                 // Simple equality check
                 match &right.category {
-                    CCGCategory::Forward(right_result, _) => **y == **right_result,
-                    CCGCategory::Backward(right_result, _) => **y == **right_result,
+                    CCGCategory::Forward(right_result, _, _) => **y == **right_result,
+                    CCGCategory::Backward(right_result, _, _) => **y == **right_result,
                     _ => **y == right.category,
                 }
             };
             
             // Only try higher-order composition (we already have first-order via rules)
             if max_order > 1 {
-                // Extract the functor chain from the right category
-                if let Some((right_base, right_args)) = extract_category_chain(&right.category, 0, max_order) {
-                    // Check if y matches the base result of the right category
-                    let base_matches = if self.config.use_morphosyntax && self.config.enforce_feature_unification {
-                        y.unify(&right_base).is_some()
+                let use_features = self.unifies_features();
+                
+                // Peel arguments off the right category, outermost first, until
+                // what remains matches Y
+                let mut right_base = &right.category;
+                let mut right_args = Vec::new();
+                while right_args.len() < max_order {
+                    let (res, is_forward, arg, arg_modality) = match right_base {
+                        CCGCategory::Forward(res, arg, arg_modality) => (res, true, arg, *arg_modality),
+                        CCGCategory::Backward(res, arg, arg_modality) => (res, false, arg, *arg_modality),
+                        CCGCategory::Atomic(..) => break,
+                    };
+                    right_args.push((is_forward, (**arg).clone(), arg_modality));
+                    right_base = res;
+                    
+                    if right_args.len() < 2 {
+                        continue;
+                    }
+                    
+                    // Check if y matches the base result of the right category,
+                    // keeping any feature variables bound in doing so
+                    let mut bindings = Bindings::with_registry(&self.feature_registry);
+                    let base_matches = if use_features {
+                        y.unify_with_bindings(right_base, &mut bindings).is_some()
                     } else {
-                        **y == right_base
+                        **y == *right_base
                     };
                     
-                    if base_matches && right_args.len() > 1 {
+                    let (inner_forward, _, inner_modality) = right_args[right_args.len() - 1];
+                    let crossed = !inner_forward;
+                    if !modality.permits_composition(crossed) || !inner_modality.permits_composition(crossed) {
+                        continue;
+                    }
+                    
+                    if base_matches {
                         // Construct the result category by combining X with all arguments from right
                         let mut result = (**x).clone();
                         
                         // Build the category by applying arguments in reverse order 
                         // (deepest arguments first)
-                        for (is_forward, arg) in right_args.iter().rev() {
+                        for (is_forward, arg, arg_modality) in right_args.iter().rev() {
                             if *is_forward {
-                                result = CCGCategory::forward(result, arg.clone());
+                                result = CCGCategory::forward_with_modality(result, arg.clone(), *arg_modality);
                             } else {
-                                result = CCGCategory::backward(result, arg.clone());
+                                result = CCGCategory::backward_with_modality(result, arg.clone(), *arg_modality);
                             }
                         }
                         
                         return Some(CCGNode::internal(
-                            result,
+                            result.apply_bindings(&bindings),
                             vec![left.clone(), right.clone()],
                             &format!(">B{}", right_args.len()), // Order is number of args
                         ));
@@ -324,22 +904,23 @@ impl CCGParser {
     }
     
     /// Backward generalized composition (order n): Y... X\Y => X...
-    /// Only the first slash needs to match (Y argument type)
+    /// Only the first slash needs to match (Y argument type). The functor's
+    /// slash must permit composition, crossed if any argument is forward.
     fn compose_backward_generalized(&self, left: &CCGNode, right: &CCGNode, max_order: usize) -> Option<CCGNode> {
         // Basic check for backward slash in the right category
-        if let CCGCategory::Backward(x, y) = &right.category {
-            let _matches = if self.config.use_morphosyntax && self.config.enforce_feature_unification {
+        if let CCGCategory::Backward(x, y, modality) = &right.category {
+            let _matches = if self.unifies_features() {
                 // Try to unify the argument category with the left-hand category's main type
                 match &left.category {
-                    CCGCategory::Forward(left_result, _) => y.unify(left_result).is_some(),
-                    CCGCategory::Backward(left_result, _) => y.unify(left_result).is_some(),
+                    CCGCategory::Forward(left_result, _, _) => y.unify(left_result).is_some(),
+                    CCGCategory::Backward(left_result, _, _) => y.unify(left_result).is_some(),
                     _ => y.unify(&left.category).is_some(),
                 }
             } else {
                 // Simple equality check
                 match &left.category {
-                    CCGCategory::Forward(left_result, _) => **y == **left_result,
-                    CCGCategory::Backward(left_result, _) => **y == **left_result,
+                    CCGCategory::Forward(left_result, _, _) => **y == **left_result,
+                    CCGCategory::Backward(left_result, _, _) => **y == **left_result,
                     _ => **y == left.category,
                 }
             };
@@ -349,23 +930,27 @@ impl CCGParser {
                 // Extract the functor chain from the left category
                 if let Some((left_base, left_args)) = extract_category_chain(&left.category, 0, max_order) {
                     // Check if y matches the base result of the left category
-                    let base_matches = if self.config.use_morphosyntax && self.config.enforce_feature_unification {
-                        y.unify(&left_base).is_some()
+                    let base_matches = if self.unifies_features() {
+                        y.unify_with_bindings(&left_base, &mut Bindings::with_registry(&self.feature_registry)).is_some()
                     } else {
                         **y == left_base
                     };
                     
-                    if base_matches && left_args.len() > 1 {
+                    let crossed = left_args.iter().any(|(is_forward, _, _)| *is_forward);
+                    let permitted = modality.permits_composition(crossed)
+                        && left_args.iter().all(|(_, _, arg_modality)| arg_modality.permits_composition(crossed));
+                    
+                    if base_matches && permitted && left_args.len() > 1 {
                         // Construct the result category by combining X with all arguments from left
                         let mut result = (**x).clone();
                         
                         // Build the category by applying arguments in reverse order
                         // (deepest arguments first)
-                        for (is_forward, arg) in left_args.iter().rev() {
+                        for (is_forward, arg, arg_modality) in left_args.iter().rev() {
                             if *is_forward {
-                                result = CCGCategory::forward(result, arg.clone());
+                                result = CCGCategory::forward_with_modality(result, arg.clone(), *arg_modality);
                             } else {
-                                result = CCGCategory::backward(result, arg.clone());
+                                result = CCGCategory::backward_with_modality(result, arg.clone(), *arg_modality);
                             }
                         }
                         
@@ -383,6 +968,109 @@ impl CCGParser {
     }
 }
 
+/// An index of the categories in a chart cell by the atoms their neighbours must match
+///
+/// Every binary rule is an application or composition, which matches the
+/// argument of one category against the result of the other. Those can only
+/// unify if they end in the same result atom, so the left neighbour `X/Y`
+/// needs a category ending in `Y`'s atom and a right neighbour `X\Y` needs a
+/// left category ending in `Y`'s atom.
+struct AdjacencyIndex<'a> {
+    /// Positions of the cell's categories by result atom
+    by_result: HashMap<&'a str, Vec<usize>>,
+    /// Positions of the cell's backward functors by argument atom
+    by_backward_arg: HashMap<&'a str, Vec<usize>>,
+}
+
+impl<'a> AdjacencyIndex<'a> {
+    /// Index the categories of the right-hand cell
+    fn new(rights: &'a [CCGNode]) -> Self {
+        let mut by_result: HashMap<&str, Vec<usize>> = HashMap::new();
+        let mut by_backward_arg: HashMap<&str, Vec<usize>> = HashMap::new();
+        
+        for (i, node) in rights.iter().enumerate() {
+            by_result.entry(node.category.result_atom()).or_default().push(i);
+            if let CCGCategory::Backward(..) = node.category {
+                if let Some(atom) = node.category.arg_atom() {
+                    by_backward_arg.entry(atom).or_default().push(i);
+                }
+            }
+        }
+        
+        AdjacencyIndex { by_result, by_backward_arg }
+    }
+    
+    /// Get the positions of the right-hand categories that may combine with `left`, in order
+    fn candidates(&self, left: &CCGNode) -> Vec<usize> {
+        let mut candidates = Vec::new();
+        
+        if let CCGCategory::Forward(..) = left.category {
+            if let Some(found) = left.category.arg_atom().and_then(|atom| self.by_result.get(atom)) {
+                candidates.extend(found);
+            }
+        }
+        if let Some(found) = self.by_backward_arg.get(left.category.result_atom()) {
+            candidates.extend(found);
+        }
+        
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates
+    }
+}
+
+/// Check the Eisner normal-form constraints on the top step of a derivation
+///
+/// The result of forward composition may not be the primary functor of a
+/// forward application or composition, and likewise for backward rules. A
+/// type-raised category may not be the functor of an application in its own
+/// direction, since applying the argument directly gives the same result.
+fn is_normal_form(node: &CCGNode) -> bool {
+    let rule = match node.rule.as_deref() {
+        Some(rule) => rule,
+        None => return true,
+    };
+    let child_rule = |index: usize| node.children.get(index).and_then(|child| child.rule.as_deref()).unwrap_or("");
+    
+    if rule == ">" || rule.starts_with(">B") {
+        let functor = child_rule(0);
+        !(functor.starts_with(">B") || (rule == ">" && functor == ">T"))
+    } else if rule == "<" || rule.starts_with("<B") {
+        let functor = child_rule(1);
+        !(functor.starts_with("<B") || (rule == "<" && functor == "<T"))
+    } else {
+        true
+    }
+}
+
+/// Collect the functor categories occurring within a category, including itself
+fn collect_functors(category: &CCGCategory, functors: &mut Vec<CCGCategory>) {
+    if let CCGCategory::Forward(result, argument, _) | CCGCategory::Backward(result, argument, _) = category {
+        if !functors.contains(category) {
+            functors.push(category.clone());
+        }
+        collect_functors(result, functors);
+        collect_functors(argument, functors);
+    }
+}
+
+/// Collect the lexical leaves of a derivation in surface order
+fn collect_leaves<'a>(node: &'a CCGNode, leaves: &mut Vec<&'a CCGNode>) {
+    if node.word.is_some() {
+        leaves.push(node);
+    }
+    
+    for child in &node.children {
+        collect_leaves(child, leaves);
+    }
+}
+
+impl Default for CCGParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Parser for CCGParser {
     type Cat = CCGCategory;
     type Node = CCGNode;
@@ -418,6 +1106,48 @@ impl Parser for CCGParser {
         self.parse_internal(sentence)
     }
     
+    fn parse_result(&self, sentence: &str) -> Result<Self::Node, Error> {
+        let lexical = self.lexical_nodes(sentence)?;
+        let n = lexical.len();
+        
+        let chart = self.fill_chart(lexical);
+        
+        // Find a complete parse (category S spanning the whole sentence)
+        Self::find_complete(&chart[0][n])
+            .cloned()
+            .ok_or_else(|| Error::NoParse { sentence: sentence.to_string() })
+    }
+    
+    /// Parse a sentence, combining at most `budget` pairs of chart cells
+    ///
+    /// When the budget runs out, the best partial parse is the first node of
+    /// the widest span filled so far.
+    fn parse_within(&self, sentence: &str, budget: StepBudget) -> ParseOutcome<Self::Node> {
+        let lexical = match self.lexical_nodes(sentence) {
+            Ok(lexical) => lexical,
+            Err(_) => return ParseOutcome::Exhausted,
+        };
+        let n = lexical.len();
+        
+        let (chart, exceeded) = self.fill_chart_within(lexical, budget.max_steps);
+        if exceeded {
+            let best_partial = (1..=n).rev()
+                .flat_map(|span| (0..=(n - span)).map(move |start| (start, start + span)))
+                .find_map(|(start, end)| chart[start][end].first())
+                .cloned();
+            return ParseOutcome::BudgetExceeded { best_partial };
+        }
+        
+        match Self::find_complete(&chart[0][n]) {
+            Some(node) => ParseOutcome::Complete(node.clone()),
+            None => ParseOutcome::Exhausted,
+        }
+    }
+    
+    fn parse_all(&self, sentence: &str) -> Vec<Self::Node> {
+        self.parse_all_internal(sentence)
+    }
+    
     /// Add a word with a category to the lexicon
     fn add_to_lexicon(&mut self, word: &str, category: Self::Cat) {
         // Validate that all atomic types used in the category are registered
@@ -428,6 +1158,11 @@ impl Parser for CCGParser {
         }
     }
     
+    /// Get the lexicon of this parser
+    fn lexicon(&self) -> Option<&Lexicon<Self::Cat>> {
+        Some(&self.lexicon)
+    }
+    
     /// Get the configuration of this parser
     fn config(&self) -> &Self::Config {
         &self.config
@@ -451,6 +1186,7 @@ impl Parser for CCGParser {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::{FeatureValue, Step, ToConll, VecTracer};
     
     // Helper function to set up a basic English parser for testing
     fn setup_test_parser() -> CCGParser {
@@ -583,4 +1319,659 @@ mod tests {
         let result = parser.parse("the cat will sleep");
         assert!(result.is_some());
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_joint_supertagging() {
+        use std::collections::HashMap;
+        
+        let parser = setup_test_parser();
+        let np = CCGCategory::np();
+        let n = CCGCategory::n();
+        let iv = CCGCategory::backward(CCGCategory::s(), np.clone());
+        
+        // The locally best tag for "runs" is a noun, which blocks the parse
+        let mut model: HashMap<String, Vec<(CCGCategory, f64)>> = HashMap::new();
+        model.insert("the".to_string(), vec![(CCGCategory::forward(np.clone(), n.clone()), 1.0)]);
+        model.insert("dog".to_string(), vec![(n.clone(), 0.9), (iv.clone(), 0.1)]);
+        model.insert("runs".to_string(), vec![(n.clone(), 0.7), (iv.clone(), 0.3)]);
+        
+        let (tags, tree) = parser.parse_joint(&["the", "dog", "runs"], &model, 4).unwrap();
+        assert_eq!(tags, vec![CCGCategory::forward(np, n.clone()), n, iv.clone()]);
+        assert_eq!(tree.category, CCGCategory::s());
+        
+        // The chart demotes the locally best sequence, so a beam of one suffices
+        let (tags, _) = parser.parse_joint(&["the", "dog", "runs"], &model, 1).unwrap();
+        assert_eq!(tags[2], iv);
+    }
+    
+    #[test]
+    fn test_gapping() {
+        let mut parser = setup_test_parser();
+        parser.register_atomic_type("CONJ");
+        
+        let mut config = parser.config.clone();
+        config.enable_gapping = true;
+        parser.set_config(config);
+        
+        let s = CCGCategory::s();
+        let np = CCGCategory::np();
+        let tv = CCGCategory::forward(CCGCategory::backward(s.clone(), np.clone()), np.clone());
+        
+        parser.add_to_lexicon("John", np.clone());
+        parser.add_to_lexicon("Mary", np.clone());
+        parser.add_to_lexicon("tea", np.clone());
+        parser.add_to_lexicon("coffee", np.clone());
+        parser.add_to_lexicon("likes", tv.clone());
+        parser.add_to_lexicon("and", CCGCategory::conj());
+        
+        let result = parser.parse("John likes tea and Mary coffee").unwrap();
+        assert_eq!(result.rule.as_deref(), Some("<Φgap>"));
+        
+        // Only the left conjunct pronounces the verb; the right one has a gap
+        let (left, right) = (&result.children[0], &result.children[2]);
+        assert_eq!((left.category.clone(), right.category.clone()), (s.clone(), s.clone()));
+        assert_eq!((result.span(), left.span(), right.span()), ((0, 6), (0, 3), (4, 6)));
+        
+        let mut leaves = Vec::new();
+        collect_leaves(right, &mut leaves);
+        let words: Vec<_> = leaves.iter().filter_map(|leaf| leaf.word.as_deref()).collect();
+        assert_eq!(words, vec!["Mary", "coffee"]);
+        
+        fn has_gap(node: &CCGNode, category: &CCGCategory) -> bool {
+            (node.word.is_none() && node.children.is_empty() && &node.category == category)
+                || node.children.iter().any(|child| has_gap(child, category))
+        }
+        assert!(has_gap(right, &tv));
+        assert!(!has_gap(left, &tv));
+        
+        // Without gapping the right conjunct has no verb to combine with
+        let mut config = parser.config.clone();
+        config.enable_gapping = false;
+        parser.set_config(config);
+        assert!(parser.parse("John likes tea and Mary coffee").is_none());
+    }
+    
+    #[test]
+    fn test_parse_forest_pp_attachment() {
+        let mut parser = setup_test_parser();
+        let mut config = parser.config.clone();
+        config.enable_type_raising = false;
+        parser.set_config(config);
+        
+        let s = CCGCategory::s();
+        let np = CCGCategory::np();
+        let vp = CCGCategory::backward(s.clone(), np.clone());
+        let vp_mod = CCGCategory::backward(vp.clone(), vp.clone());
+        let np_mod = CCGCategory::backward(np.clone(), np.clone());
+        
+        parser.add_to_lexicon("John", np.clone());
+        parser.add_to_lexicon("saw", CCGCategory::forward(vp, np.clone()));
+        parser.add_to_lexicon("man", CCGCategory::n());
+        parser.add_to_lexicon("telescope", CCGCategory::n());
+        parser.add_to_lexicon("with", CCGCategory::forward(vp_mod.clone(), np.clone()));
+        parser.add_to_lexicon("with", CCGCategory::forward(np_mod.clone(), np.clone()));
+        
+        let forest = parser.parse_forest("John saw the man with the telescope").unwrap();
+        
+        // Both attachments of the PP are packed into the same forest ...
+        let vp_attached = forest.find(&vp_mod.to_string(), 4, 7).unwrap();
+        let np_attached = forest.find(&np_mod.to_string(), 4, 7).unwrap();
+        assert!(forest.count_trees() >= 2);
+        
+        // ... and share the subforest for their object
+        let object = forest.find(&np.to_string(), 5, 7).unwrap();
+        for pp in [vp_attached, np_attached] {
+            assert!(forest.node(pp).derivations.iter().any(|derivation| derivation.children.contains(&object)));
+        }
+        assert_eq!(forest.nodes().iter().filter(|node| node.start == 5 && node.end == 7 && node.label == np.to_string()).count(), 1);
+        
+        assert!(parser.parse_forest("John saw the unicorn").is_none());
+    }
+    
+    #[test]
+    fn test_kbest_forest() {
+        let mut parser = setup_test_parser();
+        let mut config = parser.config.clone();
+        config.enable_type_raising = false;
+        config.normal_form_only = true;
+        parser.set_config(config);
+        
+        let s = CCGCategory::s();
+        let np = CCGCategory::np();
+        let n = CCGCategory::n();
+        let vp = CCGCategory::backward(s.clone(), np.clone());
+        let vp_mod = CCGCategory::backward(vp.clone(), vp.clone());
+        let np_mod = CCGCategory::backward(np.clone(), np.clone());
+        
+        let mut model: HashMap<String, Vec<(CCGCategory, f64)>> = HashMap::new();
+        model.insert("John".to_string(), vec![(np.clone(), 1.0)]);
+        model.insert("saw".to_string(), vec![(CCGCategory::forward(vp, np.clone()), 1.0)]);
+        model.insert("the".to_string(), vec![(CCGCategory::forward(np.clone(), n.clone()), 1.0)]);
+        model.insert("man".to_string(), vec![(n.clone(), 1.0)]);
+        model.insert("telescope".to_string(), vec![(n, 1.0)]);
+        model.insert("with".to_string(), vec![
+            (CCGCategory::forward(vp_mod.clone(), np.clone()), 0.6),
+            (CCGCategory::forward(np_mod.clone(), np.clone()), 0.4),
+        ]);
+        
+        let words = ["John", "saw", "the", "man", "with", "the", "telescope"];
+        let forest = parser.parse_scored_forest(&words, &model).unwrap();
+        let best = forest.kbest(2);
+        assert_eq!(best.len(), 2);
+        
+        // Verb phrase attachment is more probable than noun phrase attachment
+        let attachment = |tree: &CCGNode| tree.children[1].children[1].category.clone();
+        assert_eq!(attachment(&best[0].0), vp_mod);
+        assert!((best[0].1 - 0.6f64.ln()).abs() < 1e-9);
+        assert!((best[1].1 - 0.4f64.ln()).abs() < 1e-9);
+        assert_eq!(attachment(&best[1].0), np);
+        
+        // There are only two trees, and uniform weights tie them
+        assert_eq!(forest.kbest(5).len(), 2);
+        let trees: Vec<CCGNode> = best.into_iter().map(|(tree, _)| tree).collect();
+        let uniform = Forest::from_trees(&trees).kbest(5);
+        assert_eq!(uniform.len(), 2);
+        assert!(uniform.iter().all(|(_, score)| *score == 0.0));
+        assert!(parser.parse_scored_forest(&["telescope"], &model).is_none());
+    }
+    
+    #[test]
+    fn test_morphological_fallback() {
+        let mut parser = setup_test_parser();
+        assert!(parser.parse("the cats sleeps").is_none());
+        
+        parser.morphology = Some(Box::new(crate::common::EnglishSuffixAnalyzer::new()));
+        assert!(parser.parse("the cats sleeps").is_some());
+        
+        // With feature unification the inflection is visible on the category
+        let mut config = parser.config.clone();
+        config.use_morphosyntax = true;
+        config.enforce_feature_unification = true;
+        parser.set_config(config);
+        
+        let result = parser.parse("the cats sleeps").unwrap();
+        let mut leaves = Vec::new();
+        collect_leaves(&result, &mut leaves);
+        let cats = leaves.iter().find(|leaf| leaf.word.as_deref() == Some("cats")).unwrap();
+        assert_eq!(cats.category.get_features().and_then(|fs| fs.get("num")), Some(&FeatureValue::Atomic("pl".to_string())));
+    }
+    
+    #[test]
+    fn test_underspecified_feature_values() {
+        let mut parser = setup_test_parser();
+        let mut config = parser.config.clone();
+        config.use_morphosyntax = true;
+        config.enforce_feature_unification = true;
+        parser.set_config(config);
+        
+        parser.register_feature_dimension("num", &["sg", "pl"]);
+        parser.feature_registry.register_subsumption("num", "any", "sg");
+        parser.feature_registry.register_subsumption("num", "any", "pl");
+        
+        let np = CCGCategory::np();
+        let n_sg = parser.create_category_with_features("N", &[("num", "sg")]).unwrap();
+        let n_pl = parser.create_category_with_features("N", &[("num", "pl")]).unwrap();
+        let n_any = parser.create_category_with_features("N", &[("num", "any")]).unwrap();
+        
+        parser.add_to_lexicon("this", CCGCategory::forward(np.clone(), n_sg));
+        parser.add_to_lexicon("these", CCGCategory::forward(np.clone(), n_pl.clone()));
+        parser.add_to_lexicon("cats", n_pl);
+        parser.add_to_lexicon("sheep", n_any);
+        
+        // Number is underspecified for "sheep", so either determiner fits
+        assert!(parser.parse("this sheep sleeps").is_some());
+        assert!(parser.parse("these sheep sleeps").is_some());
+        assert!(parser.parse("these cats sleeps").is_some());
+        assert!(parser.parse("this cats sleeps").is_none());
+    }
+    
+    #[test]
+    fn test_tokenization() {
+        let mut parser = setup_test_parser();
+        parser.add_to_lexicon(".", CCGCategory::backward(CCGCategory::s(), CCGCategory::s()));
+        
+        // Punctuation is split off before lexical lookup
+        assert!(parser.parse("the cat sleeps.").is_some());
+        assert!(parser.parse("The cat sleeps.").is_none());
+        
+        parser.tokenizer = Arc::new(SimpleTokenizer::new().with_lowercase(true));
+        assert!(parser.parse("The cat sleeps.").is_some());
+    }
+    
+    #[test]
+    fn test_lexicon_phrases() {
+        let mut parser = setup_test_parser();
+        parser.add_to_lexicon("New York", CCGCategory::np());
+        
+        // The two words are looked up as one lexical item
+        let parse = parser.parse("New York sleeps").unwrap();
+        assert_eq!(parse.children[0].word.as_deref(), Some("New York"));
+        assert_eq!(parse.children[0].span(), (0, 2));
+        assert_eq!(parse.span(), (0, 3));
+        
+        // Positions count the words of the sentence
+        assert!(matches!(
+            parser.parse_result("New York sleeps soundly"),
+            Err(Error::UnknownWord { position: 3, .. })
+        ));
+        
+        assert!(parser.parse("The cat sleeps").is_none());
+        parser.lexicon.set_case_folding(true);
+        assert!(parser.parse("The cat sleeps").is_some());
+    }
+    
+    #[test]
+    fn test_parse_result() {
+        let parser = setup_test_parser();
+        
+        assert!(parser.parse_result("the cat sleeps").is_ok());
+        assert!(matches!(
+            parser.parse_result("the unicorn sleeps"),
+            Err(Error::UnknownWord { word, position: 1 }) if word == "unicorn"
+        ));
+        assert!(matches!(
+            parser.parse_result("cat the sleeps"),
+            Err(Error::NoParse { sentence }) if sentence == "cat the sleeps"
+        ));
+    }
+    
+    #[test]
+    fn test_lexicon_enumeration() {
+        let parser = setup_test_parser();
+        
+        assert_eq!(parser.lexicon_words(), vec!["a", "cat", "chases", "dog", "runs", "sees", "sleeps", "the"]);
+        assert_eq!(parser.lexicon_entries("the"), vec![CCGCategory::forward(CCGCategory::np(), CCGCategory::atomic("N"))]);
+        assert!(parser.lexicon_entries("unicorn").is_empty());
+    }
+    
+    /// The test parser with subject-verb-object entries for the transitive verbs
+    fn setup_svo_parser() -> CCGParser {
+        let mut parser = setup_test_parser();
+        let tv = CCGCategory::forward(CCGCategory::backward(CCGCategory::s(), CCGCategory::np()), CCGCategory::np());
+        parser.add_to_lexicon("chases", tv.clone());
+        parser.add_to_lexicon("sees", tv);
+        parser
+    }
+    
+    #[test]
+    fn test_dependencies() {
+        let parser = setup_svo_parser();
+        let tv = "(S\\NP)/NP";
+        let expected = vec![
+            (0, 1, "NP/N.1".to_string()),
+            (2, 1, format!("{}.1", tv)),
+            (2, 4, format!("{}.2", tv)),
+            (3, 4, "NP/N.1".to_string()),
+        ];
+        
+        let parse = parser.parse("the dog chases the cat").unwrap();
+        assert_eq!(parse.span(), (0, 5));
+        assert_eq!(parse.dependencies(), expected);
+        
+        // Derivations through type-raising and composition give the same dependencies
+        for derivation in parser.parse_all("the dog chases the cat") {
+            assert_eq!(derivation.dependencies(), expected);
+        }
+    }
+    
+    #[test]
+    fn test_derivation_display() {
+        let parser = setup_test_parser();
+        let parse = parser.parse("the cat sleeps").unwrap();
+        let expected = [
+            "        S",
+            "------------------<",
+            "   NP",
+            "--------->",
+            "NP/N  N     S\\NP",
+            "the   cat   sleeps",
+        ];
+        assert_eq!(parse.to_derivation_display(), expected.join("\n"));
+        
+        // Deeper trees keep every word on the bottom row
+        let parser = setup_svo_parser();
+        let parse = parser.parse("the dog chases the cat").unwrap();
+        let expected = [
+            "                S",
+            "----------------------------------<",
+            "                    S\\NP",
+            "            --------------------->",
+            "   NP                     NP",
+            "--------->             --------->",
+            "NP/N  N     (S\\NP)/NP  NP/N  N",
+            "the   dog   chases     the   cat",
+        ];
+        assert_eq!(parse.to_derivation_display(), expected.join("\n"));
+    }
+    
+    #[test]
+    fn test_to_conll() {
+        let parser = setup_svo_parser();
+        let parse = parser.parse("the dog chases the cat").unwrap();
+        let expected = [
+            "1\tthe\tNP/N\t2\tNP/N.1",
+            "2\tdog\tN\t3\t(S\\NP)/NP.1",
+            "3\tchases\t(S\\NP)/NP\t0\troot",
+            "4\tthe\tNP/N\t5\tNP/N.1",
+            "5\tcat\tN\t3\t(S\\NP)/NP.2",
+        ];
+        assert_eq!(parse.to_conll(), format!("{}\n", expected.join("\n")));
+    }
+    
+    #[test]
+    fn test_normal_form_only() {
+        let mut parser = setup_test_parser();
+        let tv = CCGCategory::forward(CCGCategory::backward(CCGCategory::s(), CCGCategory::np()), CCGCategory::np());
+        parser.add_to_lexicon("John", CCGCategory::np());
+        parser.add_to_lexicon("Mary", CCGCategory::np());
+        parser.add_to_lexicon("saw", tv);
+        
+        // Type-raising and composition give several equivalent derivations
+        assert!(parser.parse_all("John saw Mary").len() > 1);
+        
+        let mut config = parser.config.clone();
+        config.normal_form_only = true;
+        parser.set_config(config);
+        
+        let derivations = parser.parse_all("John saw Mary");
+        assert_eq!(derivations.len(), 1);
+        assert_eq!(derivations[0].rule.as_deref(), Some("<"));
+    }
+    
+    #[test]
+    fn test_composition_feature_percolation() {
+        let mut parser = setup_test_parser();
+        let mut config = parser.config.clone();
+        config.use_morphosyntax = true;
+        config.enforce_feature_unification = true;
+        parser.set_config(config);
+        
+        let num = |value: FeatureValue| FeatureStructure::with_feature("num", value);
+        let np = |value: FeatureValue| CCGCategory::atomic_with_features("NP", num(value));
+        let sg = || FeatureValue::Atomic("sg".to_string());
+        let pl = || FeatureValue::Atomic("pl".to_string());
+        let n = || FeatureValue::Variable("n".to_string());
+        
+        // he: S/(S\NP[sg]), remained: ((S\NP[?n])/NP[?n])/PP
+        let he = CCGNode::leaf("he", CCGCategory::forward(
+            CCGCategory::s(),
+            CCGCategory::backward(CCGCategory::s(), np(sg())),
+        ));
+        let remained = CCGNode::leaf("remained", CCGCategory::forward(
+            CCGCategory::forward(CCGCategory::backward(CCGCategory::s(), np(n())), np(n())),
+            CCGCategory::atomic("PP"),
+        ));
+        
+        // The subject's number binds ?n, which must carry over to the object
+        let composed = parser.compose_forward_generalized(&he, &remained, 2).unwrap();
+        assert_eq!(composed.rule.as_deref(), Some(">B2"));
+        assert_eq!(
+            composed.category,
+            CCGCategory::forward(CCGCategory::forward(CCGCategory::s(), np(sg())), CCGCategory::atomic("PP")),
+        );
+        
+        let pp = CCGNode::leaf("there", CCGCategory::atomic("PP"));
+        let with_pp = ForwardApplication.apply(&composed, &pp, true).unwrap();
+        assert!(ForwardApplication.apply(&with_pp, &CCGNode::leaf("doctor", np(sg())), true).is_some());
+        assert!(ForwardApplication.apply(&with_pp, &CCGNode::leaf("doctors", np(pl())), true).is_none());
+    }
+    
+    #[test]
+    fn test_prefilter_combinations() {
+        let mut parser = setup_test_parser();
+        let s = CCGCategory::s;
+        let np = CCGCategory::np;
+        let pp = || CCGCategory::atomic("PP");
+        let vp = || CCGCategory::backward(s(), np());
+        
+        // "saw" is a noun, a verb with several frames, and a curious preposition
+        parser.add_to_lexicon("John", np());
+        for category in [
+            CCGCategory::atomic("N"),
+            vp(),
+            CCGCategory::forward(vp(), np()),
+            CCGCategory::forward(vp(), pp()),
+            CCGCategory::forward(CCGCategory::forward(vp(), pp()), np()),
+            CCGCategory::forward(pp(), np()),
+            CCGCategory::backward(CCGCategory::atomic("N"), CCGCategory::atomic("N")),
+        ] {
+            parser.add_to_lexicon("saw", category);
+        }
+        
+        let sentence = "John saw the saw";
+        let before = parser.rule_attempts();
+        let filtered = parser.parse_all(sentence);
+        let filtered_attempts = parser.rule_attempts() - before;
+        
+        let mut config = parser.config.clone();
+        config.prefilter_combinations = false;
+        parser.set_config(config);
+        
+        let before = parser.rule_attempts();
+        let unfiltered = parser.parse_all(sentence);
+        let unfiltered_attempts = parser.rule_attempts() - before;
+        
+        assert!(!filtered.is_empty());
+        assert_eq!(filtered.len(), unfiltered.len());
+        assert!(filtered_attempts * 2 < unfiltered_attempts, "{} vs {}", filtered_attempts, unfiltered_attempts);
+    }
+    
+    #[test]
+    fn test_max_category_depth() {
+        let s = CCGCategory::s;
+        let np = CCGCategory::np;
+        let vp = || CCGCategory::backward(s(), np());
+        
+        // Raising everything to several targets and composing up to order 3
+        // lets derived categories grow well beyond the lexical ones
+        let chart = |max_category_depth: Option<usize>| {
+            let mut parser = setup_test_parser();
+            let mut config = parser.config.clone();
+            config.type_raising_mode = TypeRaisingMode::Eager;
+            config.type_raising_targets = vec![s(), np(), vp()];
+            config.max_composition_order = 3;
+            config.max_category_depth = max_category_depth;
+            parser.set_config(config);
+            
+            parser.add_to_lexicon("x", CCGCategory::forward(CCGCategory::forward(vp(), np()), np()));
+            parser.add_to_lexicon("x", CCGCategory::forward(vp(), vp()));
+            parser.add_to_lexicon("x", CCGCategory::backward(vp(), vp()));
+            parser.add_to_lexicon("y", np());
+            parser.add_to_lexicon("y", CCGCategory::forward(s(), s()));
+            
+            let chart = parser.fill_chart(parser.lexical_nodes("y x y x y x y").unwrap());
+            chart.into_iter().flatten().flatten().collect::<Vec<CCGNode>>()
+        };
+        
+        let unbounded = chart(None);
+        let bounded = chart(Some(3));
+        assert!(unbounded.iter().any(|node| node.category.depth() > 3));
+        assert!(bounded.iter().all(|node| node.category.depth() <= 3));
+        assert!(bounded.len() * 2 < unbounded.len(), "{} vs {}", bounded.len(), unbounded.len());
+        
+        assert_eq!(np().depth(), 0);
+        assert_eq!(CCGCategory::forward(vp(), np()).depth(), 2);
+    }
+    
+    #[test]
+    fn test_parse_tagged() {
+        let parser = setup_test_parser();
+        let s = CCGCategory::s;
+        let np = CCGCategory::np;
+        let tv = CCGCategory::forward(CCGCategory::backward(s(), np()), np());
+        
+        // None of these words are in the lexicon
+        let tokens = [("Kim", vec![np()]), ("admires", vec![tv, np()]), ("Lee", vec![np()])];
+        let tree = parser.parse_tagged(&tokens).unwrap();
+        assert_eq!(tree.category, s());
+        assert_eq!(tree.start, 0);
+        assert_eq!(tree.end, 3);
+        assert!(parser.parse("Kim admires Lee").is_none());
+        
+        // Unregistered atomic types are not used
+        assert!(parser.parse_tagged(&[("Kim", vec![CCGCategory::atomic("X")])]).is_none());
+        assert!(parser.parse_tagged(&[("Kim", vec![np()]), ("admires", vec![np()]), ("Lee", vec![np()])]).is_none());
+    }
+    
+    #[test]
+    fn test_underspecified_atomic_unify() {
+        let form = |value: &str| FeatureStructure::with_feature("form", FeatureValue::Atomic(value.to_string()));
+        let s = CCGCategory::s();
+        let s_dcl = CCGCategory::atomic_with_features("S", form("dcl"));
+        let s_q = CCGCategory::atomic_with_features("S", form("q"));
+        let np = CCGCategory::np();
+        
+        // A bare S subsumes S[dcl], but S[dcl] and S[q] conflict
+        assert_eq!(s.unify(&s_dcl), Some(s_dcl.clone()));
+        assert!(s_dcl.unify(&s_q).is_none());
+        
+        let mut parser = setup_test_parser();
+        parser.register_feature_dimension("form", &["dcl", "q"]);
+        parser.add_to_lexicon("john", np.clone());
+        parser.add_to_lexicon("snores", CCGCategory::backward(s_dcl.clone(), np.clone()));
+        parser.add_to_lexicon("maybe", CCGCategory::forward(s.clone(), s.clone()));
+        parser.add_to_lexicon("whether", CCGCategory::forward(s_q.clone(), s_q.clone()));
+        
+        assert!(parser.parse("maybe john snores").is_none());
+        
+        // The raised subject's result shares the features of the S it finds
+        let mut config = parser.config().clone();
+        config.underspecified_atomic_unify = true;
+        config.enable_type_raising = true;
+        parser.set_config(config);
+        assert!(parser.parse("maybe john snores").is_some());
+        assert!(parser.parse("whether john snores").is_none());
+    }
+    
+    #[test]
+    fn test_coverage() {
+        let parser = setup_svo_parser();
+        let suite = [
+            ("the cat sleeps", true),
+            ("the dog chases the cat", true),
+            ("a cat runs", true),
+            ("the cat the dog", false),
+            ("sleeps the cat", false),
+            ("the cat sleeps the dog", false),
+            // Undergenerated: the lexicon has no adverbs
+            ("the cat sleeps soundly", true),
+        ];
+        
+        let report = crate::common::coverage(&parser, &suite);
+        assert_eq!(report.total(), 7);
+        assert_eq!(report.accepted_grammatical, 3);
+        assert_eq!(report.accepted_ungrammatical, 0);
+        assert_eq!(report.rejected_grammatical, 1);
+        assert_eq!(report.rejected_ungrammatical, 3);
+        assert_eq!(report.precision(), 1.0);
+        assert_eq!(report.recall(), 0.75);
+        assert_eq!(report.errors, vec!["the cat sleeps soundly".to_string()]);
+    }
+    
+    #[test]
+    fn test_parse_incremental() {
+        let mut parser = setup_test_parser();
+        let category = |name: &str| CCGCategory::atomic(name);
+        let tv = CCGCategory::forward(CCGCategory::backward(category("S"), category("NP")), category("NP"));
+        parser.add_to_lexicon("likes", tv);
+        
+        // Every prefix of "the cat sleeps" forms a single constituent
+        let states = parser.parse_incremental("the cat sleeps");
+        assert_eq!(states.len(), 3);
+        assert!(states.iter().all(IncrementalState::is_connected));
+        assert_eq!(states[0].prefix_categories(), vec![&CCGCategory::forward(category("NP"), category("N"))]);
+        assert_eq!(states[1].prefix_categories(), vec![&category("NP")]);
+        assert_eq!(states[2].prefix_categories(), vec![&category("S")]);
+        
+        // Without type raising the subject cannot combine with a transitive verb
+        let states = parser.parse_incremental("the cat likes the dog");
+        assert!(!states[2].is_connected());
+        assert_eq!(states[4].prefix_categories(), vec![&category("S")]);
+        assert!(parser.parse_incremental("the unicorn").is_empty());
+        
+        // Generalized composition is left to the chart
+        let ditransitive = CCGCategory::forward(CCGCategory::forward(category("S"), category("NP")), category("NP"));
+        parser.add_to_lexicon("maybe", CCGCategory::forward(category("S"), category("S")));
+        parser.add_to_lexicon("gives", ditransitive.clone());
+        let mut config = parser.config().clone();
+        config.max_composition_order = 2;
+        parser.set_config(config);
+        assert!(parser.combine_cells(&parser.lexical_nodes("maybe").unwrap()[0], &parser.lexical_nodes("gives").unwrap()[0])
+            .iter().any(|node| node.category == ditransitive));
+        assert!(!parser.parse_incremental("maybe gives")[1].is_connected());
+    }
+    
+    #[test]
+    fn test_tracer_steps() {
+        let mut parser = setup_test_parser();
+        let mut config = parser.config.clone();
+        config.enable_type_raising = false;
+        parser.set_config(config);
+        
+        let tracer = VecTracer::new();
+        parser.set_tracer(Box::new(tracer.clone()));
+        assert!(parser.parse("the cat sleeps").is_some());
+        
+        let steps: Vec<String> = tracer.steps().iter().map(Step::to_string).collect();
+        assert_eq!(steps, vec![
+            "Lexical: the := NP/N",
+            "Lexical: cat := N",
+            "Lexical: sleeps := S\\NP",
+            "Rule: NP/N + N => NP (>) over 0..2",
+            "Rule: NP + S\\NP => S (<) over 0..3",
+        ]);
+    }
+    
+    #[test]
+    fn test_parse_within() {
+        let parser = setup_test_parser();
+        
+        assert!(parser.parse_within("the cat sleeps", StepBudget::new(100)).is_complete());
+        assert_eq!(parser.parse_within("sleeps the cat", StepBudget::new(100)), ParseOutcome::Exhausted);
+        
+        // Two combinations fill the spans of two words but not the sentence
+        match parser.parse_within("the cat sleeps", StepBudget::new(2)) {
+            ParseOutcome::BudgetExceeded { best_partial: Some(node) } => {
+                assert_eq!((node.start, node.end), (0, 2));
+            },
+            outcome => panic!("expected the budget to run out, got {:?}", outcome),
+        }
+    }
+    
+    #[test]
+    fn test_type_raising_modes() {
+        let mut parser = setup_svo_parser();
+        parser.add_to_lexicon("John", CCGCategory::np());
+        parser.add_to_lexicon("Mary", CCGCategory::np());
+        
+        let chart_size = |parser: &CCGParser, sentence: &str| {
+            let chart = parser.fill_chart(parser.lexical_nodes(sentence).unwrap());
+            chart.iter().flatten().map(Vec::len).sum::<usize>()
+        };
+        let with_mode = |parser: &mut CCGParser, mode: TypeRaisingMode| {
+            let mut config = parser.config.clone();
+            config.type_raising_mode = mode;
+            parser.set_config(config);
+        };
+        
+        let sentence = "John sees the dog";
+        let default_size = chart_size(&parser, sentence);
+        let default_parses = parser.parse_all(sentence);
+        
+        with_mode(&mut parser, TypeRaisingMode::Eager);
+        let eager_size = chart_size(&parser, sentence);
+        
+        with_mode(&mut parser, TypeRaisingMode::Lazy);
+        let lazy_size = chart_size(&parser, sentence);
+        let lazy_parses = parser.parse_all(sentence);
+        
+        assert_eq!(parser.config.type_raising_mode, TypeRaisingMode::Lazy);
+        assert!(default_size < eager_size, "{} vs {}", default_size, eager_size);
+        assert!(lazy_size < eager_size, "{} vs {}", lazy_size, eager_size);
+        
+        // The subject is still raised and composed with the verb
+        for parses in [default_parses, lazy_parses] {
+            assert!(parses.iter().any(|parse| parse.children[0].rule.as_deref() == Some(">B")));
+        }
+    }
+}
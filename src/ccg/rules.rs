@@ -1,15 +1,28 @@
 //! CCG combinatory rules
 
-use crate::ccg::category::CCGCategory;
+use crate::ccg::category::{CCGCategory, SlashModality};
 use crate::ccg::node::CCGNode;
+use crate::common::{Bindings, FeatureRegistry, FeatureStructure, FeatureValue};
 
 /// Function for applying CCG rules to derive new categories and nodes
 pub trait CCGRule {
     /// Apply this rule to the given nodes and return a new node if successful
     fn apply(&self, left: &CCGNode, right: &CCGNode, use_features: bool) -> Option<CCGNode>;
     
+    /// Apply this rule, unifying features under the value orders of a registry
+    ///
+    /// Rules that do not unify features apply as [`CCGRule::apply`] does.
+    fn apply_with_registry(&self, left: &CCGNode, right: &CCGNode, _registry: &FeatureRegistry) -> Option<CCGNode> {
+        self.apply(left, right, true)
+    }
+    
     /// Get the name of this rule
     fn name(&self) -> &str;
+    
+    /// Get the number of nodes this rule combines
+    fn arity(&self) -> usize {
+        2
+    }
 }
 
 /// Forward application rule: X/Y Y => X
@@ -17,13 +30,28 @@ pub struct ForwardApplication;
 
 impl CCGRule for ForwardApplication {
     fn apply(&self, left: &CCGNode, right: &CCGNode, use_features: bool) -> Option<CCGNode> {
-        if let CCGCategory::Forward(x, y) = &left.category {
-            if use_features {
+        self.combine(left, right, use_features.then(Bindings::new))
+    }
+    
+    fn apply_with_registry(&self, left: &CCGNode, right: &CCGNode, registry: &FeatureRegistry) -> Option<CCGNode> {
+        self.combine(left, right, Some(Bindings::with_registry(registry)))
+    }
+    
+    fn name(&self) -> &str {
+        "Forward Application"
+    }
+}
+
+impl ForwardApplication {
+    /// Combine two nodes, unifying features into `bindings` if there are any
+    fn combine(&self, left: &CCGNode, right: &CCGNode, bindings: Option<Bindings>) -> Option<CCGNode> {
+        if let CCGCategory::Forward(x, y, _) = &left.category {
+            if let Some(mut bindings) = bindings {
                 // Try to unify the argument category with the right-hand category
-                if let Some(_) = y.unify(&right.category) {
-                    // If unification succeeds, create a new node with the resulting category
+                if y.unify_with_bindings(&right.category, &mut bindings).is_some() {
+                    // If unification succeeds, the result carries the features bound by the argument
                     return Some(CCGNode::internal(
-                        (**x).clone(),
+                        x.apply_bindings(&bindings),
                         vec![left.clone(), right.clone()],
                         ">",
                     ));
@@ -42,10 +70,6 @@ impl CCGRule for ForwardApplication {
         }
         None
     }
-    
-    fn name(&self) -> &str {
-        "Forward Application"
-    }
 }
 
 /// Backward application rule: Y X\Y => X
@@ -53,13 +77,28 @@ pub struct BackwardApplication;
 
 impl CCGRule for BackwardApplication {
     fn apply(&self, left: &CCGNode, right: &CCGNode, use_features: bool) -> Option<CCGNode> {
-        if let CCGCategory::Backward(x, y) = &right.category {
-            if use_features {
+        self.combine(left, right, use_features.then(Bindings::new))
+    }
+    
+    fn apply_with_registry(&self, left: &CCGNode, right: &CCGNode, registry: &FeatureRegistry) -> Option<CCGNode> {
+        self.combine(left, right, Some(Bindings::with_registry(registry)))
+    }
+    
+    fn name(&self) -> &str {
+        "Backward Application"
+    }
+}
+
+impl BackwardApplication {
+    /// Combine two nodes, unifying features into `bindings` if there are any
+    fn combine(&self, left: &CCGNode, right: &CCGNode, bindings: Option<Bindings>) -> Option<CCGNode> {
+        if let CCGCategory::Backward(x, y, _) = &right.category {
+            if let Some(mut bindings) = bindings {
                 // Try to unify the argument category with the left-hand category
-                if let Some(_) = y.unify(&left.category) {
-                    // If unification succeeds, create a new node with the resulting category
+                if y.unify_with_bindings(&left.category, &mut bindings).is_some() {
+                    // If unification succeeds, the result carries the features bound by the argument
                     return Some(CCGNode::internal(
-                        (**x).clone(),
+                        x.apply_bindings(&bindings),
                         vec![left.clone(), right.clone()],
                         "<",
                     ));
@@ -78,28 +117,45 @@ impl CCGRule for BackwardApplication {
         }
         None
     }
-    
-    fn name(&self) -> &str {
-        "Backward Application"
-    }
 }
 
 /// Forward composition rule: X/Y Y/Z => X/Z
+///
+/// Both slashes must permit harmonic composition.
 pub struct ForwardComposition;
 
 impl CCGRule for ForwardComposition {
     fn apply(&self, left: &CCGNode, right: &CCGNode, use_features: bool) -> Option<CCGNode> {
-        if let CCGCategory::Forward(x, y) = &left.category {
-            if let CCGCategory::Forward(right_result, right_arg) = &right.category {
-                let matches = if use_features {
-                    y.unify(right_result).is_some()
-                } else {
-                    **y == **right_result
+        self.combine(left, right, use_features.then(Bindings::new))
+    }
+    
+    fn apply_with_registry(&self, left: &CCGNode, right: &CCGNode, registry: &FeatureRegistry) -> Option<CCGNode> {
+        self.combine(left, right, Some(Bindings::with_registry(registry)))
+    }
+    
+    fn name(&self) -> &str {
+        "Forward Composition"
+    }
+}
+
+impl ForwardComposition {
+    /// Combine two nodes, unifying features into `bindings` if there are any
+    fn combine(&self, left: &CCGNode, right: &CCGNode, bindings: Option<Bindings>) -> Option<CCGNode> {
+        if let CCGCategory::Forward(x, y, modality) = &left.category {
+            if let CCGCategory::Forward(right_result, right_arg, right_modality) = &right.category {
+                if !modality.permits_composition(false) || !right_modality.permits_composition(false) {
+                    return None;
+                }
+                
+                let (matches, bindings) = match bindings {
+                    Some(mut bindings) => (y.unify_with_bindings(right_result, &mut bindings).is_some(), bindings),
+                    None => (**y == **right_result, Bindings::new()),
                 };
                 
                 if matches {
-                    // Construct the result category: X/Z
-                    let result = CCGCategory::forward((**x).clone(), (**right_arg).clone());
+                    // Construct the result category: X/Z, keeping features bound by matching Y
+                    let result = CCGCategory::forward_with_modality((**x).clone(), (**right_arg).clone(), *right_modality)
+                        .apply_bindings(&bindings);
                     
                     return Some(CCGNode::internal(
                         result,
@@ -111,28 +167,45 @@ impl CCGRule for ForwardComposition {
         }
         None
     }
-    
-    fn name(&self) -> &str {
-        "Forward Composition"
-    }
 }
 
 /// Backward composition rule: Y\Z X\Y => X\Z
+///
+/// Both slashes must permit harmonic composition.
 pub struct BackwardComposition;
 
 impl CCGRule for BackwardComposition {
     fn apply(&self, left: &CCGNode, right: &CCGNode, use_features: bool) -> Option<CCGNode> {
-        if let CCGCategory::Backward(x, y) = &right.category {
-            if let CCGCategory::Backward(left_result, left_arg) = &left.category {
-                let matches = if use_features {
-                    y.unify(left_result).is_some()
-                } else {
-                    **y == **left_result
+        self.combine(left, right, use_features.then(Bindings::new))
+    }
+    
+    fn apply_with_registry(&self, left: &CCGNode, right: &CCGNode, registry: &FeatureRegistry) -> Option<CCGNode> {
+        self.combine(left, right, Some(Bindings::with_registry(registry)))
+    }
+    
+    fn name(&self) -> &str {
+        "Backward Composition"
+    }
+}
+
+impl BackwardComposition {
+    /// Combine two nodes, unifying features into `bindings` if there are any
+    fn combine(&self, left: &CCGNode, right: &CCGNode, bindings: Option<Bindings>) -> Option<CCGNode> {
+        if let CCGCategory::Backward(x, y, modality) = &right.category {
+            if let CCGCategory::Backward(left_result, left_arg, left_modality) = &left.category {
+                if !modality.permits_composition(false) || !left_modality.permits_composition(false) {
+                    return None;
+                }
+                
+                let (matches, bindings) = match bindings {
+                    Some(mut bindings) => (y.unify_with_bindings(left_result, &mut bindings).is_some(), bindings),
+                    None => (**y == **left_result, Bindings::new()),
                 };
                 
                 if matches {
-                    // Construct the result category: X\Z
-                    let result = CCGCategory::backward((**x).clone(), (**left_arg).clone());
+                    // Construct the result category: X\Z, keeping features bound by matching Y
+                    let result = CCGCategory::backward_with_modality((**x).clone(), (**left_arg).clone(), *left_modality)
+                        .apply_bindings(&bindings);
                     
                     return Some(CCGNode::internal(
                         result,
@@ -144,10 +217,6 @@ impl CCGRule for BackwardComposition {
         }
         None
     }
-    
-    fn name(&self) -> &str {
-        "Backward Composition"
-    }
 }
 
 /// Forward type-raising rule: X => T/(T\X)
@@ -158,7 +227,28 @@ pub struct ForwardTypeRaising {
 
 impl CCGRule for ForwardTypeRaising {
     fn apply(&self, node: &CCGNode, _right: &CCGNode, _use_features: bool) -> Option<CCGNode> {
+        self.raise(node, None)
+    }
+    
+    fn apply_with_registry(&self, node: &CCGNode, _right: &CCGNode, registry: &FeatureRegistry) -> Option<CCGNode> {
+        self.raise(node, Some(registry))
+    }
+    
+    fn name(&self) -> &str {
+        "Forward Type Raising"
+    }
+    
+    fn arity(&self) -> usize {
+        1
+    }
+}
+
+impl ForwardTypeRaising {
+    /// Raise a node, sharing the registry's features between both occurrences of T
+    fn raise(&self, node: &CCGNode, registry: Option<&FeatureRegistry>) -> Option<CCGNode> {
         for t in &self.targets {
+            let t = shared_target(t, node, registry);
+            
             // Create T\X
             let backward_cat = CCGCategory::backward(
                 t.clone(), 
@@ -167,7 +257,7 @@ impl CCGRule for ForwardTypeRaising {
             
             // Create T/(T\X)
             let new_cat = CCGCategory::forward(
-                t.clone(),
+                t,
                 backward_cat
             );
             
@@ -179,10 +269,6 @@ impl CCGRule for ForwardTypeRaising {
         }
         None
     }
-    
-    fn name(&self) -> &str {
-        "Forward Type Raising"
-    }
 }
 
 /// Backward type-raising rule: X => T\(T/X)
@@ -193,7 +279,28 @@ pub struct BackwardTypeRaising {
 
 impl CCGRule for BackwardTypeRaising {
     fn apply(&self, node: &CCGNode, _right: &CCGNode, _use_features: bool) -> Option<CCGNode> {
+        self.raise(node, None)
+    }
+    
+    fn apply_with_registry(&self, node: &CCGNode, _right: &CCGNode, registry: &FeatureRegistry) -> Option<CCGNode> {
+        self.raise(node, Some(registry))
+    }
+    
+    fn name(&self) -> &str {
+        "Backward Type Raising"
+    }
+    
+    fn arity(&self) -> usize {
+        1
+    }
+}
+
+impl BackwardTypeRaising {
+    /// Raise a node, sharing the registry's features between both occurrences of T
+    fn raise(&self, node: &CCGNode, registry: Option<&FeatureRegistry>) -> Option<CCGNode> {
         for t in &self.targets {
+            let t = shared_target(t, node, registry);
+            
             // Create T/X
             let forward_cat = CCGCategory::forward(
                 t.clone(),
@@ -202,7 +309,7 @@ impl CCGRule for BackwardTypeRaising {
             
             // Create T\(T/X)
             let new_cat = CCGCategory::backward(
-                t.clone(),
+                t,
                 forward_cat
             );
             
@@ -214,42 +321,61 @@ impl CCGRule for BackwardTypeRaising {
         }
         None
     }
+}
+
+/// Give a type-raising target a variable for each registered feature it leaves open
+///
+/// The raised category's result and argument then share the variables, so the
+/// features its argument is found with are those of its result, as in
+/// `S[?form]/(S[?form]\NP)`. The variables are named after the raised node's
+/// start, keeping those of different raised nodes apart.
+fn shared_target(target: &CCGCategory, node: &CCGNode, registry: Option<&FeatureRegistry>) -> CCGCategory {
+    let Some(registry) = registry else {
+        return target.clone();
+    };
     
-    fn name(&self) -> &str {
-        "Backward Type Raising"
+    let mut features: Vec<&String> = registry.features.keys().collect();
+    features.sort();
+    let mut shared = FeatureStructure::new();
+    for feature in features {
+        shared.add(feature, FeatureValue::Variable(format!("{}@{}", feature, node.start)));
     }
+    target.with_result_features(&shared).unwrap_or_else(|| target.clone())
 }
 
+/// An argument peeled off a functor: its direction (`true` for forward), category and slash modality
+pub type ChainArgument = (bool, CCGCategory, SlashModality);
+
 /// Function to extract category chain for higher-order composition
 pub fn extract_category_chain(
     cat: &CCGCategory, 
     depth: usize, 
     max_depth: usize
-) -> Option<(CCGCategory, Vec<(bool, CCGCategory)>)> {
+) -> Option<(CCGCategory, Vec<ChainArgument>)> {
     if depth >= max_depth {
         return None;
     }
     
     match cat {
-        CCGCategory::Forward(res, arg) => {
+        CCGCategory::Forward(res, arg, modality) => {
             if depth == 0 {
                 // For first level, start building the chain
-                Some(((**res).clone(), vec![(true, (**arg).clone())]))
+                Some(((**res).clone(), vec![(true, (**arg).clone(), *modality)]))
             } else if let Some((base_res, mut args)) = extract_category_chain(res, depth + 1, max_depth) {
                 // Add this argument to the chain
-                args.push((true, (**arg).clone()));
+                args.push((true, (**arg).clone(), *modality));
                 Some((base_res, args))
             } else {
                 None
             }
         },
-        CCGCategory::Backward(res, arg) => {
+        CCGCategory::Backward(res, arg, modality) => {
             if depth == 0 {
                 // For first level, start building the chain
-                Some(((**res).clone(), vec![(false, (**arg).clone())]))
+                Some(((**res).clone(), vec![(false, (**arg).clone(), *modality)]))
             } else if let Some((base_res, mut args)) = extract_category_chain(res, depth + 1, max_depth) {
                 // Add this argument to the chain
-                args.push((false, (**arg).clone()));
+                args.push((false, (**arg).clone(), *modality));
                 Some((base_res, args))
             } else {
                 None
@@ -302,6 +428,36 @@ mod tests {
         assert_eq!(result_node.rule, Some("<".to_string()));
     }
     
+    #[test]
+    fn test_application_binds_features() {
+        use crate::common::{FeatureStructure, FeatureValue};
+        
+        let per = |value: FeatureValue| FeatureStructure::with_feature("per", value);
+        let atomic = |name: &str, value: FeatureValue| CCGCategory::atomic_with_features(name, per(value));
+        let third = || FeatureValue::Atomic("3".to_string());
+        let first = || FeatureValue::Atomic("1".to_string());
+        let p = || FeatureValue::Variable("p".to_string());
+        
+        // sleep: S[per=?p]\NP[per=?p] learns its person from the subject
+        let sleep = CCGNode::leaf("sleep", CCGCategory::backward(atomic("S", p()), atomic("NP", p())));
+        let he = CCGNode::leaf("he", atomic("NP", third()));
+        let i = CCGNode::leaf("I", atomic("NP", first()));
+        
+        let clause = BackwardApplication.apply(&he, &sleep, true).unwrap();
+        assert_eq!(clause.category, atomic("S", third()));
+        
+        // A modifier of third person clauses only accepts the resolved clause
+        let modifier = CCGNode::leaf("alone", CCGCategory::backward(CCGCategory::s(), atomic("S", third())));
+        assert!(BackwardApplication.apply(&clause, &modifier, true).is_some());
+        let first_clause = BackwardApplication.apply(&i, &sleep, true).unwrap();
+        assert!(BackwardApplication.apply(&first_clause, &modifier, true).is_none());
+        
+        // The same holds for forward application
+        let sees = CCGNode::leaf("sees", CCGCategory::forward(atomic("VP", p()), atomic("NP", p())));
+        let vp = ForwardApplication.apply(&sees, &he, true).unwrap();
+        assert_eq!(vp.category, atomic("VP", third()));
+    }
+    
     #[test]
     fn test_forward_composition() {
         // Test forward composition
@@ -330,6 +486,30 @@ mod tests {
         assert_eq!(result_node.rule, Some(">B".to_string()));
     }
     
+    #[test]
+    fn test_application_only_slash() {
+        let s = CCGCategory::s();
+        let np = CCGCategory::np();
+        let vp = CCGCategory::backward(s.clone(), np.clone());
+        
+        // A modal whose slash only permits application: S/⋆VP
+        let modal_cat = CCGCategory::forward_with_modality(s.clone(), vp.clone(), SlashModality::Star);
+        assert!(matches!(&modal_cat, CCGCategory::Forward(result, argument, SlashModality::Star)
+            if **result == s && **argument == vp));
+        
+        let modal_node = CCGNode::leaf("will", modal_cat);
+        let tv_node = CCGNode::leaf("chase", CCGCategory::forward(vp.clone(), np.clone()));
+        let vp_node = CCGNode::leaf("sleep", vp.clone());
+        
+        assert!(ForwardComposition.apply(&modal_node, &tv_node, false).is_none());
+        assert_eq!(ForwardApplication.apply(&modal_node, &vp_node, false).unwrap().category, s);
+        
+        // An order-preserving slash still composes harmonically
+        let harmonic = CCGNode::leaf("will", CCGCategory::forward_with_modality(s.clone(), vp, SlashModality::Diamond));
+        assert!(ForwardComposition.apply(&harmonic, &tv_node, false).is_some());
+        assert!(!SlashModality::Diamond.permits_composition(true));
+    }
+    
     #[test]
     fn test_forward_type_raising() {
         // Test forward type raising
@@ -345,10 +525,10 @@ mod tests {
         let result_node = result.unwrap();
         // Result should be S/(S\NP)
         match &result_node.category {
-            CCGCategory::Forward(t, y) => {
+            CCGCategory::Forward(t, y, _) => {
                 assert_eq!(**t, CCGCategory::s());
                 match &**y {
-                    CCGCategory::Backward(t2, x) => {
+                    CCGCategory::Backward(t2, x, _) => {
                         assert_eq!(**t2, CCGCategory::s());
                         assert_eq!(**x, np);
                     },
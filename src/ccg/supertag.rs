@@ -0,0 +1,17 @@
+//! Supertag models for joint supertagging and parsing
+
+use std::collections::HashMap;
+use crate::ccg::category::CCGCategory;
+
+/// A model assigning scored candidate categories (supertags) to words
+pub trait SupertagModel {
+    /// Get the candidate categories for the word at `position` with their probabilities
+    fn candidates(&self, words: &[&str], position: usize) -> Vec<(CCGCategory, f64)>;
+}
+
+/// A context-free tag model mapping each word to a fixed tag distribution
+impl SupertagModel for HashMap<String, Vec<(CCGCategory, f64)>> {
+    fn candidates(&self, words: &[&str], position: usize) -> Vec<(CCGCategory, f64)> {
+        self.get(words[position]).cloned().unwrap_or_default()
+    }
+}
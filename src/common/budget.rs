@@ -0,0 +1,50 @@
+//! Step budgets for bounded parsing
+
+/// The number of search steps a parse may take
+///
+/// What counts as a step depends on the parser: a combination of two chart
+/// cells in CCG, and a state taken from the agenda in MG and TLG.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepBudget {
+    /// The maximum number of steps
+    pub max_steps: usize,
+}
+
+impl StepBudget {
+    /// Create a budget of at most `max_steps` steps
+    pub fn new(max_steps: usize) -> Self {
+        StepBudget { max_steps }
+    }
+}
+
+/// The result of parsing within a step budget
+///
+/// Unlike a plain `None`, this tells a sentence the grammar rejects apart
+/// from one the parser ran out of steps on.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseOutcome<N> {
+    /// A complete parse was found
+    Complete(N),
+    /// The search finished without finding a parse
+    Exhausted,
+    /// The budget ran out before the search finished
+    BudgetExceeded {
+        /// The largest partial analysis found so far, if any
+        best_partial: Option<N>,
+    },
+}
+
+impl<N> ParseOutcome<N> {
+    /// Check if a complete parse was found
+    pub fn is_complete(&self) -> bool {
+        matches!(self, ParseOutcome::Complete(_))
+    }
+
+    /// Get the complete parse, if one was found
+    pub fn complete(self) -> Option<N> {
+        match self {
+            ParseOutcome::Complete(node) => Some(node),
+            _ => None,
+        }
+    }
+}
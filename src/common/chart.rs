@@ -0,0 +1,185 @@
+//! Search agendas shared by the parsers of different formalisms
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
+use std::hash::Hash;
+
+/// An agenda item with its priority and insertion order
+struct Entry<I> {
+    priority: f64,
+    order: usize,
+    item: I,
+}
+
+impl<I> PartialEq for Entry<I> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<I> Eq for Entry<I> {}
+
+impl<I> PartialOrd for Entry<I> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<I> Ord for Entry<I> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Higher priority first, then first in first out
+        self.priority.total_cmp(&other.priority)
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
+
+/// A best-first agenda with duplicate detection and a bounded number of steps
+///
+/// Items are popped by descending priority; items of equal priority come
+/// out in insertion order, so an agenda with constant priorities searches
+/// breadth first. An item is only admitted once per key, and the agenda is
+/// exhausted after `max_states` items have been popped.
+pub struct Agenda<I, K> {
+    /// The items waiting to be processed
+    queue: BinaryHeap<Entry<I>>,
+    /// The keys of every item admitted so far
+    seen: HashSet<K>,
+    /// The key identifying duplicate items
+    key: Box<dyn Fn(&I) -> K>,
+    /// The maximum number of items to pop
+    max_states: usize,
+    /// The number of items popped so far
+    steps: usize,
+    /// The number of items admitted so far
+    pushed: usize,
+}
+
+impl<I, K: Eq + Hash> Agenda<I, K> {
+    /// Create an empty agenda identifying duplicates by `key`
+    pub fn new(max_states: usize, key: impl Fn(&I) -> K + 'static) -> Self {
+        Agenda {
+            queue: BinaryHeap::new(),
+            seen: HashSet::new(),
+            key: Box::new(key),
+            max_states,
+            steps: 0,
+            pushed: 0,
+        }
+    }
+
+    /// Add an item, returning `false` if an item with the same key was added before
+    pub fn push(&mut self, item: I, priority: f64) -> bool {
+        if !self.seen.insert((self.key)(&item)) {
+            return false;
+        }
+
+        self.queue.push(Entry { priority, order: self.pushed, item });
+        self.pushed += 1;
+        true
+    }
+
+    /// Take the item with the highest priority, unless the step budget is spent
+    pub fn pop(&mut self) -> Option<I> {
+        if self.is_exhausted() {
+            return None;
+        }
+
+        let entry = self.queue.pop()?;
+        self.steps += 1;
+        Some(entry.item)
+    }
+
+    /// Process items until the agenda is empty or exhausted
+    ///
+    /// `combine` receives each popped item and returns the new items to add
+    /// with their priorities, or `None` to stop the search.
+    pub fn run<F>(&mut self, mut combine: F)
+    where
+        F: FnMut(I) -> Option<Vec<(I, f64)>>,
+    {
+        while let Some(item) = self.pop() {
+            match combine(item) {
+                Some(successors) => {
+                    for (successor, priority) in successors {
+                        self.push(successor, priority);
+                    }
+                },
+                None => break,
+            }
+        }
+    }
+
+    /// Check whether the step budget has been spent
+    pub fn is_exhausted(&self) -> bool {
+        self.steps >= self.max_states
+    }
+
+    /// Get the number of items popped so far
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Get the number of items waiting to be processed
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Check if no items are waiting to be processed
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_agenda_ordering() {
+        let mut agenda = Agenda::new(10, |item: &&str| item.to_string());
+        agenda.push("low", 0.0);
+        agenda.push("first", 1.0);
+        agenda.push("second", 1.0);
+
+        assert_eq!(agenda.pop(), Some("first"));
+        assert_eq!(agenda.pop(), Some("second"));
+        assert_eq!(agenda.pop(), Some("low"));
+        assert_eq!(agenda.pop(), None);
+    }
+
+    #[test]
+    fn test_agenda_deduplication() {
+        let mut agenda = Agenda::new(10, |item: &(u32, char)| item.0);
+        assert!(agenda.push((1, 'a'), 0.0));
+        assert!(!agenda.push((1, 'b'), 5.0));
+
+        // Items stay seen after they have been popped
+        assert_eq!(agenda.pop(), Some((1, 'a')));
+        assert!(!agenda.push((1, 'c'), 0.0));
+        assert!(agenda.is_empty());
+    }
+
+    #[test]
+    fn test_agenda_budget() {
+        // Counting upwards forever is cut off by the step budget
+        let mut agenda = Agenda::new(5, |item: &u32| *item);
+        agenda.push(0, 0.0);
+
+        let mut popped = Vec::new();
+        agenda.run(|item| {
+            popped.push(item);
+            Some(vec![(item + 1, 0.0)])
+        });
+
+        assert_eq!(popped, vec![0, 1, 2, 3, 4]);
+        assert!(agenda.is_exhausted());
+        assert_eq!(agenda.steps(), 5);
+
+        // Stopping early leaves the rest of the agenda untouched
+        let mut agenda = Agenda::new(5, |item: &u32| *item);
+        agenda.push(0, 0.0);
+        agenda.run(|item| if item < 2 { Some(vec![(item + 1, 0.0)]) } else { None });
+        assert_eq!(agenda.steps(), 3);
+        assert!(!agenda.is_exhausted());
+    }
+}
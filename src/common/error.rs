@@ -28,6 +28,37 @@ pub enum Error {
     #[error("Parse error: {0}")]
     ParseError(String),
     
+    /// A word of the input has no lexical entry
+    #[error("Unknown word '{word}' at position {position}")]
+    UnknownWord {
+        /// The word that was not found
+        word: String,
+        /// The position of the word in the tokenized input
+        position: usize,
+    },
+    
+    /// The input has no complete parse
+    #[error("No parse found for: {sentence}")]
+    NoParse {
+        /// The sentence that failed to parse
+        sentence: String,
+    },
+    
+    /// A proof search did not reach its goal
+    #[error("No valid proof found for sentence with goal type: {goal} (unconsumed: {unconsumed:?}, dangling atoms: {dangling:?})")]
+    NoProof {
+        /// The goal of the search
+        goal: String,
+        /// The items left in the deepest search state reached
+        remaining: Vec<String>,
+        /// The labels of the lexical items that were never consumed
+        unconsumed: Vec<String>,
+        /// The atomic types occurring in the remaining items
+        dangling: Vec<String>,
+        /// Whether the search ran out of states before it finished
+        out_of_states: bool,
+    },
+    
     /// Feature unification error
     #[error("Feature unification error: {0}")]
     FeatureUnificationError(String),
@@ -0,0 +1,72 @@
+//! Grammar coverage over regression suites of sentences
+
+use crate::common::Parser;
+
+/// How a parser fared on a suite of grammatical and ungrammatical sentences
+///
+/// A sentence counts as accepted when the parser finds at least one parse.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoverageReport {
+    /// Grammatical sentences that were parsed
+    pub accepted_grammatical: usize,
+    /// Ungrammatical sentences that were parsed, i.e. overgeneration
+    pub accepted_ungrammatical: usize,
+    /// Grammatical sentences that were not parsed, i.e. undergeneration
+    pub rejected_grammatical: usize,
+    /// Ungrammatical sentences that were not parsed
+    pub rejected_ungrammatical: usize,
+    /// The sentences the parser got wrong, in suite order
+    pub errors: Vec<String>,
+}
+
+impl CoverageReport {
+    /// The number of sentences in the suite
+    pub fn total(&self) -> usize {
+        self.accepted_grammatical + self.accepted_ungrammatical
+            + self.rejected_grammatical + self.rejected_ungrammatical
+    }
+
+    /// The fraction of accepted sentences that are grammatical, or 0 if none were accepted
+    pub fn precision(&self) -> f64 {
+        ratio(self.accepted_grammatical, self.accepted_grammatical + self.accepted_ungrammatical)
+    }
+
+    /// The fraction of grammatical sentences that were accepted, or 0 if there were none
+    pub fn recall(&self) -> f64 {
+        ratio(self.accepted_grammatical, self.accepted_grammatical + self.rejected_grammatical)
+    }
+
+    /// The fraction of sentences classified correctly, or 0 for an empty suite
+    pub fn accuracy(&self) -> f64 {
+        ratio(self.accepted_grammatical + self.rejected_ungrammatical, self.total())
+    }
+}
+
+/// Divide two counts, giving 0 for an empty denominator
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+/// Run a parser over sentences paired with whether they are grammatical
+pub fn coverage<P: Parser + ?Sized>(parser: &P, suite: &[(&str, bool)]) -> CoverageReport {
+    let mut report = CoverageReport::default();
+
+    for &(sentence, grammatical) in suite {
+        let accepted = parser.parse(sentence).is_some();
+        match (accepted, grammatical) {
+            (true, true) => report.accepted_grammatical += 1,
+            (true, false) => report.accepted_ungrammatical += 1,
+            (false, true) => report.rejected_grammatical += 1,
+            (false, false) => report.rejected_ungrammatical += 1,
+        }
+        if accepted != grammatical {
+            report.errors.push(sentence.to_string());
+        }
+    }
+
+    report
+}
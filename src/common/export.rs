@@ -0,0 +1,56 @@
+//! Export of parse trees to a CoNLL-like column format
+
+/// One token of a CoNLL-like export
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConllToken {
+    /// The word as it appears in the input
+    pub form: String,
+    /// The category of the word in the formalism's notation
+    pub category: String,
+    /// The 1-based index of the head, or `0` for the root
+    pub head: usize,
+    /// The relation to the head
+    pub relation: String,
+}
+
+/// Trait for parse trees exported as one token per line
+///
+/// Each line has the tab-separated columns ID, FORM, CATEGORY, HEAD and
+/// RELATION, with 1-based token ids and head `0` for the root. Missing
+/// values are written `_`.
+pub trait ToConll {
+    /// Export the tree in the CoNLL-like format
+    fn to_conll(&self) -> String;
+}
+
+/// Format tokens as CoNLL-like lines
+pub fn format_conll(tokens: &[ConllToken]) -> String {
+    let field = |value: &str| if value.is_empty() { "_".to_string() } else { value.to_string() };
+    
+    tokens.iter()
+        .enumerate()
+        .map(|(i, token)| format!(
+            "{}\t{}\t{}\t{}\t{}\n",
+            i + 1,
+            field(&token.form),
+            field(&token.category),
+            token.head,
+            field(&token.relation),
+        ))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_conll() {
+        let tokens = vec![
+            ConllToken { form: "cats".to_string(), category: "N".to_string(), head: 2, relation: "subj".to_string() },
+            ConllToken { form: "sleep".to_string(), category: String::new(), head: 0, relation: "root".to_string() },
+        ];
+        
+        assert_eq!(format_conll(&tokens), "1\tcats\tN\t2\tsubj\n2\tsleep\t_\t0\troot\n");
+    }
+}
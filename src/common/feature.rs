@@ -1,6 +1,6 @@
 //! Feature structures and operations for linguistic features
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
 use std::hash;
 
@@ -40,21 +40,64 @@ impl fmt::Display for FeatureValue {
     }
 }
 
+/// Variable bindings accumulated while unifying feature structures
+///
+/// Bindings made with [`Bindings::with_registry`] also unify feature values
+/// under the registry's subsumption order, so an underspecified value
+/// unifies with each value it subsumes.
+#[derive(Debug, Clone, Default)]
+pub struct Bindings<'a> {
+    /// The value bound to each variable
+    values: HashMap<String, FeatureValue>,
+    /// The registry whose value orders apply during unification
+    registry: Option<&'a FeatureRegistry>,
+}
+
+impl<'a> Bindings<'a> {
+    /// Create an empty set of bindings
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Create an empty set of bindings that unifies values under the orders of a registry
+    pub fn with_registry(registry: &'a FeatureRegistry) -> Self {
+        Bindings { values: HashMap::new(), registry: Some(registry) }
+    }
+    
+    /// Get the value bound to a variable
+    pub fn get(&self, variable: &str) -> Option<&FeatureValue> {
+        self.values.get(variable)
+    }
+    
+    /// Bind a variable to a value
+    pub fn insert(&mut self, variable: String, value: FeatureValue) {
+        self.values.insert(variable, value);
+    }
+}
+
 /// Morphosyntactic feature structure used across grammar formalisms
+///
+/// Features are kept sorted by name, so equality, hashing, iteration and
+/// display are all independent of insertion order.
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct FeatureStructure {
     /// Map of feature names to values
-    pub features: HashMap<String, FeatureValue>,
+    pub features: BTreeMap<String, FeatureValue>,
 }
 
 impl FeatureStructure {
     /// Create a new empty feature structure
     pub fn new() -> Self {
         Self {
-            features: HashMap::new(),
+            features: BTreeMap::new(),
         }
     }
     
+    /// Start building a feature structure feature by feature
+    pub fn builder() -> FeatureStructureBuilder {
+        FeatureStructureBuilder::default()
+    }
+    
     /// Create a feature structure with a single feature
     pub fn with_feature(name: &str, value: FeatureValue) -> Self {
         let mut fs = Self::new();
@@ -72,48 +115,53 @@ impl FeatureStructure {
         self.features.get(name)
     }
     
-    /// Check if this feature structure unifies with another
-    pub fn unifies_with(&self, other: &FeatureStructure) -> bool {
-        // For each feature in this structure
-        for (name, value) in &self.features {
-            // If the other structure has this feature
-            if let Some(other_value) = other.features.get(name) {
-                // Check if the values unify
-                if !Self::values_unify(value, other_value) {
-                    return false;
-                }
+    /// Get a feature value by a dotted path through nested structures (e.g. `"agr.num"`)
+    pub fn get_path(&self, path: &str) -> Option<&FeatureValue> {
+        let mut names = path.split('.');
+        let mut value = self.features.get(names.next()?)?;
+        
+        for name in names {
+            match value {
+                FeatureValue::Complex(fs) => value = fs.features.get(name)?,
+                _ => return None,
             }
         }
-
-        // For each feature in the other structure not in this structure,
-        // we implicitly assume it unifies (open-world assumption)
-        true
+        
+        Some(value)
     }
-
-    /// Create a new feature structure by unifying two feature structures
-    pub fn unify(&self, other: &FeatureStructure) -> Option<FeatureStructure> {
-        if !self.unifies_with(other) {
-            return None;
-        }
-
-        let mut result = self.clone();
-
-        // Add features from other that are not in self
-        for (name, value) in &other.features {
-            if let Some(self_value) = self.features.get(name) {
-                // Both structures have this feature, need to unify values
-                if let Some(unified_value) = Self::unify_values(self_value, value) {
-                    result.features.insert(name.clone(), unified_value);
-                } else {
-                    return None; // Unification failure
+    
+    /// Set a feature value by a dotted path, creating nested structures as needed
+    ///
+    /// A non-complex value found along the path is replaced by a nested structure.
+    pub fn set_path(&mut self, path: &str, value: FeatureValue) {
+        match path.split_once('.') {
+            None => self.add(path, value),
+            Some((name, rest)) => {
+                let entry = self.features.entry(name.to_string())
+                    .or_insert_with(|| FeatureValue::Complex(Box::default()));
+                if !matches!(entry, FeatureValue::Complex(_)) {
+                    *entry = FeatureValue::Complex(Box::default());
                 }
-            } else {
-                // Only other has this feature, add it
-                result.features.insert(name.clone(), value.clone());
-            }
+                if let FeatureValue::Complex(fs) = entry {
+                    fs.set_path(rest, value);
+                }
+            },
         }
+    }
+    
+    /// Check if this feature structure unifies with another
+    pub fn unifies_with(&self, other: &FeatureStructure) -> bool {
+        self.unify(other).is_some()
+    }
 
-        Some(result)
+    /// Create a new feature structure by unifying two feature structures
+    ///
+    /// Nested structures are unified feature by feature, and every
+    /// occurrence of a variable is bound to the same value, so `[a=?x, b=?x]`
+    /// fails to unify with `[a=sg, b=pl]`. Features present in only one
+    /// structure are kept (open-world assumption).
+    pub fn unify(&self, other: &FeatureStructure) -> Option<FeatureStructure> {
+        self.unify_with_bindings(other, &mut Bindings::new())
     }
 
     /// Check if two feature values unify
@@ -194,6 +242,131 @@ impl FeatureStructure {
             _ => None,
         }
     }
+    
+    /// Generalize two feature structures (anti-unification)
+    ///
+    /// The result keeps the feature-value pairs both structures share and
+    /// drops any feature on which they conflict or that only one of them has,
+    /// giving the most specific structure that subsumes both.
+    pub fn generalize(&self, other: &FeatureStructure) -> FeatureStructure {
+        let mut result = FeatureStructure::new();
+        
+        for (name, value) in &self.features {
+            if let Some(other_value) = other.features.get(name) {
+                if let Some(general) = Self::generalize_values(value, other_value) {
+                    result.features.insert(name.clone(), general);
+                }
+            }
+        }
+        
+        result
+    }
+    
+    /// Generalize two feature values, returning `None` if only an
+    /// unspecified value would subsume both
+    fn generalize_values(v1: &FeatureValue, v2: &FeatureValue) -> Option<FeatureValue> {
+        if v1 == v2 {
+            return Some(v1.clone());
+        }
+        
+        match (v1, v2) {
+            (FeatureValue::Complex(fs1), FeatureValue::Complex(fs2)) => {
+                let general = fs1.generalize(fs2);
+                if general.features.is_empty() {
+                    None
+                } else {
+                    Some(FeatureValue::Complex(Box::new(general)))
+                }
+            },
+            // Any other mismatch, atomic or set, has nothing in common
+            _ => None,
+        }
+    }
+    
+    /// Unify with another feature structure, binding variables consistently
+    ///
+    /// Bindings made here are recorded in `bindings`, so a variable that
+    /// occurs elsewhere (e.g. in the result of a functor) can be resolved
+    /// afterwards with [`FeatureStructure::apply_bindings`].
+    pub fn unify_with_bindings(&self, other: &FeatureStructure, bindings: &mut Bindings) -> Option<FeatureStructure> {
+        let mut result = self.apply_bindings(bindings);
+        
+        for (name, value) in &other.features {
+            let unified = match result.features.get(name) {
+                Some(self_value) => Self::unify_values_with_bindings(name, self_value, value, bindings)?,
+                None => Self::resolve(value, bindings),
+            };
+            result.features.insert(name.clone(), unified);
+        }
+        
+        // Later bindings may refine values unified earlier
+        Some(result.apply_bindings(bindings))
+    }
+    
+    /// Replace every bound variable by its value
+    pub fn apply_bindings(&self, bindings: &Bindings) -> FeatureStructure {
+        FeatureStructure {
+            features: self.features.iter()
+                .map(|(name, value)| (name.clone(), Self::resolve(value, bindings)))
+                .collect(),
+        }
+    }
+    
+    /// Follow variable bindings until reaching an unbound variable or a value
+    fn resolve(value: &FeatureValue, bindings: &Bindings) -> FeatureValue {
+        match value {
+            FeatureValue::Variable(var) => match bindings.get(var) {
+                Some(bound) if bound != value => Self::resolve(bound, bindings),
+                _ => value.clone(),
+            },
+            FeatureValue::Complex(fs) => FeatureValue::Complex(Box::new(fs.apply_bindings(bindings))),
+            _ => value.clone(),
+        }
+    }
+    
+    /// Check whether a variable occurs inside a value
+    fn occurs(var: &str, value: &FeatureValue) -> bool {
+        match value {
+            FeatureValue::Variable(v) => v == var,
+            FeatureValue::Complex(fs) => fs.features.values().any(|value| Self::occurs(var, value)),
+            _ => false,
+        }
+    }
+    
+    /// Unify two values of a feature, recording any variable bindings
+    ///
+    /// With a registry in the bindings, atomic values and sets are first
+    /// expanded to the values they subsume.
+    fn unify_values_with_bindings(name: &str, v1: &FeatureValue, v2: &FeatureValue, bindings: &mut Bindings) -> Option<FeatureValue> {
+        let v1 = Self::resolve(v1, bindings);
+        let v2 = Self::resolve(v2, bindings);
+        
+        match (&v1, &v2) {
+            (FeatureValue::Variable(x), FeatureValue::Variable(y)) if x == y => Some(v1),
+            (FeatureValue::Variable(x), _) => {
+                if Self::occurs(x, &v2) {
+                    return None;
+                }
+                bindings.insert(x.clone(), v2.clone());
+                Some(v2)
+            },
+            (_, FeatureValue::Variable(y)) => {
+                if Self::occurs(y, &v1) {
+                    return None;
+                }
+                bindings.insert(y.clone(), v1.clone());
+                Some(v1)
+            },
+            (FeatureValue::Complex(fs1), FeatureValue::Complex(fs2)) => {
+                fs1.unify_with_bindings(fs2, bindings)
+                    .map(|fs| FeatureValue::Complex(Box::new(fs)))
+            },
+            _ => match bindings.registry {
+                Some(registry) => Self::unify_values(&registry.expand_value(name, &v1), &registry.expand_value(name, &v2)),
+                None => Self::unify_values(&v1, &v2),
+            },
+        }
+    }
 }
 
 impl fmt::Display for FeatureStructure {
@@ -255,23 +428,78 @@ impl hash::Hash for FeatureValue {
 // Add Hash implementation for FeatureStructure
 impl hash::Hash for FeatureStructure {
     fn hash<H: hash::Hasher>(&self, state: &mut H) {
-        // Create a sorted vector of entries to ensure consistent hashing
-        let mut entries: Vec<_> = self.features.iter().collect();
-        entries.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
-        
-        // Hash each entry in order
-        for (key, value) in entries {
+        // Entries are stored in key order, so hashing is deterministic
+        for (key, value) in &self.features {
             key.hash(state);
             value.hash(state);
         }
     }
 }
 
+/// A fluent builder for feature structures
+///
+/// Nested structures are built with [`FeatureStructureBuilder::sub`], e.g.
+/// `FeatureStructure::builder().str("case", "nom").sub("agr", |agr| agr.str("num", "sg")).build()`.
+#[derive(Debug, Clone, Default)]
+pub struct FeatureStructureBuilder {
+    structure: FeatureStructure,
+}
+
+impl FeatureStructureBuilder {
+    /// Add a feature with any value
+    pub fn value(mut self, name: &str, value: FeatureValue) -> Self {
+        self.structure.add(name, value);
+        self
+    }
+    
+    /// Add a feature with an atomic value
+    pub fn str(self, name: &str, value: &str) -> Self {
+        self.value(name, FeatureValue::Atomic(value.to_string()))
+    }
+    
+    /// Add a feature with a numeric atomic value
+    pub fn int(self, name: &str, value: i64) -> Self {
+        self.value(name, FeatureValue::Atomic(value.to_string()))
+    }
+    
+    /// Add a feature with a set of alternative values
+    pub fn set(self, name: &str, values: &[&str]) -> Self {
+        self.value(name, FeatureValue::Set(values.iter().map(|value| value.to_string()).collect()))
+    }
+    
+    /// Add a feature whose value is a unification variable
+    pub fn var(self, name: &str, variable: &str) -> Self {
+        self.value(name, FeatureValue::Variable(variable.to_string()))
+    }
+    
+    /// Add a feature with an unspecified value
+    pub fn unspecified(self, name: &str) -> Self {
+        self.value(name, FeatureValue::Unspecified)
+    }
+    
+    /// Add a feature with a nested structure built by `build`
+    pub fn sub(self, name: &str, build: impl FnOnce(FeatureStructureBuilder) -> FeatureStructureBuilder) -> Self {
+        let nested = build(FeatureStructureBuilder::default()).build();
+        self.value(name, FeatureValue::Complex(Box::new(nested)))
+    }
+    
+    /// Finish building the feature structure
+    pub fn build(self) -> FeatureStructure {
+        self.structure
+    }
+}
+
 /// Registry for features and their possible values
+///
+/// The values of a feature may be ordered by subsumption: an underspecified
+/// value (e.g. `nonfem`) subsumes the more specific values below it (`masc`,
+/// `neut`).
 #[derive(Debug, Clone)]
 pub struct FeatureRegistry {
     /// Feature names and their possible values
     pub features: HashMap<String, HashSet<String>>,
+    /// For each feature, the values immediately subsumed by each value
+    pub orders: HashMap<String, HashMap<String, HashSet<String>>>,
 }
 
 impl FeatureRegistry {
@@ -279,6 +507,7 @@ impl FeatureRegistry {
     pub fn new() -> Self {
         Self {
             features: HashMap::new(),
+            orders: HashMap::new(),
         }
     }
     
@@ -306,6 +535,89 @@ impl FeatureRegistry {
     pub fn get_values(&self, name: &str) -> Option<Vec<String>> {
         self.features.get(name).map(|set| set.iter().cloned().collect())
     }
+    
+    /// Register that a general value of a feature subsumes a more specific one
+    ///
+    /// Both values become valid values of the feature.
+    pub fn register_subsumption(&mut self, name: &str, general: &str, specific: &str) {
+        let values = self.features.entry(name.to_string()).or_default();
+        values.insert(general.to_string());
+        values.insert(specific.to_string());
+        
+        self.orders.entry(name.to_string())
+            .or_default()
+            .entry(general.to_string())
+            .or_default()
+            .insert(specific.to_string());
+    }
+    
+    /// Check if one value of a feature subsumes (is at least as general as) another
+    pub fn value_subsumes(&self, name: &str, general: &str, specific: &str) -> bool {
+        general == specific || self.subsumed_values(name, general).iter().any(|value| value == specific)
+    }
+    
+    /// Get every value strictly subsumed by a value of a feature, sorted
+    pub fn subsumed_values(&self, name: &str, value: &str) -> Vec<String> {
+        let order = match self.orders.get(name) {
+            Some(order) => order,
+            None => return Vec::new(),
+        };
+        
+        let mut found = HashSet::new();
+        let mut pending = vec![value.to_string()];
+        while let Some(current) = pending.pop() {
+            for below in order.get(&current).into_iter().flatten() {
+                if found.insert(below.clone()) {
+                    pending.push(below.clone());
+                }
+            }
+        }
+        
+        let mut values: Vec<String> = found.into_iter().collect();
+        values.sort();
+        values
+    }
+    
+    /// Get the feature value to store for a value of a feature
+    ///
+    /// An underspecified value becomes the set of itself and the values it
+    /// subsumes, so it unifies with any of them.
+    pub fn feature_value(&self, name: &str, value: &str) -> FeatureValue {
+        let mut values = self.subsumed_values(name, value);
+        if values.is_empty() {
+            return FeatureValue::Atomic(value.to_string());
+        }
+        
+        values.push(value.to_string());
+        values.sort();
+        FeatureValue::Set(values)
+    }
+    
+    /// Expand an atomic value or set of a feature to every value it subsumes
+    ///
+    /// Values that subsume nothing, and values other than atoms and sets,
+    /// are returned as they are.
+    pub fn expand_value(&self, name: &str, value: &FeatureValue) -> FeatureValue {
+        let members = match value {
+            FeatureValue::Atomic(atom) => return self.feature_value(name, atom),
+            FeatureValue::Set(members) => members,
+            _ => return value.clone(),
+        };
+        
+        let mut values = members.clone();
+        for member in members {
+            values.extend(self.subsumed_values(name, member));
+        }
+        values.sort();
+        values.dedup();
+        FeatureValue::Set(values)
+    }
+}
+
+impl Default for FeatureRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[cfg(test)]
@@ -390,4 +702,216 @@ mod tests {
             }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_unification_with_bindings() {
+        let mut bindings = Bindings::new();
+        
+        let pattern = FeatureStructure::with_feature("num", FeatureValue::Variable("n".to_string()));
+        let sg = FeatureStructure::with_feature("num", FeatureValue::Atomic("sg".to_string()));
+        
+        let unified = pattern.unify_with_bindings(&sg, &mut bindings);
+        assert_eq!(unified, Some(sg.clone()));
+        assert_eq!(bindings.get("n"), Some(&FeatureValue::Atomic("sg".to_string())));
+        
+        // The binding carries over to other occurrences of the variable
+        let result = FeatureStructure::with_feature("agr", FeatureValue::Variable("n".to_string()));
+        assert_eq!(result.apply_bindings(&bindings).get("agr"), Some(&FeatureValue::Atomic("sg".to_string())));
+        
+        // and constrains later unifications
+        let pl = FeatureStructure::with_feature("num", FeatureValue::Atomic("pl".to_string()));
+        assert!(pattern.unify_with_bindings(&pl, &mut bindings).is_none());
+    }
+
+    #[test]
+    fn test_generalization() {
+        let mut sg3 = FeatureStructure::new();
+        sg3.add("num", FeatureValue::Atomic("sg".to_string()));
+        sg3.add("per", FeatureValue::Atomic("3".to_string()));
+        
+        let mut pl3 = FeatureStructure::new();
+        pl3.add("num", FeatureValue::Atomic("pl".to_string()));
+        pl3.add("per", FeatureValue::Atomic("3".to_string()));
+        
+        // Conflicting features are dropped, shared ones kept
+        let general = sg3.generalize(&pl3);
+        assert_eq!(general, FeatureStructure::with_feature("per", FeatureValue::Atomic("3".to_string())));
+        
+        // The result subsumes both inputs
+        assert_eq!(general.unify(&sg3), Some(sg3.clone()));
+        assert_eq!(general.unify(&pl3), Some(pl3.clone()));
+        
+        // Features only one side has are dropped too
+        let num_only = FeatureStructure::with_feature("num", FeatureValue::Atomic("sg".to_string()));
+        assert_eq!(sg3.generalize(&num_only), num_only);
+        
+        // An atomic value and a set differing from it are dropped in either order
+        let either = FeatureStructure::with_feature("num", FeatureValue::Set(vec!["pl".to_string(), "sg".to_string()]));
+        assert_eq!(num_only.generalize(&either), FeatureStructure::new());
+        assert_eq!(either.generalize(&num_only), FeatureStructure::new());
+    }
+
+    #[test]
+    fn test_insertion_order_independence() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+        
+        let mut a = FeatureStructure::new();
+        a.add("per", FeatureValue::Atomic("3".to_string()));
+        a.add("num", FeatureValue::Atomic("sg".to_string()));
+        a.add("case", FeatureValue::Atomic("nom".to_string()));
+        
+        let mut b = FeatureStructure::new();
+        b.add("case", FeatureValue::Atomic("nom".to_string()));
+        b.add("num", FeatureValue::Atomic("sg".to_string()));
+        b.add("per", FeatureValue::Atomic("3".to_string()));
+        
+        assert_eq!(a, b);
+        assert_eq!(a.to_string(), b.to_string());
+        assert_eq!(a.to_string(), "[case=nom, num=sg, per=3]");
+        
+        let hash = |fs: &FeatureStructure| {
+            let mut hasher = DefaultHasher::new();
+            fs.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&a), hash(&b));
+    }
+
+    #[test]
+    fn test_nested_agreement_unification() {
+        let atomic = |s: &str| FeatureValue::Atomic(s.to_string());
+        
+        let subject = FeatureStructure::with_feature("agr", FeatureValue::Complex(Box::new(
+            FeatureStructure::with_feature("num", atomic("sg"))
+        )));
+        
+        let mut verb_agr = FeatureStructure::with_feature("per", atomic("3"));
+        verb_agr.add("num", FeatureValue::Set(vec!["sg".to_string(), "pl".to_string()]));
+        let verb = FeatureStructure::with_feature("agr", FeatureValue::Complex(Box::new(verb_agr)));
+        
+        // The nested bundles merge feature by feature
+        let unified = subject.unify(&verb).unwrap();
+        match unified.get("agr") {
+            Some(FeatureValue::Complex(agr)) => {
+                assert_eq!(agr.get("num"), Some(&atomic("sg")));
+                assert_eq!(agr.get("per"), Some(&atomic("3")));
+            },
+            other => panic!("Expected a nested agreement bundle, got {:?}", other),
+        }
+        
+        let plural = FeatureStructure::with_feature("agr", FeatureValue::Complex(Box::new(
+            FeatureStructure::with_feature("num", atomic("pl"))
+        )));
+        assert!(subject.unify(&plural).is_none());
+        assert!(!subject.unifies_with(&plural));
+    }
+
+    #[test]
+    fn test_shared_variable_unification() {
+        let atomic = |s: &str| FeatureValue::Atomic(s.to_string());
+        
+        let mut shared = FeatureStructure::new();
+        shared.add("num", FeatureValue::Variable("x".to_string()));
+        shared.add("subj_num", FeatureValue::Variable("x".to_string()));
+        
+        // Binding one occurrence of ?x binds the other
+        let unified = shared.unify(&FeatureStructure::with_feature("num", atomic("sg"))).unwrap();
+        assert_eq!(unified.get("subj_num"), Some(&atomic("sg")));
+        
+        let mut clash = FeatureStructure::new();
+        clash.add("num", atomic("sg"));
+        clash.add("subj_num", atomic("pl"));
+        assert!(shared.unify(&clash).is_none());
+        
+        let mut agree = FeatureStructure::new();
+        agree.add("num", atomic("pl"));
+        agree.add("subj_num", atomic("pl"));
+        assert!(shared.unify(&agree).is_some());
+    }
+
+    #[test]
+    fn test_path_access() {
+        let atomic = |s: &str| FeatureValue::Atomic(s.to_string());
+        
+        let mut fs = FeatureStructure::new();
+        fs.set_path("AGR.PER", atomic("3"));
+        fs.set_path("AGR.NUM", atomic("sg"));
+        fs.set_path("CASE", atomic("nom"));
+        
+        assert_eq!(fs.get_path("AGR.PER"), Some(&atomic("3")));
+        assert_eq!(fs.get_path("AGR.NUM"), Some(&atomic("sg")));
+        assert_eq!(fs.get_path("CASE"), Some(&atomic("nom")));
+        assert_eq!(fs.get_path("AGR.GEN"), None);
+        assert_eq!(fs.get_path("CASE.NUM"), None);
+        
+        // Both features live under the same nested structure
+        match fs.get("AGR") {
+            Some(FeatureValue::Complex(agr)) => assert_eq!(agr.features.len(), 2),
+            other => panic!("Expected a nested structure, got {:?}", other),
+        }
+        assert_eq!(fs.to_string(), "[AGR=[[NUM=sg, PER=3]], CASE=nom]");
+    }
+
+    #[test]
+    fn test_value_subsumption() {
+        let mut registry = FeatureRegistry::new();
+        registry.register_feature("num", &["sg", "pl"]);
+        registry.register_subsumption("num", "any", "sg");
+        registry.register_subsumption("num", "any", "pl");
+        
+        // Gender: nonfem subsumes masc and neut, which in turn are distinct
+        registry.register_subsumption("gen", "any", "fem");
+        registry.register_subsumption("gen", "any", "nonfem");
+        registry.register_subsumption("gen", "nonfem", "masc");
+        registry.register_subsumption("gen", "nonfem", "neut");
+        
+        assert!(registry.is_value_valid("num", "any"));
+        assert!(registry.is_value_valid("gen", "masc"));
+        assert!(registry.value_subsumes("num", "any", "pl"));
+        assert!(!registry.value_subsumes("num", "sg", "pl"));
+        assert!(registry.value_subsumes("gen", "any", "masc"));
+        assert!(!registry.value_subsumes("gen", "masc", "nonfem"));
+        assert_eq!(registry.subsumed_values("gen", "nonfem"), vec!["masc", "neut"]);
+        
+        // An underspecified value unifies with the values it subsumes only
+        let nonfem = FeatureStructure::with_feature("gen", registry.feature_value("gen", "nonfem"));
+        let masc = FeatureStructure::with_feature("gen", registry.feature_value("gen", "masc"));
+        let fem = FeatureStructure::with_feature("gen", registry.feature_value("gen", "fem"));
+        
+        assert_eq!(nonfem.unify(&masc).unwrap().get("gen"), Some(&FeatureValue::Atomic("masc".to_string())));
+        assert!(nonfem.unify(&fem).is_none());
+        assert!(masc.unify(&fem).is_none());
+        
+        // Unification under the registry expands stored atomic values itself
+        let atomic = |value: &str| FeatureStructure::with_feature("gen", FeatureValue::Atomic(value.to_string()));
+        let unify = |a: &FeatureStructure, b: &FeatureStructure| a.unify_with_bindings(b, &mut Bindings::with_registry(&registry));
+        assert!(atomic("nonfem").unify(&atomic("masc")).is_none());
+        assert_eq!(unify(&atomic("nonfem"), &atomic("masc")).unwrap().get("gen"), Some(&FeatureValue::Atomic("masc".to_string())));
+        assert_eq!(unify(&atomic("masc"), &atomic("any")).unwrap().get("gen"), Some(&FeatureValue::Atomic("masc".to_string())));
+        assert!(unify(&atomic("nonfem"), &atomic("fem")).is_none());
+    }
+    
+    #[test]
+    fn test_builder() {
+        let fs = FeatureStructure::builder()
+            .str("case", "nom")
+            .set("gen", &["masc", "neut"])
+            .var("tense", "t")
+            .sub("agr", |agr| agr.str("num", "sg").int("person", 3))
+            .build();
+        
+        let mut agr = FeatureStructure::new();
+        agr.add("num", FeatureValue::Atomic("sg".to_string()));
+        agr.add("person", FeatureValue::Atomic("3".to_string()));
+        let mut expected = FeatureStructure::new();
+        expected.add("case", FeatureValue::Atomic("nom".to_string()));
+        expected.add("gen", FeatureValue::Set(vec!["masc".to_string(), "neut".to_string()]));
+        expected.add("tense", FeatureValue::Variable("t".to_string()));
+        expected.add("agr", FeatureValue::Complex(Box::new(agr)));
+        
+        assert_eq!(fs, expected);
+        assert_eq!(fs.get_path("agr.num"), Some(&FeatureValue::Atomic("sg".to_string())));
+        assert!(FeatureStructure::builder().build().features.is_empty());
+    }
+}
@@ -0,0 +1,382 @@
+//! Packed parse forests shared across grammar formalisms
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fmt::Display;
+use crate::common::ParseNode;
+
+/// One way of deriving a forest node
+#[derive(Debug, Clone)]
+pub struct PackedDerivation<N> {
+    /// The rule used, or `None` for a lexical node
+    pub rule: Option<String>,
+    /// The ids of the child nodes, in order
+    pub children: Vec<usize>,
+    /// The score the derivation adds to those of its children
+    pub score: f64,
+    /// The node as first built by this derivation
+    pub node: N,
+}
+
+/// A node of a packed forest: a category over a span of the input
+#[derive(Debug, Clone)]
+pub struct ForestNode<N> {
+    /// The category label
+    pub label: String,
+    /// The first word covered
+    pub start: usize,
+    /// One past the last word covered
+    pub end: usize,
+    /// The word if this node is lexical
+    pub word: Option<String>,
+    /// The alternative derivations of this node
+    pub derivations: Vec<PackedDerivation<N>>,
+}
+
+/// A packed forest of parse trees
+///
+/// Nodes are identified by their label and span, so a subtree shared by
+/// several analyses is stored once and referenced by id. Each derivation
+/// carries a local score, and the score of a tree is the sum of the scores
+/// of its derivations, so log-probabilities give the most probable trees
+/// first and uniform scores of zero keep every tree equal.
+#[derive(Debug, Clone)]
+pub struct Forest<N> {
+    /// All nodes of the forest
+    nodes: Vec<ForestNode<N>>,
+    /// The nodes spanning the whole input
+    roots: Vec<usize>,
+    /// Map from label and span to node id
+    index: HashMap<(String, usize, usize), usize>,
+}
+
+impl<N> Default for Forest<N> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            roots: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+}
+
+impl<N> Forest<N>
+where
+    N: ParseNode,
+    N::Cat: Display,
+{
+    /// Create a new empty forest
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a forest by packing a set of parse trees that all score zero
+    pub fn from_trees(trees: &[N]) -> Self {
+        Self::from_scored_trees(trees, |_| 0.0)
+    }
+
+    /// Build a forest by packing a set of parse trees
+    ///
+    /// `score` gives the local score of the derivation of each node.
+    pub fn from_scored_trees(trees: &[N], score: impl Fn(&N) -> f64) -> Self {
+        let mut forest = Forest::new();
+        for tree in trees {
+            let (root, _) = forest.add_tree(tree, 0, &score);
+            if !forest.roots.contains(&root) {
+                forest.roots.push(root);
+            }
+        }
+        forest
+    }
+
+    /// Add a tree starting at the given word, returning its node id and end
+    fn add_tree(&mut self, tree: &N, start: usize, score: &dyn Fn(&N) -> f64) -> (usize, usize) {
+        let label = tree.category().to_string();
+
+        if tree.is_leaf() {
+            let id = self.add_node(&label, start, start + 1);
+            self.nodes[id].word = tree.word().map(str::to_string);
+            self.add_derivation(id, tree.clone(), Vec::new(), score(tree));
+            return (id, start + 1);
+        }
+
+        let mut children = Vec::new();
+        let mut end = start;
+        for child in tree.children() {
+            let (child_id, child_end) = self.add_tree(child, end, score);
+            children.push(child_id);
+            end = child_end;
+        }
+
+        let id = self.add_node(&label, start, end);
+        self.add_derivation(id, tree.clone(), children, score(tree));
+        (id, end)
+    }
+
+    /// Get the node with a label and span, adding it if it does not exist
+    pub fn add_node(&mut self, label: &str, start: usize, end: usize) -> usize {
+        let key = (label.to_string(), start, end);
+        if let Some(&id) = self.index.get(&key) {
+            return id;
+        }
+
+        let id = self.nodes.len();
+        self.nodes.push(ForestNode {
+            label: label.to_string(),
+            start,
+            end,
+            word: None,
+            derivations: Vec::new(),
+        });
+        self.index.insert(key, id);
+        id
+    }
+
+    /// Add an alternative derivation of a node by the rule of `node`, ignoring duplicates
+    ///
+    /// Derivations are told apart by their rule and children, so a duplicate
+    /// keeps the node and score it was first added with.
+    pub fn add_derivation(&mut self, id: usize, node: N, children: Vec<usize>, score: f64) {
+        let rule = node.rule().map(str::to_string);
+        let duplicate = self.nodes[id].derivations.iter()
+            .any(|derivation| derivation.rule == rule && derivation.children == children);
+        if !duplicate {
+            self.nodes[id].derivations.push(PackedDerivation { rule, children, score, node });
+        }
+    }
+
+    /// Get a node by id
+    pub fn node(&self, id: usize) -> &ForestNode<N> {
+        &self.nodes[id]
+    }
+
+    /// Get all nodes of the forest
+    pub fn nodes(&self) -> &[ForestNode<N>] {
+        &self.nodes
+    }
+
+    /// Get the ids of the nodes spanning the whole input
+    pub fn roots(&self) -> &[usize] {
+        &self.roots
+    }
+
+    /// Find the node with a label and span
+    pub fn find(&self, label: &str, start: usize, end: usize) -> Option<usize> {
+        self.index.get(&(label.to_string(), start, end)).copied()
+    }
+
+    /// Get the number of nodes in the forest
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Check if the forest is empty
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Count the trees packed in the forest
+    pub fn count_trees(&self) -> usize {
+        let mut counts = HashMap::new();
+        self.roots.iter().map(|&root| self.count_from(root, &mut counts)).sum()
+    }
+
+    fn count_from(&self, id: usize, counts: &mut HashMap<usize, usize>) -> usize {
+        if let Some(&count) = counts.get(&id) {
+            return count;
+        }
+
+        let count = self.nodes[id].derivations.iter()
+            .map(|derivation| derivation.children.iter()
+                .map(|&child| self.count_from(child, counts))
+                .product::<usize>())
+            .sum();
+
+        counts.insert(id, count);
+        count
+    }
+}
+
+/// Parse nodes that can be rebuilt over other children
+///
+/// Extracting trees from a [`Forest`] combines the derivation of a node
+/// with different analyses of its children.
+pub trait Rebuild: ParseNode {
+    /// Get a copy of this node over new children, in the order of [`ParseNode::children`]
+    fn with_children(&self, children: Vec<Self>) -> Self;
+}
+
+impl<N> Forest<N>
+where
+    N: Rebuild,
+    N::Cat: Display,
+{
+    /// Extract the `k` highest scoring trees with their scores, best first
+    ///
+    /// Trees are enumerated lazily in the manner of cube pruning: the next
+    /// best analysis of a node is only computed when a larger tree asks for
+    /// it, so only as much of the forest is explored as the `k` trees need.
+    pub fn kbest(&self, k: usize) -> Vec<(N, f64)> {
+        let mut kbest = KBest::new(self.nodes.len());
+        let mut trees = Vec::new();
+        for &root in &self.roots {
+            for rank in 0..k {
+                match kbest.score(self, root, rank) {
+                    Some(score) => trees.push((root, rank, score)),
+                    None => break,
+                }
+            }
+        }
+        
+        trees.sort_by(|a, b| b.2.total_cmp(&a.2));
+        trees.truncate(k);
+        trees.into_iter()
+            .map(|(root, rank, score)| (kbest.build(self, root, rank), score))
+            .collect()
+    }
+}
+
+/// A candidate analysis: a derivation of a node with a rank for each child
+#[derive(Debug, Clone)]
+struct Candidate {
+    score: f64,
+    order: usize,
+    derivation: usize,
+    ranks: Vec<usize>,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Higher score first, then first found first
+        self.score.total_cmp(&other.score)
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
+
+/// The analyses of each node found so far during k-best extraction
+struct KBest {
+    /// The analyses of each node, best first
+    found: Vec<Vec<Candidate>>,
+    /// The candidates waiting to be found, once a node has been visited
+    frontier: Vec<Option<BinaryHeap<Candidate>>>,
+    /// The derivations and ranks already queued at each node
+    queued: Vec<HashSet<(usize, Vec<usize>)>>,
+    /// The number of candidates queued so far
+    order: usize,
+}
+
+impl KBest {
+    fn new(nodes: usize) -> Self {
+        KBest {
+            found: vec![Vec::new(); nodes],
+            frontier: vec![None; nodes],
+            queued: vec![HashSet::new(); nodes],
+            order: 0,
+        }
+    }
+    
+    /// Get the score of the analysis of a node with the given rank, if it has that many
+    fn score<N>(&mut self, forest: &Forest<N>, node: usize, rank: usize) -> Option<f64> {
+        if self.frontier[node].is_none() {
+            self.frontier[node] = Some(BinaryHeap::new());
+            for derivation in 0..forest.nodes[node].derivations.len() {
+                let ranks = vec![0; forest.nodes[node].derivations[derivation].children.len()];
+                self.queue(forest, node, derivation, ranks);
+            }
+        }
+        
+        while self.found[node].len() <= rank {
+            // The successors of the last analysis found are only needed now
+            if let Some(last) = self.found[node].last().cloned() {
+                for child in 0..last.ranks.len() {
+                    let mut ranks = last.ranks.clone();
+                    ranks[child] += 1;
+                    self.queue(forest, node, last.derivation, ranks);
+                }
+            }
+            
+            let best = self.frontier[node].as_mut().and_then(BinaryHeap::pop)?;
+            self.found[node].push(best);
+        }
+        Some(self.found[node][rank].score)
+    }
+    
+    /// Queue a derivation of a node over the children's analyses of the given ranks
+    fn queue<N>(&mut self, forest: &Forest<N>, node: usize, derivation: usize, ranks: Vec<usize>) {
+        if !self.queued[node].insert((derivation, ranks.clone())) {
+            return;
+        }
+        
+        let scored = &forest.nodes[node].derivations[derivation];
+        let mut score = scored.score;
+        for (&child, &rank) in scored.children.iter().zip(&ranks) {
+            match self.score(forest, child, rank) {
+                Some(child_score) => score += child_score,
+                None => return,
+            }
+        }
+        
+        self.order += 1;
+        let candidate = Candidate { score, order: self.order, derivation, ranks };
+        if let Some(frontier) = self.frontier[node].as_mut() {
+            frontier.push(candidate);
+        }
+    }
+    
+    /// Rebuild the analysis of a node with the given rank
+    fn build<N: Rebuild>(&self, forest: &Forest<N>, node: usize, rank: usize) -> N {
+        let candidate = &self.found[node][rank];
+        let scored = &forest.nodes[node].derivations[candidate.derivation];
+        let children = scored.children.iter().zip(&candidate.ranks)
+            .map(|(&child, &rank)| self.build(forest, child, rank))
+            .collect();
+        scored.node.with_children(children)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ccg::category::CCGCategory;
+    use crate::ccg::node::CCGNode;
+
+    #[test]
+    fn test_shared_nodes() {
+        let a_leaf = CCGNode::leaf("a", CCGCategory::atomic("A"));
+        let b_leaf = CCGNode::leaf("b", CCGCategory::atomic("B"));
+        let forward = CCGNode::internal(CCGCategory::s(), vec![a_leaf.clone(), b_leaf.clone()], ">");
+        let backward = CCGNode::internal(CCGCategory::s(), vec![a_leaf.clone(), b_leaf.clone()], "<");
+
+        let mut forest = Forest::new();
+        let a = forest.add_node("A", 0, 1);
+        let b = forest.add_node("B", 1, 2);
+        forest.add_derivation(a, a_leaf, vec![], 0.0);
+        forest.add_derivation(b, b_leaf, vec![], 0.0);
+
+        let root = forest.add_node("S", 0, 2);
+        forest.add_derivation(root, forward.clone(), vec![a, b], 0.0);
+        forest.add_derivation(root, backward, vec![a, b], 0.0);
+        forest.add_derivation(root, forward, vec![a, b], 0.0);
+        forest.roots.push(root);
+
+        assert_eq!(forest.add_node("A", 0, 1), a);
+        assert_eq!(forest.len(), 3);
+        assert_eq!(forest.node(root).derivations.len(), 2);
+        assert_eq!(forest.count_trees(), 2);
+        assert_eq!(forest.kbest(5).len(), 2);
+    }
+}
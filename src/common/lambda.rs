@@ -0,0 +1,226 @@
+//! Lambda terms for the semantics of derivations
+//!
+//! Through the Curry-Howard correspondence every natural deduction proof
+//! determines a term of the simply typed lambda calculus: eliminations of
+//! implications are applications, introductions are abstractions, and
+//! products are pairs. Minimalist derivations compose the same terms.
+
+use std::collections::HashSet;
+use std::fmt;
+
+/// A term of the lambda calculus
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LambdaTerm {
+    /// A constant contributed by a lexical entry
+    Const(String),
+    /// A variable bound by an abstraction
+    Var(String),
+    /// Function application
+    App(Box<LambdaTerm>, Box<LambdaTerm>),
+    /// Lambda abstraction
+    Abs(String, Box<LambdaTerm>),
+    /// Pairing
+    Pair(Box<LambdaTerm>, Box<LambdaTerm>),
+    /// First projection of a pair
+    Fst(Box<LambdaTerm>),
+    /// Second projection of a pair
+    Snd(Box<LambdaTerm>),
+}
+
+impl fmt::Display for LambdaTerm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LambdaTerm::Const(name) | LambdaTerm::Var(name) => write!(f, "{}", name),
+            LambdaTerm::App(fun, arg) => {
+                if let LambdaTerm::Abs(_, _) = **fun {
+                    write!(f, "({})({})", fun, arg)
+                } else {
+                    write!(f, "{}({})", fun, arg)
+                }
+            },
+            LambdaTerm::Abs(var, body) => write!(f, "λ{}.{}", var, body),
+            LambdaTerm::Pair(first, second) => write!(f, "⟨{}, {}⟩", first, second),
+            LambdaTerm::Fst(term) => write!(f, "π1({})", term),
+            LambdaTerm::Snd(term) => write!(f, "π2({})", term),
+        }
+    }
+}
+
+impl LambdaTerm {
+    /// Create a constant
+    pub fn constant(name: &str) -> Self {
+        LambdaTerm::Const(name.to_string())
+    }
+
+    /// Create a variable
+    pub fn var(name: &str) -> Self {
+        LambdaTerm::Var(name.to_string())
+    }
+
+    /// Create an application
+    pub fn app(fun: LambdaTerm, arg: LambdaTerm) -> Self {
+        LambdaTerm::App(Box::new(fun), Box::new(arg))
+    }
+
+    /// Create an abstraction
+    pub fn abs(var: &str, body: LambdaTerm) -> Self {
+        LambdaTerm::Abs(var.to_string(), Box::new(body))
+    }
+
+    /// Create a pair
+    pub fn pair(first: LambdaTerm, second: LambdaTerm) -> Self {
+        LambdaTerm::Pair(Box::new(first), Box::new(second))
+    }
+
+    /// Get the free variables of this term
+    pub fn free_vars(&self) -> HashSet<String> {
+        match self {
+            LambdaTerm::Const(_) => HashSet::new(),
+            LambdaTerm::Var(name) => std::iter::once(name.clone()).collect(),
+            LambdaTerm::App(a, b) | LambdaTerm::Pair(a, b) => {
+                let mut vars = a.free_vars();
+                vars.extend(b.free_vars());
+                vars
+            },
+            LambdaTerm::Abs(var, body) => {
+                let mut vars = body.free_vars();
+                vars.remove(var);
+                vars
+            },
+            LambdaTerm::Fst(term) | LambdaTerm::Snd(term) => term.free_vars(),
+        }
+    }
+
+    /// Substitute a term for the free occurrences of a variable, renaming
+    /// bound variables where necessary to avoid capture
+    pub fn substitute(&self, var: &str, replacement: &LambdaTerm) -> LambdaTerm {
+        match self {
+            LambdaTerm::Const(_) => self.clone(),
+            LambdaTerm::Var(name) => {
+                if name == var {
+                    replacement.clone()
+                } else {
+                    self.clone()
+                }
+            },
+            LambdaTerm::App(a, b) => LambdaTerm::app(
+                a.substitute(var, replacement),
+                b.substitute(var, replacement),
+            ),
+            LambdaTerm::Pair(a, b) => LambdaTerm::pair(
+                a.substitute(var, replacement),
+                b.substitute(var, replacement),
+            ),
+            LambdaTerm::Fst(term) => LambdaTerm::Fst(Box::new(term.substitute(var, replacement))),
+            LambdaTerm::Snd(term) => LambdaTerm::Snd(Box::new(term.substitute(var, replacement))),
+            LambdaTerm::Abs(bound, body) => {
+                if bound == var {
+                    return self.clone();
+                }
+
+                let replacement_vars = replacement.free_vars();
+                if !replacement_vars.contains(bound) {
+                    return LambdaTerm::abs(bound, body.substitute(var, replacement));
+                }
+
+                // Rename the bound variable to something fresh
+                let mut avoid = body.free_vars();
+                avoid.extend(replacement_vars);
+                let mut fresh = format!("{}'", bound);
+                while avoid.contains(&fresh) {
+                    fresh.push('\'');
+                }
+
+                let renamed = body.substitute(bound, &LambdaTerm::Var(fresh.clone()));
+                LambdaTerm::abs(&fresh, renamed.substitute(var, replacement))
+            },
+        }
+    }
+
+    /// Perform a single leftmost-outermost reduction step, if any redex exists
+    fn reduce_step(&self) -> Option<LambdaTerm> {
+        match self {
+            LambdaTerm::App(fun, arg) => {
+                if let LambdaTerm::Abs(var, body) = &**fun {
+                    return Some(body.substitute(var, arg));
+                }
+
+                if let Some(fun) = fun.reduce_step() {
+                    return Some(LambdaTerm::App(Box::new(fun), arg.clone()));
+                }
+
+                arg.reduce_step().map(|arg| LambdaTerm::App(fun.clone(), Box::new(arg)))
+            },
+            LambdaTerm::Abs(var, body) => {
+                body.reduce_step().map(|body| LambdaTerm::abs(var, body))
+            },
+            LambdaTerm::Pair(a, b) => {
+                if let Some(a) = a.reduce_step() {
+                    return Some(LambdaTerm::Pair(Box::new(a), b.clone()));
+                }
+
+                b.reduce_step().map(|b| LambdaTerm::Pair(a.clone(), Box::new(b)))
+            },
+            LambdaTerm::Fst(term) => {
+                if let LambdaTerm::Pair(a, _) = &**term {
+                    return Some((**a).clone());
+                }
+
+                term.reduce_step().map(|term| LambdaTerm::Fst(Box::new(term)))
+            },
+            LambdaTerm::Snd(term) => {
+                if let LambdaTerm::Pair(_, b) = &**term {
+                    return Some((**b).clone());
+                }
+
+                term.reduce_step().map(|term| LambdaTerm::Snd(Box::new(term)))
+            },
+            LambdaTerm::Const(_) | LambdaTerm::Var(_) => None,
+        }
+    }
+
+    /// Reduce this term to beta normal form
+    ///
+    /// Terms built from linear proofs are strongly normalizing, so this
+    /// always terminates for terms extracted from TLG derivations.
+    pub fn beta_normalize(&self) -> LambdaTerm {
+        let mut term = self.clone();
+        while let Some(next) = term.reduce_step() {
+            term = next;
+        }
+        term
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_beta_reduction() {
+        // (λx.sleeps(x))(john) ~> sleeps(john)
+        let term = LambdaTerm::app(
+            LambdaTerm::abs("x", LambdaTerm::app(LambdaTerm::constant("sleeps"), LambdaTerm::var("x"))),
+            LambdaTerm::constant("john"),
+        );
+
+        assert_eq!(term.beta_normalize().to_string(), "sleeps(john)");
+
+        // Projections of pairs reduce as well
+        let pair = LambdaTerm::pair(LambdaTerm::constant("a"), LambdaTerm::constant("b"));
+        assert_eq!(LambdaTerm::Snd(Box::new(pair)).beta_normalize(), LambdaTerm::constant("b"));
+    }
+
+    #[test]
+    fn test_capture_avoiding_substitution() {
+        // (λx.λy.x)(y) must not capture the free y
+        let term = LambdaTerm::app(
+            LambdaTerm::abs("x", LambdaTerm::abs("y", LambdaTerm::var("x"))),
+            LambdaTerm::var("y"),
+        );
+
+        let normal = term.beta_normalize();
+        assert_eq!(normal, LambdaTerm::abs("y'", LambdaTerm::var("y")));
+        assert!(normal.free_vars().contains("y"));
+    }
+}
@@ -1,16 +1,25 @@
 //! Generic lexicon implementation for any grammar formalism
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::hash::Hash;
 
 /// Generic lexicon that maps words to their possible categories in a grammar formalism
+///
+/// Each category carries a weight (e.g. a frequency). Categories are returned
+/// by descending weight, and in insertion order among equal weights. An entry
+/// may span several words ("New York"), stored with its words separated by
+/// single spaces; with case folding, words are looked up ignoring case.
 #[derive(Debug, Clone)]
 pub struct Lexicon<C> 
 where
     C: Clone + PartialEq + Eq + Hash
 {
-    /// Map from words to their possible categories
-    entries: HashMap<String, HashSet<C>>,
+    /// Map from words to their possible categories and weights, in insertion order
+    entries: HashMap<String, Vec<(C, f64)>>,
+    /// Whether words are lowercased before they are stored or looked up
+    case_folding: bool,
+    /// The number of words in the longest entry
+    max_phrase_len: usize,
 }
 
 impl<C> Lexicon<C> 
@@ -21,28 +30,118 @@ where
     pub fn new() -> Self {
         Lexicon {
             entries: HashMap::new(),
+            case_folding: false,
+            max_phrase_len: 0,
         }
     }
+    
+    /// Create a lexicon with case folding switched on or off
+    pub fn with_case_folding(mut self, case_folding: bool) -> Self {
+        self.set_case_folding(case_folding);
+        self
+    }
+    
+    /// Switch case folding on or off
+    ///
+    /// Switching it on merges the entries of words that differ only in case.
+    pub fn set_case_folding(&mut self, case_folding: bool) {
+        self.case_folding = case_folding;
+        if case_folding {
+            for (word, categories) in std::mem::take(&mut self.entries) {
+                for (category, weight) in categories {
+                    self.add_with_weight(&word, category, weight);
+                }
+            }
+        }
+    }
+    
+    /// Check whether words are looked up ignoring case
+    pub fn is_case_folding(&self) -> bool {
+        self.case_folding
+    }
+    
+    /// Get the key a word is stored under
+    fn key(&self, word: &str) -> String {
+        if self.case_folding {
+            word.to_lowercase()
+        } else {
+            word.to_string()
+        }
+    }
+    
+    /// Get the categories stored for a word, creating the entry if needed
+    fn entry(&mut self, word: &str) -> &mut Vec<(C, f64)> {
+        let key = self.key(word);
+        self.max_phrase_len = self.max_phrase_len.max(key.split(' ').count());
+        self.entries.entry(key).or_default()
+    }
 
-    /// Add a word with its category to the lexicon
+    /// Add a word with its category to the lexicon, with weight 0
     pub fn add(&mut self, word: &str, category: C) {
-        self.entries
-            .entry(word.to_string())
-            .or_insert_with(HashSet::new)
-            .insert(category);
+        let categories = self.entry(word);
+        if !categories.iter().any(|(existing, _)| *existing == category) {
+            categories.push((category, 0.0));
+        }
     }
 
-    /// Get all possible categories for a word
+    /// Add a word with a weighted category, updating the weight if the category exists
+    pub fn add_with_weight(&mut self, word: &str, category: C, weight: f64) {
+        let categories = self.entry(word);
+        match categories.iter_mut().find(|(existing, _)| *existing == category) {
+            Some(entry) => entry.1 = weight,
+            None => categories.push((category, weight)),
+        }
+    }
+
+    /// Get all possible categories for a word, by descending weight
     pub fn get_categories(&self, word: &str) -> Vec<C> {
-        match self.entries.get(word) {
-            Some(categories) => categories.iter().cloned().collect(),
+        match self.entries.get(&self.key(word)) {
+            Some(categories) => {
+                let mut weighted: Vec<&(C, f64)> = categories.iter().collect();
+                // The sort is stable, so equal weights keep their insertion order
+                weighted.sort_by(|(_, w1), (_, w2)| w2.total_cmp(w1));
+                weighted.into_iter().map(|(category, _)| category.clone()).collect()
+            },
             None => vec![],
         }
     }
     
+    /// Get the weight of a category for a word
+    pub fn weight(&self, word: &str, category: &C) -> Option<f64> {
+        self.entries.get(&self.key(word))?
+            .iter()
+            .find(|(existing, _)| existing == category)
+            .map(|(_, weight)| *weight)
+    }
+    
     /// Check if a word is in the lexicon
     pub fn contains(&self, word: &str) -> bool {
-        self.entries.contains_key(word)
+        self.entries.contains_key(&self.key(word))
+    }
+    
+    /// Add a multi-word entry with its category, with weight 0
+    pub fn add_phrase(&mut self, words: &[&str], category: C) {
+        self.add(&words.join(" "), category);
+    }
+    
+    /// Get the number of words in the longest entry at the start of `words`
+    pub fn longest_match(&self, words: &[&str]) -> Option<usize> {
+        (1..=self.max_phrase_len.min(words.len())).rev()
+            .find(|&len| self.contains(&words[..len].join(" ")))
+    }
+    
+    /// Group tokens into the multi-word entries of the lexicon, longest first
+    ///
+    /// Tokens that do not start a multi-word entry are kept as they are.
+    pub fn group_phrases(&self, tokens: &[String]) -> Vec<String> {
+        self.group_phrases_with_spans(tokens).into_iter().map(|(phrase, _)| phrase).collect()
+    }
+    
+    /// Group tokens into the multi-word entries of the lexicon, with the tokens each covers
+    ///
+    /// Each group comes with its `[start, end)` span of token indices.
+    pub fn group_phrases_with_spans(&self, tokens: &[String]) -> Vec<(String, (usize, usize))> {
+        group_tokens(tokens, self.max_phrase_len, |phrase| self.contains(phrase))
     }
     
     /// Get the number of entries in the lexicon
@@ -62,15 +161,16 @@ where
     
     /// Remove a word from the lexicon
     pub fn remove(&mut self, word: &str) {
-        self.entries.remove(word);
+        self.entries.remove(&self.key(word));
     }
     
     /// Remove a specific category for a word
     pub fn remove_category(&mut self, word: &str, category: &C) {
-        if let Some(categories) = self.entries.get_mut(word) {
-            categories.remove(category);
+        let key = self.key(word);
+        if let Some(categories) = self.entries.get_mut(&key) {
+            categories.retain(|(existing, _)| existing != category);
             if categories.is_empty() {
-                self.entries.remove(word);
+                self.entries.remove(&key);
             }
         }
     }
@@ -78,17 +178,19 @@ where
     /// Clear the lexicon
     pub fn clear(&mut self) {
         self.entries.clear();
+        self.max_phrase_len = 0;
     }
     
     /// Get an iterator over all entries in the lexicon
-    pub fn iter(&self) -> impl Iterator<Item = (&String, &HashSet<C>)> {
+    pub fn iter(&self) -> impl Iterator<Item = (&String, Vec<&C>)> {
         self.entries.iter()
+            .map(|(word, categories)| (word, categories.iter().map(|(category, _)| category).collect()))
     }
     
     /// Check if a word has a specific category
     pub fn has_category(&self, word: &str, category: &C) -> bool {
-        if let Some(categories) = self.entries.get(word) {
-            categories.contains(category)
+        if let Some(categories) = self.entries.get(&self.key(word)) {
+            categories.iter().any(|(existing, _)| existing == category)
         } else {
             false
         }
@@ -105,6 +207,23 @@ where
     }
 }
 
+/// Group tokens into the longest phrases of at most `max_phrase_len` words accepted by `contains`
+///
+/// Each group comes with its `[start, end)` span of token indices; tokens
+/// that do not start an accepted phrase form a group of their own.
+pub fn group_tokens(tokens: &[String], max_phrase_len: usize, contains: impl Fn(&str) -> bool) -> Vec<(String, (usize, usize))> {
+    let mut grouped = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let len = (2..=max_phrase_len.min(tokens.len() - i)).rev()
+            .find(|&len| contains(&tokens[i..i + len].join(" ")))
+            .unwrap_or(1);
+        grouped.push((tokens[i..i + len].join(" "), (i, i + len)));
+        i += len;
+    }
+    grouped
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -176,4 +295,64 @@ mod tests {
         assert!(lexicon.contains("bank"));
         assert_eq!(lexicon.get_categories("bank").len(), 1);
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_weighted_categories() {
+        let mut lexicon = Lexicon::new();
+        
+        // Without weights, categories come back in insertion order
+        lexicon.add("fast", TestCategory::Verb);
+        lexicon.add("fast", TestCategory::Adjective);
+        lexicon.add("fast", TestCategory::Noun);
+        assert_eq!(lexicon.get_categories("fast"), vec![
+            TestCategory::Verb,
+            TestCategory::Adjective,
+            TestCategory::Noun,
+        ]);
+        
+        // Heavier categories come first; ties keep insertion order
+        lexicon.add_with_weight("fast", TestCategory::Noun, 2.0);
+        lexicon.add_with_weight("fast", TestCategory::Adjective, 2.0);
+        assert_eq!(lexicon.get_categories("fast"), vec![
+            TestCategory::Adjective,
+            TestCategory::Noun,
+            TestCategory::Verb,
+        ]);
+        assert_eq!(lexicon.weight("fast", &TestCategory::Noun), Some(2.0));
+        assert_eq!(lexicon.weight("fast", &TestCategory::Verb), Some(0.0));
+        
+        // Re-adding a category does not duplicate it
+        lexicon.add("fast", TestCategory::Verb);
+        assert_eq!(lexicon.get_categories("fast").len(), 3);
+    }
+    
+    #[test]
+    fn test_case_folding() {
+        let mut lexicon = Lexicon::new();
+        lexicon.add("the", TestCategory::Adjective);
+        assert!(!lexicon.contains("The"));
+        
+        lexicon.set_case_folding(true);
+        lexicon.add("THE", TestCategory::Noun);
+        assert!(lexicon.contains("The"));
+        assert_eq!(lexicon.get_categories("The"), vec![TestCategory::Adjective, TestCategory::Noun]);
+        assert_eq!(lexicon.len(), 1);
+    }
+    
+    #[test]
+    fn test_phrases() {
+        let mut lexicon = Lexicon::new();
+        lexicon.add("new", TestCategory::Adjective);
+        lexicon.add("york", TestCategory::Noun);
+        lexicon.add_phrase(&["New", "York"], TestCategory::Noun);
+        
+        assert_eq!(lexicon.longest_match(&["New", "York", "sleeps"]), Some(2));
+        assert_eq!(lexicon.longest_match(&["new", "York"]), Some(1));
+        assert_eq!(lexicon.longest_match(&["old"]), None);
+        
+        let tokens: Vec<String> = ["I", "love", "New", "York"].iter().map(|t| t.to_string()).collect();
+        assert_eq!(lexicon.group_phrases(&tokens), vec!["I", "love", "New York"]);
+        assert_eq!(lexicon.group_phrases_with_spans(&tokens)[2], ("New York".to_string(), (2, 4)));
+        assert_eq!(lexicon.get_categories("New York"), vec![TestCategory::Noun]);
+    }
+}
@@ -1,14 +1,32 @@
 //! Common data structures and functionality shared across grammar formalisms
 
+pub mod budget;
+pub mod chart;
 pub mod feature;
 pub mod lexicon;
+pub mod morphology;
 pub mod registry;
+pub mod tokenize;
 pub mod error;
+pub mod eval;
+pub mod forest;
+pub mod export;
+pub mod trace;
+pub mod lambda;
 
-pub use feature::{FeatureValue, FeatureStructure, FeatureRegistry};
+pub use budget::{ParseOutcome, StepBudget};
+pub use chart::Agenda;
+pub use feature::{FeatureValue, FeatureStructure, FeatureStructureBuilder, FeatureRegistry, Bindings};
 pub use lexicon::Lexicon;
+pub use morphology::{Morphology, EnglishSuffixAnalyzer};
 pub use registry::AtomicTypeRegistry;
+pub use tokenize::{Tokenizer, SimpleTokenizer};
 pub use error::Error;
+pub use eval::{coverage, CoverageReport};
+pub use forest::{Forest, Rebuild};
+pub use export::{ToConll, ConllToken};
+pub use trace::{Step, StepKind, StepTracer, TraceHandle, Tracer, VecTracer};
+pub use lambda::LambdaTerm;
 
 /// Trait representing a grammatical category
 /// 
@@ -85,6 +103,23 @@ pub trait Parser {
     /// Add a word with a category to the lexicon
     fn add_to_lexicon(&mut self, word: &str, category: Self::Cat);
     
+    /// Get the lexicon of this parser, if it keeps one of the generic type
+    fn lexicon(&self) -> Option<&Lexicon<Self::Cat>> {
+        None
+    }
+    
+    /// Get the words in the lexicon, in alphabetical order
+    fn lexicon_words(&self) -> Vec<String> {
+        let mut words = self.lexicon().map(Lexicon::get_words).unwrap_or_default();
+        words.sort();
+        words
+    }
+    
+    /// Get the categories of a word in the lexicon
+    fn lexicon_entries(&self, word: &str) -> Vec<Self::Cat> {
+        self.lexicon().map(|lexicon| lexicon.get_categories(word)).unwrap_or_default()
+    }
+    
     /// Get the configuration of this parser
     fn config(&self) -> &Self::Config;
     
@@ -94,8 +129,40 @@ pub trait Parser {
     /// Create a category with features
     fn create_category_with_features(&self, name: &str, features: &[(&str, &str)]) -> Result<Self::Cat, Error>;
     
+    /// Parse a sentence, reporting why it failed if there is no parse
+    ///
+    /// Parsers that look words up in a lexicon report the first unknown
+    /// word as [`Error::UnknownWord`]; otherwise a failure is [`Error::NoParse`].
+    fn parse_result(&self, sentence: &str) -> Result<Self::Node, Error> {
+        self.parse(sentence).ok_or_else(|| Error::NoParse { sentence: sentence.to_string() })
+    }
+    
+    /// Parse a sentence within a budget of search steps
+    ///
+    /// Parsers without a bounded search ignore the budget and report a
+    /// failed parse as [`ParseOutcome::Exhausted`].
+    fn parse_within(&self, sentence: &str, _budget: StepBudget) -> ParseOutcome<Self::Node> {
+        match self.parse(sentence) {
+            Some(node) => ParseOutcome::Complete(node),
+            None => ParseOutcome::Exhausted,
+        }
+    }
+    
     /// Get all possible parses for a sentence
     fn parse_all(&self, sentence: &str) -> Vec<Self::Node> {
         self.parse(sentence).into_iter().collect()
     }
+    
+    /// Get a packed forest of all parses for a sentence
+    fn parse_forest(&self, sentence: &str) -> Option<Forest<Self::Node>>
+    where
+        Self::Cat: std::fmt::Display,
+    {
+        let trees = self.parse_all(sentence);
+        if trees.is_empty() {
+            None
+        } else {
+            Some(Forest::from_trees(&trees))
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,119 @@
+//! Morphological analysis of words missing from a lexicon
+
+use crate::common::{FeatureStructure, FeatureValue};
+
+/// Trait for morphological analyzers consulted for unknown words
+///
+/// An analysis pairs a candidate lemma with the features contributed by
+/// the inflection, e.g. `cats -> (cat, [num=pl])`. Parsers look the lemma up
+/// in their lexicon and add the features to the categories they find.
+pub trait Morphology {
+    /// Analyze a word into candidate lemmas and their inflectional features
+    fn analyze(&self, word: &str) -> Vec<(String, FeatureStructure)>;
+}
+
+/// A regular suffix analyzer for English inflection
+///
+/// Recognizes plural `-s`/`-es`/`-ies` (`num=pl`), past `-ed` (`tense=past`)
+/// and progressive `-ing` (`aspect=prog`). Stems are proposed with and
+/// without a final `e` and with an undoubled final consonant, so `baked`,
+/// `walked` and `running` all find their lemmas.
+#[derive(Debug, Clone, Default)]
+pub struct EnglishSuffixAnalyzer;
+
+impl EnglishSuffixAnalyzer {
+    /// Create a new analyzer
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The candidate lemmas for a stem left by stripping a suffix
+    fn stems(stem: &str) -> Vec<String> {
+        let mut stems = vec![stem.to_string(), format!("{}e", stem)];
+
+        let chars: Vec<char> = stem.chars().collect();
+        if let [.., a, b] = chars[..] {
+            if a == b && !"aeiou".contains(a) {
+                stems.push(chars[..chars.len() - 1].iter().collect());
+            }
+        }
+
+        stems
+    }
+}
+
+impl Morphology for EnglishSuffixAnalyzer {
+    fn analyze(&self, word: &str) -> Vec<(String, FeatureStructure)> {
+        let feature = |name: &str, value: &str| {
+            FeatureStructure::with_feature(name, FeatureValue::Atomic(value.to_string()))
+        };
+
+        let mut analyses = Vec::new();
+        let mut add = |lemma: String, features: &FeatureStructure| {
+            if !lemma.is_empty() && lemma != word && !analyses.iter().any(|(l, _)| *l == lemma) {
+                analyses.push((lemma, features.clone()));
+            }
+        };
+
+        if let Some(stem) = word.strip_suffix("ing") {
+            let features = feature("aspect", "prog");
+            for lemma in Self::stems(stem) {
+                add(lemma, &features);
+            }
+        } else if let Some(stem) = word.strip_suffix("ed") {
+            let features = feature("tense", "past");
+            for lemma in Self::stems(stem) {
+                add(lemma, &features);
+            }
+        } else if let Some(stem) = word.strip_suffix('s') {
+            let features = feature("num", "pl");
+            if let Some(root) = stem.strip_suffix("ie") {
+                add(format!("{}y", root), &features);
+            }
+            add(stem.to_string(), &features);
+            if let Some(root) = stem.strip_suffix('e') {
+                add(root.to_string(), &features);
+            }
+        }
+
+        analyses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lemmas(word: &str) -> Vec<String> {
+        EnglishSuffixAnalyzer::new().analyze(word).into_iter().map(|(lemma, _)| lemma).collect()
+    }
+
+    #[test]
+    fn test_plural_suffix() {
+        let analyses = EnglishSuffixAnalyzer::new().analyze("cats");
+        assert_eq!(analyses[0].0, "cat");
+        assert_eq!(analyses[0].1.get("num"), Some(&FeatureValue::Atomic("pl".to_string())));
+
+        assert!(lemmas("boxes").contains(&"box".to_string()));
+        assert!(lemmas("ponies").contains(&"pony".to_string()));
+    }
+
+    #[test]
+    fn test_past_suffix() {
+        let analyses = EnglishSuffixAnalyzer::new().analyze("walked");
+        assert_eq!(analyses[0].0, "walk");
+        assert_eq!(analyses[0].1.get("tense"), Some(&FeatureValue::Atomic("past".to_string())));
+
+        assert!(lemmas("baked").contains(&"bake".to_string()));
+    }
+
+    #[test]
+    fn test_progressive_suffix() {
+        let analyses = EnglishSuffixAnalyzer::new().analyze("sleeping");
+        assert_eq!(analyses[0].0, "sleep");
+        assert_eq!(analyses[0].1.get("aspect"), Some(&FeatureValue::Atomic("prog".to_string())));
+
+        assert!(lemmas("running").contains(&"run".to_string()));
+        assert!(lemmas("cat").is_empty());
+    }
+}
@@ -0,0 +1,162 @@
+//! Tokenization of input sentences
+
+use std::collections::HashMap;
+
+/// Trait for splitting an input sentence into the tokens looked up in a lexicon
+pub trait Tokenizer {
+    /// Split a sentence into tokens
+    fn tokenize(&self, text: &str) -> Vec<String>;
+}
+
+/// A whitespace tokenizer that also separates punctuation and clitics
+///
+/// Punctuation is split off the start and end of each word, with runs of
+/// the same mark kept together (`"wait..."` gives `wait`, `...`). Words in
+/// the contraction table are replaced by their expansion, and otherwise a
+/// clitic suffix is split off (`don't` gives `do`, `n't`). Typographic
+/// apostrophes are treated as plain ones.
+#[derive(Debug, Clone)]
+pub struct SimpleTokenizer {
+    /// Whether to lowercase every token
+    pub lowercase: bool,
+    /// Whether to separate leading and trailing punctuation
+    pub split_punctuation: bool,
+    /// Clitic suffixes split off the end of a word
+    pub clitics: Vec<String>,
+    /// Irregular contractions and their tokens, keyed by lowercase form
+    pub contractions: HashMap<String, Vec<String>>,
+}
+
+impl Default for SimpleTokenizer {
+    fn default() -> Self {
+        let contractions = [
+            ("won't", vec!["wo", "n't"]),
+            ("can't", vec!["ca", "n't"]),
+            ("shan't", vec!["sha", "n't"]),
+        ];
+
+        Self {
+            lowercase: false,
+            split_punctuation: true,
+            clitics: ["n't", "'s", "'re", "'ve", "'ll", "'d", "'m"].iter().map(|c| c.to_string()).collect(),
+            contractions: contractions.into_iter()
+                .map(|(word, tokens)| (word.to_string(), tokens.into_iter().map(str::to_string).collect()))
+                .collect(),
+        }
+    }
+}
+
+impl SimpleTokenizer {
+    /// Create a tokenizer with the default English rules
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether tokens are lowercased
+    pub fn with_lowercase(mut self, lowercase: bool) -> Self {
+        self.lowercase = lowercase;
+        self
+    }
+
+    /// Split a word without punctuation into its contraction or clitic tokens
+    fn split_clitics(&self, word: &str) -> Vec<String> {
+        if let Some(tokens) = self.contractions.get(&word.to_lowercase()) {
+            return tokens.clone();
+        }
+
+        let lower = word.to_lowercase();
+        for clitic in &self.clitics {
+            if lower.len() > clitic.len() && lower.ends_with(clitic.as_str()) {
+                let split = word.len() - clitic.len();
+                if word.is_char_boundary(split) {
+                    return vec![word[..split].to_string(), word[split..].to_string()];
+                }
+            }
+        }
+
+        vec![word.to_string()]
+    }
+}
+
+/// Split a run of punctuation into groups of the same mark
+fn punctuation_tokens(marks: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = Vec::new();
+    for mark in marks.chars() {
+        match tokens.last_mut() {
+            Some(last) if last.ends_with(mark) => last.push(mark),
+            _ => tokens.push(mark.to_string()),
+        }
+    }
+    tokens
+}
+
+impl Tokenizer for SimpleTokenizer {
+    fn tokenize(&self, text: &str) -> Vec<String> {
+        let mut tokens = Vec::new();
+
+        for chunk in text.split_whitespace() {
+            let chunk = chunk.replace('’', "'");
+
+            // A clitic written on its own is already a token
+            if self.clitics.iter().any(|clitic| clitic.eq_ignore_ascii_case(&chunk)) {
+                tokens.push(chunk);
+                continue;
+            }
+
+            if !self.split_punctuation {
+                tokens.extend(self.split_clitics(&chunk));
+                continue;
+            }
+
+            let is_word_char = |c: char| c.is_alphanumeric();
+            let core_start = chunk.find(is_word_char);
+            let core_end = chunk.rfind(is_word_char).map(|i| i + chunk[i..].chars().next().map_or(1, char::len_utf8));
+
+            match (core_start, core_end) {
+                (Some(start), Some(end)) => {
+                    tokens.extend(punctuation_tokens(&chunk[..start]));
+                    tokens.extend(self.split_clitics(&chunk[start..end]));
+                    tokens.extend(punctuation_tokens(&chunk[end..]));
+                },
+                _ => tokens.extend(punctuation_tokens(&chunk)),
+            }
+        }
+
+        if self.lowercase {
+            tokens.iter_mut().for_each(|token| *token = token.to_lowercase());
+        }
+
+        tokens
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_punctuation() {
+        let tokenizer = SimpleTokenizer::new();
+        assert_eq!(tokenizer.tokenize("the cat, sleeps."), vec!["the", "cat", ",", "sleeps", "."]);
+        assert_eq!(tokenizer.tokenize("\"wait...\" (she said)"), vec!["\"", "wait", "...", "\"", "(", "she", "said", ")"]);
+        assert_eq!(tokenizer.tokenize("¿qué? ¡señor!"), vec!["¿", "qué", "?", "¡", "señor", "!"]);
+    }
+
+    #[test]
+    fn test_clitics() {
+        let tokenizer = SimpleTokenizer::new();
+        assert_eq!(tokenizer.tokenize("John's cat doesn't sleep"), vec!["John", "'s", "cat", "does", "n't", "sleep"]);
+        assert_eq!(tokenizer.tokenize("I won’t go, they'll see"), vec!["I", "wo", "n't", "go", ",", "they", "'ll", "see"]);
+        assert_eq!(tokenizer.tokenize("'s"), vec!["'s"]);
+    }
+
+    #[test]
+    fn test_lowercase() {
+        let tokenizer = SimpleTokenizer::new().with_lowercase(true);
+        assert_eq!(tokenizer.tokenize("The Cat SLEEPS!"), vec!["the", "cat", "sleeps", "!"]);
+
+        let mut whitespace = SimpleTokenizer::new();
+        whitespace.split_punctuation = false;
+        assert_eq!(whitespace.tokenize("the cat, sleeps."), vec!["the", "cat,", "sleeps."]);
+    }
+}
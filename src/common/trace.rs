@@ -0,0 +1,130 @@
+//! Step-by-step tracing of derivations
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+/// The kind of a derivation step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepKind {
+    /// A lexical entry entered the derivation
+    Lexical,
+    /// A rule combined or rewrote items
+    Rule,
+    /// Two trees were merged
+    Merge,
+    /// An element moved
+    Move,
+}
+
+/// One step of a derivation, with a human-readable description
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// The kind of step
+    pub kind: StepKind,
+    /// What happened at this step
+    pub message: String,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {}", self.kind, self.message)
+    }
+}
+
+/// Trait for observers of the steps a parser takes
+pub trait Tracer {
+    /// Called by the parser after each step
+    fn on_step(&mut self, step: &Step);
+}
+
+/// A tracer that prints each step as it happens, numbered from 1
+#[derive(Debug, Clone, Default)]
+pub struct StepTracer {
+    count: usize,
+}
+
+impl StepTracer {
+    /// Create a new printing tracer
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Tracer for StepTracer {
+    fn on_step(&mut self, step: &Step) {
+        self.count += 1;
+        eprintln!("{:>4}. {}", self.count, step);
+    }
+}
+
+/// A tracer collecting every step
+///
+/// Clones share the collected steps, so a clone can be attached to a parser
+/// and the steps read back through the original.
+#[derive(Debug, Clone, Default)]
+pub struct VecTracer {
+    steps: Rc<RefCell<Vec<Step>>>,
+}
+
+impl VecTracer {
+    /// Create a new collecting tracer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the steps collected so far
+    pub fn steps(&self) -> Vec<Step> {
+        self.steps.borrow().clone()
+    }
+
+    /// Forget the steps collected so far
+    pub fn clear(&self) {
+        self.steps.borrow_mut().clear();
+    }
+}
+
+impl Tracer for VecTracer {
+    fn on_step(&mut self, step: &Step) {
+        self.steps.borrow_mut().push(step.clone());
+    }
+}
+
+/// The tracer attached to a parser, if any
+///
+/// Parsers hold one of these and report steps through shared references.
+/// Messages are only built when a tracer is attached.
+#[derive(Clone, Default)]
+pub struct TraceHandle {
+    tracer: Option<Rc<RefCell<Box<dyn Tracer>>>>,
+}
+
+impl TraceHandle {
+    /// Attach a tracer, replacing any previous one
+    pub fn set(&mut self, tracer: Box<dyn Tracer>) {
+        self.tracer = Some(Rc::new(RefCell::new(tracer)));
+    }
+
+    /// Detach the tracer
+    pub fn clear(&mut self) {
+        self.tracer = None;
+    }
+
+    /// Check if a tracer is attached
+    pub fn is_active(&self) -> bool {
+        self.tracer.is_some()
+    }
+
+    /// Report a step to the tracer, if one is attached
+    pub fn step(&self, kind: StepKind, message: impl FnOnce() -> String) {
+        if let Some(tracer) = &self.tracer {
+            tracer.borrow_mut().on_step(&Step { kind, message: message() });
+        }
+    }
+}
+
+impl fmt::Debug for TraceHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TraceHandle").field("active", &self.is_active()).finish()
+    }
+}
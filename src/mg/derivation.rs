@@ -1,11 +1,26 @@
 //! Derivation trees for Minimalist Grammar
 
+use std::collections::{HashMap, HashSet};
 use std::fmt;
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 use crate::mg::feature::Feature;
 use crate::mg::lexical_item::LexicalItem;
 use crate::common::FeatureStructure;
 use crate::common::ParseNode;
+use crate::common::export::{format_conll, ConllToken, ToConll};
+use crate::common::LambdaTerm;
+
+/// Which copies of a moved chain are pronounced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PronounceCopy {
+    /// Only the copy at the landing site
+    #[default]
+    Highest,
+    /// Only the copy at the base position
+    Lowest,
+    /// Every copy, as in resumption
+    All,
+}
 
 /// Chain elements in a derived structure
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -14,10 +29,16 @@ pub struct Chain {
     pub head: LexicalItem,
     /// The tail positions (traces)
     pub tail: Vec<usize>,
+    /// The lower copies left at the tail positions, in the same order
+    pub copies: Vec<LexicalItem>,
+    /// Which copies are pronounced
+    pub pronounce: PronounceCopy,
     /// Agreement information
     pub agreement: Option<FeatureStructure>,
     /// Whether this is a phase head
     pub is_phase_head: bool,
+    /// Whether the head is pronounced at the landing site of its movement
+    pub overt: bool,
 }
 
 impl Chain {
@@ -32,8 +53,11 @@ impl Chain {
         Chain {
             head,
             tail: Vec::new(),
+            copies: Vec::new(),
+            pronounce: PronounceCopy::Highest,
             agreement,
             is_phase_head,
+            overt: true,
         }
     }
     
@@ -44,6 +68,39 @@ impl Chain {
         chain
     }
     
+    /// Record a lower copy left at a tail position
+    pub fn push_copy(&mut self, index: usize, copy: LexicalItem) {
+        self.tail.push(index);
+        self.copies.push(copy);
+    }
+    
+    /// Get the form pronounced at a tail position, if that copy is pronounced
+    ///
+    /// The base position is the first tail position. A tail position without
+    /// a recorded copy spells out the head's form.
+    pub fn copy_form(&self, index: usize) -> Option<&str> {
+        self.pronounced_copy(index).map(|copy| copy.phonetic_form.as_str())
+    }
+    
+    /// Get the copy pronounced at a tail position, as [`Chain::copy_form`] does its form
+    pub fn pronounced_copy(&self, index: usize) -> Option<&LexicalItem> {
+        let k = self.tail.iter().position(|&tail| tail == index)?;
+        let pronounced = match self.pronounce {
+            PronounceCopy::Highest => false,
+            PronounceCopy::Lowest => k == 0,
+            PronounceCopy::All => true,
+        };
+        if !pronounced {
+            return None;
+        }
+        Some(self.copies.get(k).unwrap_or(&self.head))
+    }
+    
+    /// Check if the copy at the landing site is pronounced
+    pub fn pronounces_head(&self) -> bool {
+        self.pronounce != PronounceCopy::Lowest
+    }
+    
     /// Create a chain with explicit agreement information
     pub fn with_agreement(mut self, agreement: FeatureStructure) -> Self {
         self.agreement = Some(agreement);
@@ -81,8 +138,40 @@ impl fmt::Display for Chain {
     }
 }
 
+/// The address of a node in a derived tree
+///
+/// Each step records which daughter was taken from the root: `0` for the
+/// left daughter and `1` for the right one.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Position(pub Vec<usize>);
+
+impl Position {
+    /// The position of a daughter of the node at this position
+    pub fn child(&self, branch: usize) -> Self {
+        let mut path = self.0.clone();
+        path.push(branch);
+        Position(path)
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "ε");
+        }
+        for branch in &self.0 {
+            write!(f, "{}", branch)?;
+        }
+        Ok(())
+    }
+}
+
 /// Derived syntactic structure in Minimalist Grammar
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+///
+/// Equality and hashing compare content and ignore node indices: trees that
+/// differ only in the order their nodes were built are equal, with trace
+/// positions compared by the node they point to rather than by index.
+#[derive(Debug, Clone)]
 pub struct DerivationTree {
     /// The chain at this node
     pub chain: Chain,
@@ -98,6 +187,11 @@ pub struct DerivationTree {
     pub is_phase: bool,
     /// Whether the phase is completed (transferred to interfaces)
     pub phase_completed: bool,
+    /// Further specifiers of the same head, tucked in below the chain's
+    /// landing site in the order they moved
+    pub specifiers: Vec<Chain>,
+    /// The categorial feature checked when this node was selected
+    pub category: Option<String>,
 }
 
 impl DerivationTree {
@@ -117,20 +211,20 @@ impl DerivationTree {
             delayed_features,
             is_phase,
             phase_completed: false,
+            specifiers: Vec::new(),
+            category: None,
         }
     }
     
     /// Create a new internal node via Merge
     pub fn merge(left: DerivationTree, right: DerivationTree, head_features: Vec<Feature>, index: usize) -> Self {
-        // Create a new lexical item with the remaining features
+        // Create a new lexical item with the remaining features, labelled by the head
         let head_item = LexicalItem {
-            phonetic_form: if !left.chain.head.phonetic_form.is_empty() {
-                left.chain.head.phonetic_form.clone()
-            } else {
-                right.chain.head.phonetic_form.clone()
-            },
+            phonetic_form: right.chain.head.phonetic_form.clone(),
             features: head_features.clone(),
             agreement_features: None,
+            semantics: None,
+            weight: 0.0,
         };
         
         // Extract any delayed features
@@ -162,23 +256,48 @@ impl DerivationTree {
             delayed_features,
             is_phase,
             phase_completed: false,
+            specifiers: Vec::new(),
+            category: None,
         }
     }
     
     /// Create a new internal node via Pair Merge (adjunction)
-    pub fn pair_merge(host: DerivationTree, adjunct: DerivationTree, index: usize) -> Self {
-        // In pair merge, the host projects and the adjunct becomes an adjunct
-        let mut result = host.clone();
-        
-        // Mark the adjunct as an adjunct
-        let mut adjunct_copy = adjunct.clone();
-        adjunct_copy.is_adjunct = true;
+    ///
+    /// The host projects a new node labelled like itself, with the adjunct
+    /// as its left child. Adjoining to the result again stacks the next
+    /// adjunct above the earlier ones, so "big red book" is
+    /// `[big [red book]]`.
+    pub fn pair_merge(host: DerivationTree, mut adjunct: DerivationTree, index: usize) -> Self {
+        adjunct.is_adjunct = true;
         
-        // Combine them - adjunct as the specifier (left child)
-        result.children = Some((Box::new(adjunct_copy), Box::new(host)));
-        result.index = index;
+        let mut chain = Chain::new(host.chain.head.clone());
+        chain.merge_agreement(&host.chain);
         
-        result
+        DerivationTree {
+            chain,
+            index,
+            is_adjunct: false,
+            delayed_features: host.delayed_features.clone(),
+            is_phase: host.is_phase,
+            phase_completed: false,
+            specifiers: Vec::new(),
+            category: None,
+            children: Some((Box::new(adjunct), Box::new(host))),
+        }
+    }
+    
+    /// Get the adjuncts stacked on this node, outermost first
+    pub fn adjuncts(&self) -> Vec<&DerivationTree> {
+        let mut adjuncts = Vec::new();
+        let mut node = self;
+        while let Some((left, right)) = &node.children {
+            if !left.is_adjunct {
+                break;
+            }
+            adjuncts.push(left.as_ref());
+            node = right;
+        }
+        adjuncts
     }
     
     /// Create a new node via Late Merge
@@ -209,7 +328,11 @@ impl DerivationTree {
     }
     
     /// Create a new node via Move
-    pub fn r#move(base: DerivationTree, moved_chain: Chain, head_features: Vec<Feature>, index: usize) -> Self {
+    ///
+    /// Overt movement pronounces the moved chain at its landing site. Covert
+    /// movement only checks features there; the mover is pronounced in its
+    /// base position, which `base` is expected to keep.
+    pub fn r#move(base: DerivationTree, moved_chain: Chain, head_features: Vec<Feature>, index: usize, overt: bool) -> Self {
         // Extract any delayed features
         let delayed_features: Vec<Feature> = head_features.iter()
             .filter_map(|f| {
@@ -230,10 +353,15 @@ impl DerivationTree {
                     phonetic_form: moved_chain.head.phonetic_form.clone(),
                     features: head_features,
                     agreement_features: moved_chain.agreement.clone(),
+                    semantics: None,
+                    weight: 0.0,
                 },
                 tail: moved_chain.tail,
+                copies: moved_chain.copies,
+                pronounce: moved_chain.pronounce,
                 agreement: moved_chain.agreement,
                 is_phase_head: moved_chain.is_phase_head,
+                overt,
             },
             children: Some((Box::new(base), Box::new(DerivationTree {
                 chain: Chain::new(LexicalItem::empty()),
@@ -243,12 +371,16 @@ impl DerivationTree {
                 delayed_features: Vec::new(),
                 is_phase: false,
                 phase_completed: false,
+                specifiers: Vec::new(),
+                category: None,
             }))),
             index,
             is_adjunct: false,
             delayed_features,
             is_phase,
             phase_completed: false,
+            specifiers: Vec::new(),
+            category: None,
         }
     }
     
@@ -258,8 +390,14 @@ impl DerivationTree {
     }
     
     /// Remove the first feature from this node's chain head
+    ///
+    /// A removed categorial feature is kept as the node's category.
     pub fn remove_first_feature(&mut self) -> Option<Feature> {
-        self.chain.head.remove_first_feature()
+        let feature = self.chain.head.remove_first_feature();
+        if let Some(Feature::Categorial(category)) = &feature {
+            self.category = Some(category.clone());
+        }
+        feature
     }
     
     /// Create a copy with the first feature removed
@@ -295,23 +433,333 @@ impl DerivationTree {
         }
     }
     
+    /// Linearize the pronounced forms of this tree from its structure
+    ///
+    /// Precedence follows the derived tree rather than node indexes. A
+    /// moved element is pronounced at its landing site, before the
+    /// structure it moved out of, and its trace is silent unless the chain
+    /// pronounces its lower copies; a covertly moved
+    /// element is only pronounced in its base position. Further specifiers
+    /// of the same head follow the first. A complement
+    /// merged with a lexical head follows the head; specifiers and adjuncts
+    /// precede it. Each form is paired with the position it is spelled out at.
+    pub fn linearize_structural(&self) -> Vec<(String, Position)> {
+        self.linearize_with(&mut |_, _, _| false)
+    }
+    
+    /// Linearize the pronounced forms of this tree, reordering some sisters
+    ///
+    /// `swap` is called at each branching node with its position and its
+    /// daughters in structural order, and returns whether the second daughter
+    /// should be pronounced first instead.
+    pub fn linearize_with(&self, swap: &mut dyn FnMut(&Position, &DerivationTree, &DerivationTree) -> bool) -> Vec<(String, Position)> {
+        type Swap<'a> = dyn FnMut(&Position, &DerivationTree, &DerivationTree) -> bool + 'a;
+        
+        fn collect(node: &DerivationTree, position: Position, placeholder: bool, chains: &[&Chain], swap: &mut Swap<'_>, forms: &mut Vec<(String, Position)>) {
+            let pronounced = node.is_leaf() || (node.chain.has_traces() && node.chain.overt && node.chain.pronounces_head());
+            if pronounced && !node.chain.head.phonetic_form.is_empty() {
+                forms.push((node.chain.head.phonetic_form.clone(), position.clone()));
+            }
+            
+            // A trace spells out the lower copy of a chain pronouncing it
+            if node.is_leaf() && !placeholder && node.chain.head.phonetic_form.is_empty() {
+                if let Some(form) = chains.iter().find_map(|chain| chain.copy_form(node.index)) {
+                    forms.push((form.to_string(), position.clone()));
+                }
+            }
+            
+            for specifier in node.specifiers.iter().filter(|specifier| specifier.pronounces_head() && !specifier.head.phonetic_form.is_empty()) {
+                forms.push((specifier.head.phonetic_form.clone(), position.clone()));
+            }
+            
+            if let Some((left, right)) = &node.children {
+                // The right daughter of a movement step only stands in for the mover
+                let moved = node.chain.has_traces();
+                let left_position = position.child(0);
+                let right_position = position.child(1);
+                
+                // First Merge with a lexical head: the complement follows
+                let complement = !node.chain.has_traces() && !left.is_adjunct && right.is_leaf();
+                let mut daughters = if complement {
+                    [(right, right_position), (left, left_position)]
+                } else {
+                    [(left, left_position), (right, right_position)]
+                };
+                if swap(&position, daughters[0].0, daughters[1].0) {
+                    daughters.swap(0, 1);
+                }
+                for (daughter, daughter_position) in daughters {
+                    let placeholder = moved && std::ptr::eq(daughter.as_ref(), right.as_ref());
+                    collect(daughter, daughter_position, placeholder, chains, swap, forms);
+                }
+            }
+        }
+        
+        let mut chains = Vec::new();
+        self.copy_chains(&mut chains);
+        
+        let mut forms = Vec::new();
+        collect(self, Position::default(), false, &chains, swap, &mut forms);
+        forms
+    }
+    
+    /// Collect the chains of this tree that pronounce some of their lower copies
+    fn copy_chains<'a>(&'a self, chains: &mut Vec<&'a Chain>) {
+        for chain in std::iter::once(&self.chain).chain(&self.specifiers) {
+            if chain.overt && chain.pronounce != PronounceCopy::Highest {
+                chains.push(chain);
+            }
+        }
+        if let Some((left, right)) = &self.children {
+            left.copy_chains(chains);
+            right.copy_chains(chains);
+        }
+    }
+    
     /// Get the yield (linearized string) of this tree
+    ///
+    /// Words are pronounced once each, in the order given by
+    /// [`DerivationTree::linearize_structural`].
     pub fn get_yield(&self) -> Vec<String> {
-        // Return the linearized string
-        let mut forms = Vec::new();
+        self.linearize_structural().into_iter().map(|(form, _)| form).collect()
+    }
+    
+    /// Compose the meanings of the lexical items into a logical form
+    ///
+    /// Merge applies the head's meaning to that of its complement or
+    /// specifier, and an adjunct's meaning to its host's. Move abstracts over
+    /// the positions in the chain's tail, and the mover's own meaning, if it
+    /// has one, is applied to the abstraction. Items without a meaning are
+    /// transparent. The result is beta-normalized; `None` means no item
+    /// contributed a meaning.
+    pub fn logical_form(&self) -> Option<LambdaTerm> {
+        let mut lexical = HashSet::new();
+        self.lexical_variables(&mut lexical);
+        self.compose(&mut HashMap::new(), &lexical).map(|term| term.beta_normalize())
+    }
+    
+    /// Collect the free variables of the meanings in this tree
+    fn lexical_variables(&self, variables: &mut HashSet<String>) {
+        let chains = std::iter::once(&self.chain).chain(&self.specifiers);
+        let items = chains.flat_map(|chain| std::iter::once(&chain.head).chain(&chain.copies));
+        for semantics in items.filter_map(|item| item.semantics.as_ref()) {
+            variables.extend(semantics.free_vars());
+        }
+        if let Some((left, right)) = &self.children {
+            left.lexical_variables(variables);
+            right.lexical_variables(variables);
+        }
+    }
+    
+    /// Compose the logical form of this node, given the variables bound at its tail positions
+    ///
+    /// Movement binds names not already bound here nor free in `lexical`.
+    fn compose(&self, variables: &mut HashMap<usize, String>, lexical: &HashSet<String>) -> Option<LambdaTerm> {
+        if let Some(variable) = variables.get(&self.index) {
+            return Some(LambdaTerm::var(variable));
+        }
+        
+        let (left, right) = match &self.children {
+            Some((left, right)) => (left, right),
+            None => return self.chain.head.semantics.clone(),
+        };
         
-        // Add this node's phonetic form if non-empty and not a trace
-        if !self.chain.head.phonetic_form.is_empty() && !self.chain.tail.contains(&self.index) {
-            forms.push(self.chain.head.phonetic_form.clone());
+        if !self.chain.has_traces() {
+            let (functor, argument) = if left.is_adjunct { (left, right) } else { (right, left) };
+            return match (functor.compose(variables, lexical), argument.compose(variables, lexical)) {
+                (Some(functor), Some(argument)) => Some(LambdaTerm::app(functor, argument)),
+                (functor, argument) => functor.or(argument),
+            };
         }
         
-        // Recursively collect from children
-        if let Some((left, right)) = &self.children {
-            forms.extend(left.as_ref().get_yield());
-            forms.extend(right.as_ref().get_yield());
+        // Bind a variable at the tail of each chain landing here, outermost first
+        let chains: Vec<&Chain> = std::iter::once(&self.chain).chain(&self.specifiers).collect();
+        let mut bound = Vec::new();
+        for chain in &chains {
+            let variable = (0..)
+                .map(|k| match k {
+                    0..=3 => ["x", "y", "z", "w"][k].to_string(),
+                    k => format!("x{}", k),
+                })
+                .find(|name| !lexical.contains(name) && !variables.values().any(|bound| bound == name))
+                .unwrap();
+            for &index in &chain.tail {
+                variables.insert(index, variable.clone());
+            }
+            bound.push(variable);
         }
         
-        forms
+        // The right daughter only stands in for the mover
+        let body = left.compose(variables, lexical);
+        for index in chains.iter().flat_map(|chain| &chain.tail) {
+            variables.remove(index);
+        }
+        
+        let mut term = body?;
+        for (chain, variable) in chains.iter().zip(&bound).rev() {
+            let abstraction = LambdaTerm::abs(variable, term);
+            term = match chain.copies.first().and_then(|copy| copy.semantics.clone()) {
+                Some(mover) => LambdaTerm::app(mover, abstraction),
+                None => abstraction,
+            };
+        }
+        Some(term)
+    }
+    
+    /// Render this tree in the Graphviz DOT language
+    ///
+    /// Each node is labelled with its remaining features. Traces are dashed,
+    /// a dashed arrow leads from each trace back to the node its mover landed
+    /// at, and completed phases are boxed as clusters.
+    pub fn to_dot(&self) -> String {
+        /// The rendered nodes: leaves by index and the landing sites of movers
+        #[derive(Default)]
+        struct Dot {
+            out: String,
+            next: usize,
+            leaves: Vec<(usize, usize)>,
+            movers: Vec<(usize, Vec<usize>)>,
+        }
+        
+        fn render(node: &DerivationTree, landing: bool, depth: usize, dot: &mut Dot) -> usize {
+            let id = dot.next;
+            dot.next += 1;
+            let indent = "    ".repeat(depth);
+            
+            let phase = node.phase_completed;
+            if phase {
+                dot.out.push_str(&format!("{}subgraph cluster_{} {{\n{}    label=\"phase\";\n", indent, id, indent));
+            }
+            let inner = if phase { format!("{}    ", indent) } else { indent.clone() };
+            
+            let empty = node.is_leaf() && node.chain.head.is_empty();
+            let label = match (empty, landing) {
+                (true, true) => "ε".to_string(),
+                (true, false) => format!("t{}", node.index),
+                _ => node.chain.head.to_string().replace('\\', "\\\\").replace('"', "\\\""),
+            };
+            let style = if empty && !landing { ", style=dashed" } else { "" };
+            dot.out.push_str(&format!("{}n{} [label=\"{}\"{}];\n", inner, id, label, style));
+            
+            if node.is_leaf() && !landing {
+                dot.leaves.push((node.index, id));
+            }
+            let tails: Vec<usize> = std::iter::once(&node.chain).chain(&node.specifiers)
+                .flat_map(|chain| chain.tail.iter().copied())
+                .collect();
+            if !tails.is_empty() {
+                dot.movers.push((id, tails));
+            }
+            
+            if let Some((left, right)) = &node.children {
+                let left_id = render(left, false, depth + usize::from(phase), dot);
+                // The empty right daughter of a movement stands for the landing site
+                let right_id = render(right, node.chain.has_traces(), depth + usize::from(phase), dot);
+                dot.out.push_str(&format!("{}n{} -> n{};\n{}n{} -> n{};\n", inner, id, left_id, inner, id, right_id));
+            }
+            
+            if phase {
+                dot.out.push_str(&format!("{}}}\n", indent));
+            }
+            id
+        }
+        
+        let mut dot = Dot::default();
+        render(self, false, 1, &mut dot);
+        
+        for (mover, tails) in &dot.movers {
+            for tail in tails {
+                if let Some((_, trace)) = dot.leaves.iter().find(|(index, _)| index == tail) {
+                    dot.out.push_str(&format!("    n{} -> n{} [style=dashed, constraint=false];\n", trace, mover));
+                }
+            }
+        }
+        
+        format!("digraph derivation {{\n    node [shape=plaintext];\n{}}}\n", dot.out)
+    }
+}
+
+/// A chain with its trace indices replaced by preorder node positions
+#[derive(PartialEq, Eq, Hash)]
+struct ChainContent<'a> {
+    head: &'a LexicalItem,
+    tail: Vec<Option<usize>>,
+    copies: &'a [LexicalItem],
+    pronounce: PronounceCopy,
+    agreement: &'a Option<FeatureStructure>,
+    is_phase_head: bool,
+    overt: bool,
+}
+
+impl<'a> ChainContent<'a> {
+    /// Describe a chain, given the preorder position of each node index
+    fn new(chain: &'a Chain, positions: &HashMap<usize, usize>) -> Self {
+        ChainContent {
+            head: &chain.head,
+            tail: chain.tail.iter().map(|index| positions.get(index).copied()).collect(),
+            copies: &chain.copies,
+            pronounce: chain.pronounce,
+            agreement: &chain.agreement,
+            is_phase_head: chain.is_phase_head,
+            overt: chain.overt,
+        }
+    }
+}
+
+/// The index-free content of a single node of a derivation tree
+#[derive(PartialEq, Eq, Hash)]
+struct NodeContent<'a> {
+    chain: ChainContent<'a>,
+    is_leaf: bool,
+    is_adjunct: bool,
+    delayed_features: &'a [Feature],
+    is_phase: bool,
+    phase_completed: bool,
+    specifiers: Vec<ChainContent<'a>>,
+}
+
+impl DerivationTree {
+    /// The content of every node in preorder, with indices renumbered by position
+    fn content(&self) -> Vec<NodeContent<'_>> {
+        let mut nodes = Vec::new();
+        let mut stack = vec![self];
+        while let Some(node) = stack.pop() {
+            nodes.push(node);
+            if let Some((left, right)) = &node.children {
+                stack.push(right);
+                stack.push(left);
+            }
+        }
+        
+        let mut positions = HashMap::new();
+        for (position, node) in nodes.iter().enumerate() {
+            positions.entry(node.index).or_insert(position);
+        }
+        nodes.iter()
+            .map(|node| NodeContent {
+                chain: ChainContent::new(&node.chain, &positions),
+                is_leaf: node.children.is_none(),
+                is_adjunct: node.is_adjunct,
+                delayed_features: &node.delayed_features,
+                is_phase: node.is_phase,
+                phase_completed: node.phase_completed,
+                specifiers: node.specifiers.iter().map(|chain| ChainContent::new(chain, &positions)).collect(),
+            })
+            .collect()
+    }
+}
+
+impl PartialEq for DerivationTree {
+    fn eq(&self, other: &Self) -> bool {
+        self.content() == other.content()
+    }
+}
+
+impl Eq for DerivationTree {}
+
+impl Hash for DerivationTree {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.content().hash(state);
     }
 }
 
@@ -322,6 +770,9 @@ impl fmt::Display for DerivationTree {
             
             // Print the current node
             write!(f, "{}{}", indent_str, node.chain)?;
+            for specifier in &node.specifiers {
+                write!(f, ", {}", specifier)?;
+            }
             if node.is_adjunct {
                 write!(f, " (adjunct)")?;
             }
@@ -347,6 +798,116 @@ impl fmt::Display for DerivationTree {
     }
 }
 
+impl ToConll for DerivationTree {
+    /// Export the pronounced words in linear order with their heads
+    ///
+    /// The words are those of [`DerivationTree::linearize_structural`], in
+    /// its order. The head of a merged phrase is its selecting head; the
+    /// other daughter attaches to it as `comp` if it was first merged, as
+    /// `adjunct` if it was pair-merged and otherwise as `spec`, as do moved
+    /// elements at their landing sites. A lower copy attaches in the place of
+    /// its trace. A phrase whose head is silent is headed by the head of its
+    /// daughter instead. The category column holds the features a word
+    /// still has in the derived tree.
+    fn to_conll(&self) -> String {
+        fn token(item: &LexicalItem) -> ConllToken {
+            let features: Vec<String> = item.features.iter().map(Feature::to_string).collect();
+            ConllToken {
+                form: item.phonetic_form.clone(),
+                category: features.join(" "),
+                head: 0,
+                relation: "root".to_string(),
+            }
+        }
+        
+        fn attach(tokens: &mut [(ConllToken, Position)], dependent: Option<usize>, head: Option<usize>, relation: &str) -> Option<usize> {
+            match (dependent, head) {
+                (Some(dependent), Some(head)) => {
+                    tokens[dependent].0.head = head + 1;
+                    tokens[dependent].0.relation = relation.to_string();
+                    Some(head)
+                },
+                _ => head.or(dependent),
+            }
+        }
+        
+        // Collect the pronounced tokens with their positions, as
+        // `linearize_structural` pronounces them, and return the head token
+        fn collect(node: &DerivationTree, position: Position, chains: &[&Chain], tokens: &mut Vec<(ConllToken, Position)>) -> Option<usize> {
+            let push = |tokens: &mut Vec<(ConllToken, Position)>, item: &LexicalItem| {
+                tokens.push((token(item), position.clone()));
+                tokens.len() - 1
+            };
+            
+            let pronounced = node.is_leaf() || (node.chain.has_traces() && node.chain.overt && node.chain.pronounces_head());
+            let mut landed = Vec::new();
+            if pronounced && !node.chain.head.phonetic_form.is_empty() {
+                landed.push(push(tokens, &node.chain.head));
+            }
+            
+            // A trace spells out the lower copy of a chain pronouncing it,
+            // which attaches in the position of the trace
+            if node.is_leaf() && node.chain.head.phonetic_form.is_empty() {
+                if let Some(copy) = chains.iter().find_map(|chain| chain.pronounced_copy(node.index)) {
+                    return Some(push(tokens, copy));
+                }
+            }
+            
+            for specifier in node.specifiers.iter().filter(|specifier| specifier.pronounces_head() && !specifier.head.phonetic_form.is_empty()) {
+                landed.push(push(tokens, &specifier.head));
+            }
+            
+            let mut head = match &node.children {
+                None => return landed.first().copied(),
+                Some((left, right)) => {
+                    let complement = !node.chain.has_traces() && !left.is_adjunct && right.is_leaf();
+                    if complement {
+                        let head = collect(right, position.child(1), chains, tokens);
+                        let dependent = collect(left, position.child(0), chains, tokens);
+                        attach(tokens, dependent, head, "comp")
+                    } else if node.chain.has_traces() {
+                        // The base of a movement step heads it; the other daughter only stands in for the mover
+                        collect(left, position.child(0), chains, tokens)
+                    } else {
+                        let dependent = collect(left, position.child(0), chains, tokens);
+                        let head = collect(right, position.child(1), chains, tokens);
+                        attach(tokens, dependent, head, if left.is_adjunct { "adjunct" } else { "spec" })
+                    }
+                },
+            };
+            
+            for moved in landed {
+                head = attach(tokens, Some(moved), head, "spec");
+            }
+            head
+        }
+        
+        let mut chains = Vec::new();
+        self.copy_chains(&mut chains);
+        let mut tokens = Vec::new();
+        collect(self, Position::default(), &chains, &mut tokens);
+        
+        // Number the tokens in the order `linearize_structural` gives
+        let mut order: Vec<usize> = Vec::new();
+        for (form, position) in self.linearize_structural() {
+            if let Some(k) = (0..tokens.len()).find(|k| !order.contains(k) && tokens[*k].0.form == form && tokens[*k].1 == position) {
+                order.push(k);
+            }
+        }
+        let numbers: HashMap<usize, usize> = order.iter().enumerate().map(|(number, &k)| (k, number + 1)).collect();
+        let tokens: Vec<ConllToken> = order.iter()
+            .map(|&k| {
+                let mut token = tokens[k].0.clone();
+                if token.head > 0 {
+                    token.head = numbers[&(token.head - 1)];
+                }
+                token
+            })
+            .collect();
+        format_conll(&tokens)
+    }
+}
+
 impl ParseNode for DerivationTree {
     type Cat = LexicalItem;
     
@@ -491,7 +1052,8 @@ mod tests {
             base,
             dp_chain,
             vec![Feature::Categorial("CP".to_string())],
-            3
+            3,
+            true
         );
         
         // Check the resulting structure
@@ -507,6 +1069,31 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_content_equality() {
+        // The same wh-movement built with different node indices
+        let build = |offset: usize, trace: usize| {
+            let v = DerivationTree::leaf(LexicalItem::new("sees", vec![
+                Feature::Categorial("v".to_string()),
+                Feature::Licensor("wh".to_string()),
+            ]), offset);
+            let dp = DerivationTree::leaf(LexicalItem::new("what", vec![
+                Feature::Categorial("D".to_string()),
+                Feature::Licensee("wh".to_string()),
+            ]), offset + 1);
+            let base = DerivationTree::merge(dp, v, vec![Feature::Categorial("vP".to_string())], offset + 2);
+            let chain = Chain::with_tail(LexicalItem::new("what", vec![Feature::Categorial("D".to_string())]), vec![offset + trace]);
+            DerivationTree::r#move(base, chain, vec![Feature::Categorial("CP".to_string())], offset + 3, true)
+        };
+        
+        assert_eq!(build(0, 1), build(10, 1));
+        let deduped: std::collections::HashSet<DerivationTree> = [build(0, 1), build(10, 1), build(20, 1)].into_iter().collect();
+        assert_eq!(deduped.len(), 1);
+        
+        // A trace pointing at a different node is a different derivation
+        assert_ne!(build(0, 1), build(0, 0));
+    }
+    
     #[test]
     fn test_pair_merge() {
         // Create a noun and adjective
@@ -537,6 +1124,47 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_stacked_adjuncts() {
+        let noun = LexicalItem::new("book", vec![Feature::Categorial("N".to_string())]);
+        let adj = |form: &str| LexicalItem::new(form, vec![Feature::Categorial("A".to_string())]);
+        
+        // Adjoin "red" and then "big" to "book"
+        let red_book = DerivationTree::pair_merge(DerivationTree::leaf(noun, 0), DerivationTree::leaf(adj("red"), 1), 2);
+        let big_red_book = DerivationTree::pair_merge(red_book, DerivationTree::leaf(adj("big"), 3), 4);
+        
+        assert_eq!(big_red_book.chain.head.phonetic_form, "book");
+        assert_eq!(big_red_book.get_yield(), vec!["big", "red", "book"]);
+        
+        let adjuncts: Vec<&str> = big_red_book.adjuncts().iter()
+            .map(|adjunct| adjunct.chain.head.phonetic_form.as_str())
+            .collect();
+        assert_eq!(adjuncts, vec!["big", "red"]);
+    }
+    
+    #[test]
+    fn test_to_dot() {
+        let trace = |index| DerivationTree::leaf(LexicalItem::empty(), index);
+        let see = LexicalItem::new("see", vec![Feature::Categorial("V".to_string())]);
+        let john = LexicalItem::new("John", vec![Feature::Categorial("D".to_string())]);
+        let what = LexicalItem::new("what", vec![Feature::Categorial("D".to_string())]);
+        
+        // [what [John [t0 see]]], with the subject moved from t3 and the object from t0
+        let vp = DerivationTree::merge(trace(0), DerivationTree::leaf(see, 1), vec![Feature::Categorial("V".to_string())], 2);
+        let tp = DerivationTree::merge(trace(3), vp, vec![Feature::Licensor("k".to_string())], 4);
+        let subject = DerivationTree::r#move(tp, Chain::with_tail(john, vec![3]), vec![Feature::Licensor("wh".to_string())], 5, true);
+        let mut cp = DerivationTree::r#move(subject, Chain::with_tail(what, vec![0]), vec![Feature::Categorial("C".to_string())], 6, true);
+        cp.is_phase = true;
+        cp.complete_phase();
+        
+        let dot = cp.to_dot();
+        assert!(dot.starts_with("digraph derivation {"));
+        assert!(dot.contains("[label=\"what[C]\"]"));
+        assert_eq!(dot.matches("style=dashed];").count(), 2);
+        assert_eq!(dot.matches("constraint=false").count(), 2);
+        assert_eq!(dot.matches("subgraph cluster_").count(), 1);
+    }
+    
     #[test]
     fn test_late_merge() {
         // Create a DP with a delayed feature
@@ -11,6 +11,8 @@ pub enum Feature {
     Selector(String),
     /// Licensor features (e.g., +wh, +case)
     Licensor(String),
+    /// Licensor features checked by covert, LF-only movement (e.g., +q[covert])
+    CovertLicensor(String),
     /// Licensee features (e.g., -wh, -case)
     Licensee(String),
     /// Strong selector features that trigger head movement (e.g., =v+)
@@ -31,6 +33,7 @@ impl fmt::Display for Feature {
             Feature::Categorial(s) => write!(f, "{}", s),
             Feature::Selector(s) => write!(f, "={}", s),
             Feature::Licensor(s) => write!(f, "+{}", s),
+            Feature::CovertLicensor(s) => write!(f, "+{}[covert]", s),
             Feature::Licensee(s) => write!(f, "-{}", s),
             Feature::StrongSelector(s) => write!(f, "={}+", s),
             Feature::AdjunctSelector(s) => write!(f, "~{}", s),
@@ -57,6 +60,11 @@ impl Feature {
         Feature::Licensor(name.to_string())
     }
     
+    /// Create a new covert licensor feature
+    pub fn covert_licensor(name: &str) -> Self {
+        Feature::CovertLicensor(name.to_string())
+    }
+    
     /// Create a new licensee feature
     pub fn licensee(name: &str) -> Self {
         Feature::Licensee(name.to_string())
@@ -99,11 +107,16 @@ impl Feature {
     /// Check if this feature matches another for Move operation
     pub fn matches_move(&self, other: &Feature) -> bool {
         match (self, other) {
-            (Feature::Licensor(s1), Feature::Licensee(s2)) => s1 == s2,
+            (Feature::Licensor(s1) | Feature::CovertLicensor(s1), Feature::Licensee(s2)) => s1 == s2,
             _ => false,
         }
     }
     
+    /// Check if this feature triggers movement that is not pronounced
+    pub fn is_covert(&self) -> bool {
+        matches!(self, Feature::CovertLicensor(_))
+    }
+    
     /// Check if this feature can trigger head movement
     pub fn triggers_head_movement(&self) -> bool {
         matches!(self, Feature::StrongSelector(_))
@@ -1,11 +1,13 @@
 //! Lexical items in Minimalist Grammar
 
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use crate::mg::feature::Feature;
 use crate::common::{FeatureStructure, Category};
+use crate::common::LambdaTerm;
 
 /// Item in the lexicon (lexical or functional)
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone)]
 pub struct LexicalItem {
     /// The phonological form
     pub phonetic_form: String,
@@ -13,6 +15,32 @@ pub struct LexicalItem {
     pub features: Vec<Feature>,
     /// Additional agreement information
     pub agreement_features: Option<FeatureStructure>,
+    /// The meaning contributed to the logical form, if any
+    pub semantics: Option<LambdaTerm>,
+    /// The cost of using this item in a derivation
+    pub weight: f64,
+}
+
+impl PartialEq for LexicalItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.phonetic_form == other.phonetic_form
+            && self.features == other.features
+            && self.agreement_features == other.agreement_features
+            && self.semantics == other.semantics
+            && self.weight.to_bits() == other.weight.to_bits()
+    }
+}
+
+impl Eq for LexicalItem {}
+
+impl Hash for LexicalItem {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.phonetic_form.hash(state);
+        self.features.hash(state);
+        self.agreement_features.hash(state);
+        self.semantics.hash(state);
+        self.weight.to_bits().hash(state);
+    }
 }
 
 impl fmt::Display for LexicalItem {
@@ -41,6 +69,8 @@ impl LexicalItem {
             phonetic_form: pf.to_string(),
             features,
             agreement_features: None,
+            semantics: None,
+            weight: 0.0,
         }
     }
     
@@ -50,15 +80,31 @@ impl LexicalItem {
             phonetic_form: pf.to_string(),
             features,
             agreement_features: Some(agreement),
+            semantics: None,
+            weight: 0.0,
         }
     }
     
+    /// Attach a lambda term as the meaning of this item
+    pub fn with_semantics(mut self, semantics: LambdaTerm) -> Self {
+        self.semantics = Some(semantics);
+        self
+    }
+    
+    /// Set the cost of using this item in a derivation
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        self.weight = weight;
+        self
+    }
+    
     /// Create a new empty lexical item (for traces)
     pub fn empty() -> Self {
         LexicalItem {
             phonetic_form: String::new(),
             features: Vec::new(),
             agreement_features: None,
+            semantics: None,
+            weight: 0.0,
         }
     }
     
@@ -68,6 +114,7 @@ impl LexicalItem {
             Feature::Categorial(s) => s == feature_type,
             Feature::Selector(s) => s == feature_type,
             Feature::Licensor(s) => s == feature_type,
+            Feature::CovertLicensor(s) => s == feature_type,
             Feature::Licensee(s) => s == feature_type,
             Feature::StrongSelector(s) => s == feature_type,
             Feature::AdjunctSelector(s) => s == feature_type,
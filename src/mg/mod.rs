@@ -6,11 +6,12 @@ pub mod derivation;
 pub mod parser;
 pub mod workspace;
 pub mod phase;
+pub mod precedence;
 
 pub use feature::Feature;
 pub use lexical_item::LexicalItem;
-pub use derivation::DerivationTree;
-pub use parser::{MinimalistParser, ParserConfig};
+pub use derivation::{DerivationTree, Position, PronounceCopy};
+pub use parser::{GrammarWarning, MinimalistParser, ParserConfig};
 pub use crate::common::Parser;
 
 use crate::common::Feature as FeatureTrait;
@@ -22,6 +23,7 @@ impl FeatureTrait for Feature {
             Feature::Categorial(s) => s,
             Feature::Selector(s) => s,
             Feature::Licensor(s) => s,
+            Feature::CovertLicensor(s) => s,
             Feature::Licensee(s) => s,
             Feature::StrongSelector(s) => s,
             Feature::AdjunctSelector(s) => s,
@@ -1,12 +1,15 @@
 //! Parser for Minimalist Grammar
 
 use std::collections::{HashSet, VecDeque};
+use std::fmt;
+use std::sync::Arc;
 use crate::mg::feature::Feature;
 use crate::mg::lexical_item::LexicalItem;
-use crate::mg::derivation::{DerivationTree, Chain};
+use crate::mg::derivation::{DerivationTree, Chain, Position, PronounceCopy};
 use crate::mg::workspace::WorkspaceRegistry;
 use crate::mg::phase::{PhaseConfig, PhaseChecker};
-use crate::common::{Parser, Lexicon, FeatureRegistry};
+use crate::mg::precedence::LinearPrecedence;
+use crate::common::{Agenda, Error, Parser, Lexicon, FeatureRegistry, FeatureStructure, FeatureValue, ParseOutcome, SimpleTokenizer, StepBudget, StepKind, TraceHandle, Tokenizer, Tracer};
 
 /// Different types of movement strategies supported by the parser
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -66,6 +69,16 @@ pub struct ParserConfig {
     pub max_workspaces: usize,
     /// Phase-based processing configuration
     pub phase_config: PhaseConfig,
+    /// The category a complete derivation must bear
+    pub start_category: String,
+    /// Linear precedence statements overriding the structural word order
+    pub lp_rules: LinearPrecedence,
+    /// Which copies of the chains formed by Move are pronounced
+    pub pronounce: PronounceCopy,
+    /// The cost added by each application of Merge
+    pub merge_cost: f64,
+    /// The cost added by each application of Move
+    pub move_cost: f64,
 }
 
 impl Default for ParserConfig {
@@ -80,6 +93,35 @@ impl Default for ParserConfig {
             enable_parallel_workspaces: false,
             max_workspaces: 3,
             phase_config: PhaseConfig::default(),
+            start_category: "C".to_string(),
+            lp_rules: LinearPrecedence::new(),
+            pronounce: PronounceCopy::Highest,
+            merge_cost: 0.0,
+            move_cost: 0.0,
+        }
+    }
+}
+
+/// A problem found by validating a grammar before parsing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GrammarWarning {
+    /// A category borne by some item but selected by none
+    UnselectedCategory(String),
+    /// A category selected by some item but borne by none
+    MissingCategory(String),
+    /// A licensee feature with no matching licensor
+    OrphanLicensee(String),
+    /// The start category cannot be derived from the lexicon
+    UnreachableStart(String),
+}
+
+impl fmt::Display for GrammarWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GrammarWarning::UnselectedCategory(category) => write!(f, "Category {} is never selected", category),
+            GrammarWarning::MissingCategory(category) => write!(f, "Category {} is selected but no item bears it", category),
+            GrammarWarning::OrphanLicensee(licensee) => write!(f, "Licensee -{} has no matching licensor", licensee),
+            GrammarWarning::UnreachableStart(category) => write!(f, "Start category {} cannot be derived", category),
         }
     }
 }
@@ -162,6 +204,10 @@ pub struct MinimalistParser {
     pub next_index: usize, // For tracking node indices during derivation
     pub workspaces: WorkspaceRegistry,
     pub phase_checker: PhaseChecker,
+    /// Tokenizer splitting sentences into words
+    pub tokenizer: Arc<dyn Tokenizer>,
+    /// The tracer told about each lexical item, Merge and Move
+    tracer: TraceHandle,
 }
 
 impl MinimalistParser {
@@ -178,6 +224,8 @@ impl MinimalistParser {
             next_index: 0,
             workspaces: WorkspaceRegistry::new(),
             phase_checker,
+            tokenizer: Arc::new(SimpleTokenizer::default()),
+            tracer: TraceHandle::default(),
         }
     }
     
@@ -189,6 +237,11 @@ impl MinimalistParser {
         parser
     }
     
+    /// Attach a tracer told about each lexical item, Merge and Move in a derivation
+    pub fn set_tracer(&mut self, tracer: Box<dyn Tracer>) {
+        self.tracer.set(tracer);
+    }
+    
     /// Register a new categorial feature
     pub fn register_categorial_feature(&mut self, feature: &str) {
         self.feature_types.register_categorial(feature);
@@ -208,7 +261,7 @@ impl MinimalistParser {
             Feature::Selector(name) | Feature::StrongSelector(name) | Feature::AdjunctSelector(name) => {
                 self.feature_types.is_categorial_registered(name)
             },
-            Feature::Licensor(name) | Feature::Licensee(name) => {
+            Feature::Licensor(name) | Feature::CovertLicensor(name) | Feature::Licensee(name) => {
                 self.feature_types.is_movement_registered(name)
             },
             Feature::Agreement(_, _) => true, // Agreement features are always allowed
@@ -228,6 +281,167 @@ impl MinimalistParser {
     
     /// Parse a sentence, returning a derivation tree if successful
     pub fn parse_internal(&mut self, sentence: &str) -> Option<DerivationTree> {
+        self.parse_result_internal(sentence)
+            .map_err(|error| eprintln!("{}", error))
+            .ok()
+    }
+    
+    /// Parse a sentence, reporting an unknown word or the lack of a derivation
+    pub fn parse_result_internal(&mut self, sentence: &str) -> Result<DerivationTree, Error> {
+        self.derive(sentence, false)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::NoParse { sentence: sentence.to_string() })
+    }
+    
+    /// Parse a sentence, returning the lowest-cost derivation and its cost
+    ///
+    /// The cost of a derivation is the sum of the weights of its lexical
+    /// items plus the configured cost of each Merge and Move. Costs are
+    /// expected to be non-negative, so the first complete derivation taken
+    /// from the agenda is the cheapest.
+    pub fn parse_best(&mut self, sentence: &str) -> Option<(DerivationTree, f64)> {
+        let max_states = self.config.max_derivation_depth;
+        self.search(sentence, false, max_states)
+            .map_err(|error| eprintln!("{}", error))
+            .ok()?
+            .derivations
+            .into_iter()
+            .next()
+    }
+    
+    /// Parse a sentence, returning every complete derivation found within the depth bound
+    pub fn parse_all_internal(&mut self, sentence: &str) -> Vec<DerivationTree> {
+        self.derive(sentence, true)
+            .map_err(|error| eprintln!("{}", error))
+            .unwrap_or_default()
+    }
+    
+    /// The phonologically null functional heads available to every derivation
+    fn functional_heads() -> Vec<LexicalItem> {
+        vec![
+            LexicalItem::new("", vec![
+                Feature::Selector("V".to_string()),
+                Feature::Selector("D".to_string()),
+                Feature::Categorial("T".to_string()),
+            ]),
+            LexicalItem::new("", vec![
+                Feature::Selector("T".to_string()),
+                Feature::Categorial("C".to_string()),
+            ]),
+        ]
+    }
+    
+    /// Check the lexicon and functional heads for features that can never be used
+    ///
+    /// A category is derivable if some item bearing it only selects derivable
+    /// categories. The warnings report categories that nothing selects,
+    /// selected categories that no item bears, licensees that no licensor can
+    /// check, and a start category that is not derivable.
+    pub fn validate_grammar(&self) -> Vec<GrammarWarning> {
+        let mut items = Self::functional_heads();
+        for (_, categories) in self.lexicon.iter() {
+            items.extend(categories.into_iter().cloned());
+        }
+        
+        let selected = |feature: &Feature| match feature {
+            Feature::Selector(category) | Feature::StrongSelector(category) | Feature::AdjunctSelector(category) => Some(category.clone()),
+            Feature::Delayed(inner) => match inner.as_ref() {
+                Feature::Selector(category) => Some(category.clone()),
+                _ => None,
+            },
+            _ => None,
+        };
+        let categories = |item: &LexicalItem| -> Vec<String> {
+            item.features.iter()
+                .filter_map(|feature| match feature {
+                    Feature::Categorial(category) => Some(category.clone()),
+                    _ => None,
+                })
+                .collect()
+        };
+        
+        let mut borne = HashSet::new();
+        let mut selections = HashSet::new();
+        let mut licensors = HashSet::new();
+        let mut licensees = Vec::new();
+        for item in &items {
+            borne.extend(categories(item));
+            for feature in &item.features {
+                selections.extend(selected(feature));
+                match feature {
+                    Feature::Licensor(name) | Feature::CovertLicensor(name) => {
+                        licensors.insert(name.clone());
+                    },
+                    Feature::Licensee(name) if !licensees.contains(name) => licensees.push(name.clone()),
+                    _ => {},
+                }
+            }
+        }
+        
+        // Grow the derivable categories to a fixed point
+        let mut derivable: HashSet<String> = HashSet::new();
+        loop {
+            let before = derivable.len();
+            for item in &items {
+                let complete = item.features.iter()
+                    .filter_map(selected)
+                    .all(|category| derivable.contains(&category));
+                if complete {
+                    derivable.extend(categories(item));
+                }
+            }
+            if derivable.len() == before {
+                break;
+            }
+        }
+        
+        let mut warnings = Vec::new();
+        let sorted = |set: &HashSet<String>| {
+            let mut names: Vec<String> = set.iter().cloned().collect();
+            names.sort();
+            names
+        };
+        for category in sorted(&borne) {
+            if category != self.config.start_category && !selections.contains(&category) {
+                warnings.push(GrammarWarning::UnselectedCategory(category));
+            }
+        }
+        for category in sorted(&selections) {
+            if !borne.contains(&category) {
+                warnings.push(GrammarWarning::MissingCategory(category));
+            }
+        }
+        for licensee in licensees {
+            if !licensors.contains(&licensee) {
+                warnings.push(GrammarWarning::OrphanLicensee(licensee));
+            }
+        }
+        if !derivable.contains(&self.config.start_category) {
+            warnings.push(GrammarWarning::UnreachableStart(self.config.start_category.clone()));
+        }
+        
+        warnings
+    }
+    
+    /// Search for complete derivations of a sentence
+    ///
+    /// The search stops at the first complete derivation unless `all` is set,
+    /// in which case it continues up to the depth bound and returns the
+    /// distinct derivations it found.
+    fn derive(&mut self, sentence: &str, all: bool) -> Result<Vec<DerivationTree>, Error> {
+        let max_states = self.config.max_derivation_depth;
+        self.search(sentence, all, max_states)
+            .map(|search| search.derivations.into_iter().map(|(tree, _)| tree).collect())
+    }
+    
+    /// Search for complete derivations, taking at most `max_states` trees from the agenda
+    fn search(&mut self, sentence: &str, all: bool, max_states: usize) -> Result<Search, Error> {
+        let start = self.config.start_category.clone();
+        if !self.feature_types.is_categorial_registered(&start) {
+            return Err(Error::UnregisteredType(start));
+        }
+        
         // Initialize workspaces
         self.workspaces = WorkspaceRegistry::new();
         let _main_workspace_id = self.workspaces.new_workspace();
@@ -235,111 +449,137 @@ impl MinimalistParser {
         // Reset the next index counter
         self.next_index = 0;
         
-        let words: Vec<&str> = sentence.split_whitespace().collect();
+        let tokens = self.lexicon.group_phrases_with_spans(&self.tokenizer.tokenize(sentence));
+        let words: Vec<&str> = tokens.iter().map(|(word, _)| word.as_str()).collect();
         
-        // Create initial lexical items
+        // Create initial lexical items, each costing its weight
         let mut lexical_trees = Vec::new();
-        for word in &words {
+        for (word, (position, _)) in &tokens {
             let items = self.lexicon.get_categories(word);
             
             if items.is_empty() {
-                eprintln!("Unknown word: {}", word);
-                return None;
+                return Err(Error::UnknownWord { word: word.to_string(), position: *position });
             }
             
             for item in items {
-                lexical_trees.push(DerivationTree::leaf(item, self.get_next_index()));
+                self.tracer.step(StepKind::Lexical, || format!("{} at {}", item, position));
+                let weight = item.weight;
+                lexical_trees.push((DerivationTree::leaf(item, self.get_next_index()), weight));
             }
         }
         
         // Add null elements (functional heads that might be phonologically null)
-        lexical_trees.push(DerivationTree::leaf(
-            LexicalItem::new("", vec![
-                Feature::Categorial("T".to_string()),
-                Feature::Selector("V".to_string()),
-                Feature::Selector("D".to_string()),
-            ]),
-            self.get_next_index(),
-        ));
-        
-        lexical_trees.push(DerivationTree::leaf(
-            LexicalItem::new("", vec![
-                Feature::Categorial("C".to_string()),
-                Feature::Selector("T".to_string()),
-            ]),
-            self.get_next_index(),
-        ));
+        for item in Self::functional_heads() {
+            let index = self.get_next_index();
+            let weight = item.weight;
+            lexical_trees.push((DerivationTree::leaf(item, index), weight));
+        }
         
-        // Try to derive a complete sentence using a breadth-first search
-        let mut queue = VecDeque::new();
+        // Try to derive a complete sentence using a best-first agenda on
+        // accumulated cost, which is breadth first when nothing has a cost,
+        // identifying trees by their content so that trees differing only in
+        // node indices are explored once
+        let mut agenda = Agenda::new(max_states, |(tree, _): &(DerivationTree, f64)| tree.clone());
         
         // Initial state: individual lexical items
-        for tree in lexical_trees {
-            queue.push_back(tree);
+        for (tree, cost) in lexical_trees {
+            agenda.push((tree, cost), -cost);
         }
         
-        // Keep track of trees we've seen to avoid duplicates
-        let mut seen_trees = Vec::new();
+        // Trees processed so far with their costs, available to Merge with
+        let mut seen_trees: Vec<(DerivationTree, f64)> = Vec::new();
+        let merge_cost = self.config.merge_cost;
+        let move_cost = self.config.move_cost;
         
-        // BFS for derivation
-        for _ in 0..self.config.max_derivation_depth {
-            if queue.is_empty() {
-                break;
-            }
-            
-            let current_tree = queue.pop_front().unwrap();
-            
-            // Check if this is a complete derivation (only a C feature remains)
+        // Complete derivations, deduplicated by content
+        let mut derivations = Vec::new();
+        let mut complete = HashSet::new();
+        
+        agenda.run(|(current_tree, cost)| {
+            // Check if this is a complete derivation (only the start category remains)
             if let Some(Feature::Categorial(cat)) = current_tree.first_feature() {
-                if cat == "C" && current_tree.chain.head.features.len() == 1 {
+                if *cat == start && current_tree.chain.head.features.len() == 1 {
                     // This is a complete derivation
                     // Check if the derived string matches the input
-                    let derived = self.linearize(&current_tree);
+                    let matched = self.linearizations(&current_tree).iter()
+                        .any(|derived| self.matches_input(derived, &words));
                     
-                    if self.matches_input(&derived, &words) {
-                        return Some(current_tree);
+                    if matched && complete.insert(current_tree.clone()) {
+                        derivations.push((current_tree.clone(), cost));
+                        
+                        if !all {
+                            return None;
+                        }
                     }
                 }
             }
             
+            let mut successors = Vec::new();
+            
             // Try to apply Merge with all other trees we've seen
-            for other_tree in &seen_trees {
+            for (other_tree, other_cost) in &seen_trees {
+                let merged_cost = cost + other_cost + merge_cost;
+                
                 // Try merging current as specifier, other as head
                 if let Some(merged_tree) = self.apply_merge(&current_tree, other_tree) {
-                    // Check if we've seen this tree before
-                    if !seen_trees.iter().any(|tree| tree_equals(&merged_tree, tree)) {
-                        queue.push_back(merged_tree);
-                    }
+                    successors.push(((merged_tree, merged_cost), -merged_cost));
                 }
                 
                 // Try merging other as specifier, current as head
                 if let Some(merged_tree) = self.apply_merge(other_tree, &current_tree) {
-                    // Check if we've seen this tree before
-                    if !seen_trees.iter().any(|tree| tree_equals(&merged_tree, tree)) {
-                        queue.push_back(merged_tree);
-                    }
+                    successors.push(((merged_tree, merged_cost), -merged_cost));
                 }
             }
             
             // Try to apply Move to the current tree
             if let Some(moved_tree) = self.apply_move(&current_tree) {
-                // Check if we've seen this tree before
-                if !seen_trees.iter().any(|tree| tree_equals(&moved_tree, tree)) {
-                    queue.push_back(moved_tree);
-                }
+                let moved_cost = cost + move_cost;
+                successors.push(((moved_tree, moved_cost), -moved_cost));
             }
             
             // Add current tree to seen trees
-            seen_trees.push(current_tree);
-        }
+            seen_trees.push((current_tree, cost));
+            Some(successors)
+        });
         
-        // No complete derivation found
-        eprintln!("No valid derivation found for: {}", sentence);
-        None
+        // The budget ran out if trees were left unexplored without a result
+        let exceeded = agenda.is_exhausted() && !agenda.is_empty() && (all || derivations.is_empty());
+        let best_partial = seen_trees.into_iter()
+            .map(|(tree, _)| tree)
+            .rev()
+            .max_by_key(|tree| tree.get_yield().len());
+        
+        Ok(Search { derivations, exceeded, best_partial })
     }
     
-    /// Apply the Merge operation to two trees
+    /// Apply the Merge operation to two trees, numbering new nodes from the parser's counter
     fn apply_merge(&mut self, spec: &DerivationTree, head: &DerivationTree) -> Option<DerivationTree> {
+        let mut index = self.next_index;
+        let merged = self.try_merge(spec, head, &mut index);
+        self.next_index = index;
+        if let Some(tree) = &merged {
+            self.tracer.step(StepKind::Merge, || format!("{} + {} => {}", spec.chain.head, head.chain.head, tree.chain.head));
+        }
+        merged
+    }
+    
+    /// Apply the Move operation, numbering new nodes from the parser's counter
+    fn apply_move(&mut self, tree: &DerivationTree) -> Option<DerivationTree> {
+        let mut index = self.next_index;
+        let moved = self.try_move(tree, &mut index);
+        self.next_index = index;
+        if let Some(moved_tree) = &moved {
+            self.tracer.step(StepKind::Move, || format!("{} => {}", tree.chain.head, moved_tree.chain.head));
+        }
+        moved
+    }
+    
+    /// Merge a specifier or complement into a head, if their features allow it
+    ///
+    /// The configured merge strategies are tried in order. A new node takes
+    /// its index from `index`, which is then advanced, so derivations can be
+    /// driven step by step without a mutable parser.
+    pub fn try_merge(&self, spec: &DerivationTree, head: &DerivationTree, index: &mut usize) -> Option<DerivationTree> {
         // If phases are enabled, check phase constraints
         if self.config.phase_config.enforce_pic {
             // If the head is a completed phase, only its edge should be accessible
@@ -378,13 +618,17 @@ impl MinimalistParser {
                                                 spec.chain.head.phonetic_form),
                                             features: head_features,
                                             agreement_features: None,
+                                            semantics: None,
+                                            weight: 0.0,
                                         }),
                                         children: Some((Box::new(spec_new), Box::new(head_new))),
-                                        index: self.get_next_index(),
+                                        index: next_index(index),
                                         is_adjunct: false,
                                         delayed_features: Vec::new(),
                                         is_phase: false,
                                         phase_completed: false,
+                                        specifiers: Vec::new(),
+                                        category: None,
                                     });
                                 }
                                 
@@ -393,7 +637,7 @@ impl MinimalistParser {
                                     spec_new,
                                     head_new,
                                     head_features,
-                                    self.get_next_index(),
+                                    next_index(index),
                                 ));
                             }
                         }
@@ -419,7 +663,7 @@ impl MinimalistParser {
                                         return Some(DerivationTree::pair_merge(
                                             head_new,
                                             spec_new,
-                                            self.get_next_index(),
+                                            next_index(index),
                                         ));
                                     }
                                 }
@@ -437,7 +681,7 @@ impl MinimalistParser {
                                     return Some(DerivationTree::late_merge(
                                         head.clone(),
                                         spec.clone(),
-                                        self.get_next_index(),
+                                        next_index(index),
                                     ));
                                 }
                             }
@@ -450,24 +694,35 @@ impl MinimalistParser {
         None
     }
     
-    /// Apply the Move operation
-    fn apply_move(&mut self, tree: &DerivationTree) -> Option<DerivationTree> {
+    /// Move the element whose licensee matches the first licensor of a tree
+    ///
+    /// A new node takes its index from `index`, which is then advanced.
+    pub fn try_move(&self, tree: &DerivationTree, index: &mut usize) -> Option<DerivationTree> {
         // Look for a licensor feature in the tree's head
-        if let Some(tree_feature) = tree.first_feature() {
-            if let Feature::Licensor(lic) = tree_feature {
-                // Find a matching licensee feature in the tree
-                if let Some((moved_chain, new_base)) = self.find_movable_element(tree, &lic) {
-                    let mut new_tree = new_base;
-                    new_tree.remove_first_feature(); // Remove the licensor feature
-                    
-                    // Return the moved tree
-                    return Some(DerivationTree::r#move(
-                        new_tree,
-                        moved_chain,
-                        tree.chain.head.features[1..].to_vec(), // Keep remaining features
-                        self.get_next_index(),
-                    ));
+        if let Some(Feature::Licensor(lic) | Feature::CovertLicensor(lic)) = tree.first_feature() {
+            let overt = !tree.first_feature().is_some_and(Feature::is_covert);
+            
+            // Find a matching licensee feature in the tree
+            if let Some((moved_chain, new_base)) = self.find_movable_element(tree, lic, overt) {
+                let mut new_tree = new_base;
+                new_tree.remove_first_feature(); // Remove the licensor feature
+                
+                // A head that already hosts a specifier stacks another one
+                // instead of projecting again
+                let multiple_specifiers = self.config.movement_strategies.contains(&MovementStrategy::MultiSpecifier);
+                if multiple_specifiers && overt && tree.chain.has_traces() && tree.chain.overt {
+                    new_tree.specifiers.push(moved_chain);
+                    return Some(new_tree);
                 }
+                
+                // Return the moved tree
+                return Some(DerivationTree::r#move(
+                    new_tree,
+                    moved_chain,
+                    tree.chain.head.features[1..].to_vec(), // Keep remaining features
+                    next_index(index),
+                    overt,
+                ));
             }
         }
         
@@ -475,7 +730,12 @@ impl MinimalistParser {
     }
     
     /// Find a movable element with a matching licensee feature
-    fn find_movable_element(&self, tree: &DerivationTree, licensor: &str) -> Option<(Chain, DerivationTree)> {
+    ///
+    /// The element is replaced by a trace in the returned base, unless the
+    /// movement is covert, in which case it stays in place with its licensee
+    /// checked. The chain keeps the element as its lower copy and pronounces
+    /// the copies chosen by the configuration.
+    fn find_movable_element(&self, tree: &DerivationTree, licensor: &str, overt: bool) -> Option<(Chain, DerivationTree)> {
         fn find_internal(
             tree: &DerivationTree, 
             licensor: &str, 
@@ -492,6 +752,8 @@ impl MinimalistParser {
                                 phonetic_form: tree.chain.head.phonetic_form.clone(),
                                 features: tree.chain.head.features[1..].to_vec(), // Remove licensee
                                 agreement_features: tree.chain.agreement.clone(),
+                                semantics: None,
+                                weight: 0.0,
                             },
                             Vec::new(), // Will be filled in later
                         ),
@@ -527,11 +789,20 @@ impl MinimalistParser {
             tree: &DerivationTree, 
             path: &[bool], 
             path_index: usize,
-            trace_indices: &mut Vec<usize>
+            overt: bool,
+            chain: &mut Chain
         ) -> DerivationTree {
             if path_index >= path.len() {
                 // We've reached the leaf to replace with a trace
-                trace_indices.push(tree.index);
+                // The copy carries the meaning of the whole constituent
+                let mut copy = tree.chain.head.clone();
+                copy.semantics = tree.logical_form();
+                chain.push_copy(tree.index, copy);
+                
+                // A covert mover is still pronounced here
+                if !overt {
+                    return tree.without_first_feature();
+                }
                 
                 // Return an empty trace node
                 return DerivationTree {
@@ -542,6 +813,8 @@ impl MinimalistParser {
                     delayed_features: Vec::new(),
                     is_phase: false,
                     phase_completed: false,
+                    specifiers: Vec::new(),
+                    category: None,
                 };
             }
             
@@ -553,12 +826,12 @@ impl MinimalistParser {
                     // Go right
                     new_tree.children = Some((
                         left.clone(),
-                        Box::new(create_moved_tree(right, path, path_index + 1, trace_indices))
+                        Box::new(create_moved_tree(right, path, path_index + 1, overt, chain))
                     ));
                 } else {
                     // Go left
                     new_tree.children = Some((
-                        Box::new(create_moved_tree(left, path, path_index + 1, trace_indices)),
+                        Box::new(create_moved_tree(left, path, path_index + 1, overt, chain)),
                         right.clone()
                     ));
                 }
@@ -576,11 +849,8 @@ impl MinimalistParser {
         
         if find_internal(tree, licensor, &mut path, &mut moved) {
             if let Some((mut chain, path)) = moved {  // Add 'mut' here
-                let mut trace_indices = Vec::new();
-                let new_tree = create_moved_tree(tree, &path, 0, &mut trace_indices);
-                
-                // Update the chain with trace indices
-                chain.tail = trace_indices;
+                chain.pronounce = self.config.pronounce;
+                let new_tree = create_moved_tree(tree, &path, 0, overt, &mut chain);
                 
                 return Some((chain, new_tree));
             }
@@ -589,29 +859,156 @@ impl MinimalistParser {
         None
     }
     
-    /// Linearize a derivation tree to get the surface string
-    pub fn linearize(&self, tree: &DerivationTree) -> Vec<String> {
-        fn collect_phonetic_forms(tree: &DerivationTree, forms: &mut Vec<(String, usize)>) {
-            // Add this node's phonetic form if non-empty and not a trace
-            if !tree.chain.head.phonetic_form.is_empty() && !tree.chain.tail.contains(&tree.index) {
-                forms.push((tree.chain.head.phonetic_form.clone(), tree.index));
+    /// Apply Agree between the head of a tree and a goal in its domain
+    ///
+    /// The probe is the lexical head of `tree`; its agreement features that are
+    /// still `Unspecified` are valued by the closest goal that supplies them.
+    /// When the PIC is enforced, a completed phase only exposes its edge, so a
+    /// goal inside the phase cannot be reached.
+    pub fn apply_agree(&self, tree: &DerivationTree) -> Option<DerivationTree> {
+        // Walk down the projection line to the probe, collecting the
+        // non-head daughters that make up its search domain
+        let mut domain = Vec::new();
+        let mut probe = tree;
+        while let Some((non_head, head)) = &probe.children {
+            domain.push(non_head.as_ref());
+            probe = head;
+        }
+        
+        let probe_agr = probe.chain.head.agreement_features.as_ref()?;
+        let unvalued: Vec<String> = probe_agr.features.iter()
+            .filter(|(_, value)| **value == FeatureValue::Unspecified)
+            .map(|(name, _)| name.clone())
+            .collect();
+        
+        if unvalued.is_empty() {
+            return None;
+        }
+        
+        // Search the complement first, then any specifiers
+        for subtree in domain.into_iter().rev() {
+            if let Some(goal_agr) = self.find_goal(subtree, &unvalued) {
+                let mut valued = probe_agr.clone();
+                for name in &unvalued {
+                    if let Some(value) = goal_agr.get(name) {
+                        valued.add(name, value.clone());
+                    }
+                }
+                
+                let mut result = tree.clone();
+                set_probe_agreement(&mut result, &valued);
+                return Some(result);
+            }
+        }
+        
+        None
+    }
+    
+    /// Find the closest goal in a subtree that values one of the given features
+    fn find_goal<'a>(&self, tree: &'a DerivationTree, unvalued: &[String]) -> Option<&'a FeatureStructure> {
+        let mut queue = VecDeque::new();
+        queue.push_back(tree);
+        
+        while let Some(node) = queue.pop_front() {
+            // Only the edge of a completed phase is visible from outside it
+            if self.config.phase_config.enforce_pic && node.phase_completed && self.phase_checker.is_phase_head(node) {
+                queue.extend(self.phase_checker.get_phase_edge(node));
+                continue;
             }
             
-            // Recursively collect from children
-            if let Some((left, right)) = &tree.children {
-                collect_phonetic_forms(left, forms);
-                collect_phonetic_forms(right, forms);
+            if let Some(agr) = &node.chain.agreement {
+                let values_probe = unvalued.iter().any(|name| {
+                    matches!(agr.get(name), Some(value) if *value != FeatureValue::Unspecified)
+                });
+                
+                if values_probe {
+                    return Some(agr);
+                }
+            }
+            
+            if let Some((left, right)) = &node.children {
+                queue.push_back(left);
+                queue.push_back(right);
             }
         }
         
-        let mut forms = Vec::new();
-        collect_phonetic_forms(tree, &mut forms);
+        None
+    }
+    
+    /// Linearize a derivation tree to get the surface string
+    ///
+    /// Sisters ordered by a linear precedence rule follow it; unordered
+    /// sisters keep their structural order.
+    pub fn linearize(&self, tree: &DerivationTree) -> Vec<String> {
+        self.linearizations(tree).swap_remove(0)
+    }
+    
+    /// Get every surface string of a derivation tree allowed by the linear precedence rules
+    ///
+    /// The structural order comes first. Each pair of sisters whose
+    /// categories are unordered doubles the number of strings.
+    pub fn linearizations(&self, tree: &DerivationTree) -> Vec<Vec<String>> {
+        let lp = &self.config.lp_rules;
+        let forms = |linearized: Vec<(String, Position)>| -> Vec<String> {
+            linearized.into_iter().map(|(form, _)| form).collect()
+        };
+        if lp.is_empty() {
+            return vec![forms(tree.linearize_structural())];
+        }
         
-        // Sort by index to get the correct word order
-        forms.sort_by_key(|(_, idx)| *idx);
+        // Orders fixed by a rule, and the positions of unordered sisters
+        let order = |first: &DerivationTree, second: &DerivationTree| {
+            let (first, second) = (Self::sister_categories(first), Self::sister_categories(second));
+            let pairs = || first.iter().flat_map(|a| second.iter().map(move |b| (a, b)));
+            if pairs().any(|(a, b)| lp.is_unordered(a, b)) {
+                None
+            } else {
+                Some(pairs().any(|(a, b)| lp.precedes(b, a)))
+            }
+        };
+        let mut unordered = Vec::new();
+        tree.linearize_with(&mut |position, first, second| {
+            let fixed = order(first, second);
+            if fixed.is_none() {
+                unordered.push(position.clone());
+            }
+            fixed.unwrap_or(false)
+        });
         
-        // Return just the words
-        forms.into_iter().map(|(form, _)| form).collect()
+        let mut strings: Vec<Vec<String>> = Vec::new();
+        for choice in 0..1usize << unordered.len() {
+            let derived = forms(tree.linearize_with(&mut |position, first, second| {
+                match order(first, second) {
+                    Some(swapped) => swapped,
+                    None => unordered.iter().position(|p| p == position)
+                        .is_some_and(|bit| choice & (1 << bit) != 0),
+                }
+            }));
+            if !strings.contains(&derived) {
+                strings.push(derived);
+            }
+        }
+        strings
+    }
+    
+    /// The categories a daughter may be ordered by
+    ///
+    /// A daughter's category has usually been checked by the time it is
+    /// linearized, so the category its selection checked is used along with
+    /// any it still has.
+    fn sister_categories(node: &DerivationTree) -> Vec<String> {
+        let mut categories: Vec<String> = node.chain.head.features.iter()
+            .filter_map(|feature| match feature {
+                Feature::Categorial(category) => Some(category.clone()),
+                _ => None,
+            })
+            .collect();
+        if let Some(category) = &node.category {
+            if !categories.contains(category) {
+                categories.push(category.clone());
+            }
+        }
+        categories
     }
     
     /// Check if the derived string matches the input
@@ -630,7 +1027,11 @@ impl MinimalistParser {
     }
     
     /// Handle sideward movement between workspaces
-    fn sideward_move(
+    ///
+    /// Nunes-style movement copies the constituent pronounced as the chain's
+    /// head out of the source workspace, leaving a trace in the chain, and
+    /// merges the copy into the target workspace.
+    pub fn sideward_move(
         &mut self,
         source_workspace_id: usize,
         target_workspace_id: usize,
@@ -644,33 +1045,29 @@ impl MinimalistParser {
         match movement_type {
             SidewardMovementType::NunesStyle => {
                 // Classic Nunes-style sideward movement:
-                // 1. Copy from source
+                // 1. Copy from source, leaving a trace
                 // 2. Merge with target
                 // 3. Chain formation across workspaces
+                let (mut copy, source_with_trace, trace_index) = extract_constituent(&source_tree, &moved_chain.head.phonetic_form)?;
                 
-                // The moved element acts as the specifier in the target workspace
-                let _head_features = moved_chain.head.features.clone(); // Add underscore to unused variable
+                // The copy still bears the features the chain moves with
+                copy.chain.head.features = moved_chain.head.features.clone();
                 
-                // Create trace in source workspace (replacing the moved element)
-                let source_with_trace = source_tree.clone(); // Remove 'mut' as it's not modified
-                // This is simplified; in reality we would need to find and replace the actual source
+                // The lexical head of the copy heads the chain through the trace
+                let mut head = &mut copy;
+                while let Some((_, right)) = head.children.as_mut() {
+                    head = &mut **right;
+                }
+                head.chain.tail = moved_chain.tail.clone();
+                head.chain.copies = moved_chain.copies.clone();
+                head.chain.tail.push(trace_index);
                 
-                // Create a new derivation tree in the target workspace
-                let result = DerivationTree {
-                    chain: moved_chain.clone(),
-                    children: Some((Box::new(target_tree), Box::new(DerivationTree::leaf(
-                        LexicalItem::empty(),
-                        self.get_next_index(),
-                    )))),
-                    index: self.get_next_index(),
-                    is_adjunct: false,
-                    delayed_features: Vec::new(),
-                    is_phase: false,
-                    phase_completed: false,
-                };
+                // The copy is merged as an argument in the target workspace
+                let result = self.apply_merge(&copy, &target_tree)?;
                 
                 // Update the workspaces
                 self.workspaces.add_tree(source_workspace_id, source_with_trace);
+                self.workspaces.add_tree(target_workspace_id, result.clone());
                 
                 Some(result)
             },
@@ -694,6 +1091,8 @@ impl MinimalistParser {
                     delayed_features: Vec::new(),
                     is_phase: false,
                     phase_completed: false,
+                    specifiers: Vec::new(),
+                    category: None,
                 };
                 
                 // Add to the new workspace
@@ -719,6 +1118,8 @@ impl MinimalistParser {
                     delayed_features: Vec::new(),
                     is_phase: false,
                     phase_completed: false,
+                    specifiers: Vec::new(),
+                    category: None,
                 };
                 
                 Some(result)
@@ -745,6 +1146,16 @@ impl MinimalistParser {
     }
 }
 
+/// The result of a derivation search
+struct Search {
+    /// The complete derivations found, with their costs
+    derivations: Vec<(DerivationTree, f64)>,
+    /// Whether the search stopped because it ran out of steps
+    exceeded: bool,
+    /// The explored tree pronouncing the most words
+    best_partial: Option<DerivationTree>,
+}
+
 impl Parser for MinimalistParser {
     type Cat = LexicalItem;
     type Node = DerivationTree;
@@ -756,10 +1167,40 @@ impl Parser for MinimalistParser {
         parser.parse_internal(sentence)
     }
     
+    fn parse_result(&self, sentence: &str) -> Result<Self::Node, Error> {
+        let mut parser = self.clone();
+        parser.parse_result_internal(sentence)
+    }
+    
+    /// Parse a sentence, taking at most `budget` trees from the agenda
+    ///
+    /// When the budget runs out, the best partial derivation is the explored
+    /// tree pronouncing the most words.
+    fn parse_within(&self, sentence: &str, budget: StepBudget) -> ParseOutcome<Self::Node> {
+        let mut parser = self.clone();
+        match parser.search(sentence, false, budget.max_steps) {
+            Ok(search) => match search.derivations.into_iter().next() {
+                Some((tree, _)) => ParseOutcome::Complete(tree),
+                None if search.exceeded => ParseOutcome::BudgetExceeded { best_partial: search.best_partial },
+                None => ParseOutcome::Exhausted,
+            },
+            Err(_) => ParseOutcome::Exhausted,
+        }
+    }
+    
+    fn parse_all(&self, sentence: &str) -> Vec<Self::Node> {
+        let mut parser = self.clone();
+        parser.parse_all_internal(sentence)
+    }
+    
     fn add_to_lexicon(&mut self, word: &str, category: Self::Cat) {
         self.lexicon.add(word, category);
     }
     
+    fn lexicon(&self) -> Option<&Lexicon<Self::Cat>> {
+        Some(&self.lexicon)
+    }
+    
     fn config(&self) -> &Self::Config {
         &self.config
     }
@@ -782,6 +1223,7 @@ impl Parser for MinimalistParser {
                 "strong_selector" => Feature::StrongSelector(feat_name.to_string()),
                 "adjunct_selector" => Feature::AdjunctSelector(feat_name.to_string()),
                 "licensor" => Feature::Licensor(feat_name.to_string()),
+                "covert_licensor" => Feature::CovertLicensor(feat_name.to_string()),
                 "licensee" => Feature::Licensee(feat_name.to_string()),
                 "phase" => Feature::Phase(feat_name.to_string()),
                 _ => return Err(crate::common::error::Error::ParseError(
@@ -796,12 +1238,35 @@ impl Parser for MinimalistParser {
     }
 }
 
-// Helper function to compare trees since DerivationTree doesn't implement Eq
-fn tree_equals(a: &DerivationTree, b: &DerivationTree) -> bool {
-    // Simple comparison based on index and features
-    a.index == b.index && 
-    a.chain.head.features == b.chain.head.features &&
-    a.chain.head.phonetic_form == b.chain.head.phonetic_form
+/// Take the next node index from a counter
+fn next_index(index: &mut usize) -> usize {
+    let next = *index;
+    *index += 1;
+    next
+}
+
+/// Split off the highest constituent pronounced as `form`
+///
+/// Returns the constituent, the tree with a trace in its place, and the
+/// index of the trace.
+fn extract_constituent(tree: &DerivationTree, form: &str) -> Option<(DerivationTree, DerivationTree, usize)> {
+    if tree.chain.head.phonetic_form == form {
+        let trace = DerivationTree::leaf(LexicalItem::empty(), tree.index);
+        return Some((tree.clone(), trace, tree.index));
+    }
+    
+    let (left, right) = tree.children.as_ref()?;
+    let (constituent, left, right, index) = match extract_constituent(left, form) {
+        Some((constituent, left, index)) => (constituent, left, (**right).clone(), index),
+        None => {
+            let (constituent, right, index) = extract_constituent(right, form)?;
+            (constituent, (**left).clone(), right, index)
+        },
+    };
+    
+    let mut rest = tree.clone();
+    rest.children = Some((Box::new(left), Box::new(right)));
+    Some((constituent, rest, index))
 }
 
 /// Process a merge operation between two trees
@@ -811,6 +1276,16 @@ fn process_merge(_parser: &mut MinimalistParser, _left: &DerivationTree, _right:
     None
 }
 
+/// Record valued agreement on every node of a tree's projection line
+fn set_probe_agreement(tree: &mut DerivationTree, valued: &FeatureStructure) {
+    tree.chain.agreement = Some(valued.clone());
+    
+    match &mut tree.children {
+        Some((_, head)) => set_probe_agreement(head, valued),
+        None => tree.chain.head.agreement_features = Some(valued.clone()),
+    }
+}
+
 /// Parse a feature string into a Feature enum
 pub fn parse_feature(feature_str: &str) -> Result<Feature, crate::common::error::Error> {
     // Split the feature string into type and name
@@ -831,6 +1306,7 @@ pub fn parse_feature(feature_str: &str) -> Result<Feature, crate::common::error:
         "sel+" => Ok(Feature::StrongSelector(feat_name.to_string())),
         "sel*" => Ok(Feature::AdjunctSelector(feat_name.to_string())),
         "licensor" => Ok(Feature::Licensor(feat_name.to_string())),
+        "covert_licensor" => Ok(Feature::CovertLicensor(feat_name.to_string())),
         "licensee" => Ok(Feature::Licensee(feat_name.to_string())),
         "phase" => Ok(Feature::Phase(feat_name.to_string())),
         _ => Err(crate::common::error::Error::ParseError(
@@ -842,6 +1318,9 @@ pub fn parse_feature(feature_str: &str) -> Result<Feature, crate::common::error:
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::ToConll;
+    use crate::mg::derivation::Position;
+    use crate::common::LambdaTerm;
     
     // Helper function to create a minimalist parser with basic lexicon
     fn setup_test_parser() -> MinimalistParser {
@@ -982,6 +1461,136 @@ mod tests {
         assert_eq!(linearized, vec!["what", "see"]);
     }
 
+    #[test]
+    fn test_structural_linearization() {
+        let mut parser = setup_test_parser();
+        
+        // Number the object before the verb so that index order is wrong
+        let what = LexicalItem::new("what", vec![
+            Feature::Categorial("D".to_string()),
+            Feature::Licensee("wh".to_string()),
+        ]);
+        let see = LexicalItem::new("see", vec![
+            Feature::Selector("D".to_string()),
+            Feature::Categorial("V".to_string()),
+        ]);
+        let c = LexicalItem::new("", vec![
+            Feature::Selector("V".to_string()),
+            Feature::Licensor("wh".to_string()),
+            Feature::Categorial("C".to_string()),
+        ]);
+        
+        let vp = parser.apply_merge(&DerivationTree::leaf(what, 0), &DerivationTree::leaf(see, 1)).unwrap();
+        assert_eq!(parser.linearize(&vp), vec!["see", "what"]);
+        
+        let cp = parser.apply_merge(&vp, &DerivationTree::leaf(c, 2)).unwrap();
+        let moved = parser.apply_move(&cp).unwrap();
+        
+        // The wh-phrase is spelled out at its landing site and its trace is silent
+        assert_eq!(moved.linearize_structural(), vec![
+            ("what".to_string(), Position(vec![])),
+            ("see".to_string(), Position(vec![0, 0, 1])),
+        ]);
+    }
+
+    #[test]
+    fn test_covert_movement() {
+        let mut parser = setup_test_parser();
+        
+        let everyone = LexicalItem::new("everyone", vec![
+            Feature::Categorial("D".to_string()),
+            Feature::Licensee("q".to_string()),
+        ]).with_semantics(LambdaTerm::constant("every"));
+        let saw = LexicalItem::new("saw", vec![
+            Feature::Selector("D".to_string()),
+            Feature::Selector("D".to_string()),
+            Feature::Categorial("V".to_string()),
+        ]).with_semantics(LambdaTerm::constant("see"));
+        let john = LexicalItem::new("John", vec![Feature::Categorial("D".to_string())])
+            .with_semantics(LambdaTerm::constant("john"));
+        
+        // In situ the quantifier is only the object of the verb
+        let everyone = DerivationTree::leaf(everyone, parser.get_next_index());
+        let saw = DerivationTree::leaf(saw, parser.get_next_index());
+        let vp = parser.apply_merge(&everyone, &saw).unwrap();
+        let john = DerivationTree::leaf(john, parser.get_next_index());
+        let vp = parser.apply_merge(&john, &vp).unwrap();
+        assert_eq!(vp.logical_form().unwrap().to_string(), "see(every)(john)");
+        
+        let clause = |parser: &mut MinimalistParser, licensor: Feature| {
+            let c = LexicalItem::new("", vec![
+                Feature::Selector("V".to_string()),
+                licensor,
+                Feature::Categorial("C".to_string()),
+            ]);
+            let c = DerivationTree::leaf(c, parser.get_next_index());
+            let cp = parser.apply_merge(&vp, &c).unwrap();
+            parser.apply_move(&cp).unwrap()
+        };
+        
+        // Quantifier raising puts "everyone" above the subject at LF only
+        let raised = clause(&mut parser, Feature::covert_licensor("q"));
+        assert_eq!(raised.chain.head.phonetic_form, "everyone");
+        assert!(raised.chain.has_traces() && !raised.chain.overt);
+        assert_eq!(raised.first_feature(), Some(&Feature::Categorial("C".to_string())));
+        assert_eq!(parser.linearize(&raised), vec!["John", "saw", "everyone"]);
+        
+        // but takes scope over the clause, subject included
+        assert_eq!(raised.logical_form().unwrap().to_string(), "every(λx.see(x)(john))");
+        
+        // Overt movement to the same position changes the word order
+        let fronted = clause(&mut parser, Feature::licensor("q"));
+        assert_eq!(parser.linearize(&fronted), vec!["everyone", "John", "saw"]);
+        assert_eq!(fronted.logical_form(), raised.logical_form());
+    }
+    
+    #[test]
+    fn test_multiple_specifiers() {
+        // Bulgarian multiple wh-fronting: "koj kakvo kupil" (who what bought)
+        let derive = |strategies: Vec<MovementStrategy>| {
+            let mut parser = setup_test_parser();
+            parser.config.movement_strategies = strategies;
+            
+            let wh = |form: &str| LexicalItem::new(form, vec![
+                Feature::Categorial("D".to_string()),
+                Feature::Licensee("wh".to_string()),
+            ]);
+            let kupil = LexicalItem::new("kupil", vec![
+                Feature::Selector("D".to_string()),
+                Feature::Selector("D".to_string()),
+                Feature::Categorial("V".to_string()),
+            ]);
+            let c = LexicalItem::new("", vec![
+                Feature::Selector("V".to_string()),
+                Feature::Licensor("wh".to_string()),
+                Feature::Licensor("wh".to_string()),
+                Feature::Categorial("C".to_string()),
+            ]);
+            
+            let vp = parser.apply_merge(&DerivationTree::leaf(wh("kakvo"), 0), &DerivationTree::leaf(kupil, 1)).unwrap();
+            let vp = parser.apply_merge(&DerivationTree::leaf(wh("koj"), 2), &vp).unwrap();
+            let cp = parser.apply_merge(&vp, &DerivationTree::leaf(c, 3)).unwrap();
+            let once = parser.apply_move(&cp).unwrap();
+            let twice = parser.apply_move(&once).unwrap();
+            (once, twice, parser)
+        };
+        
+        // Both wh-phrases land in specifiers of the same C, the second tucking in
+        let (once, twice, parser) = derive(vec![MovementStrategy::Standard, MovementStrategy::MultiSpecifier]);
+        assert_eq!(twice.depth(), once.depth());
+        assert_eq!(twice.chain.head.phonetic_form, "koj");
+        assert_eq!(twice.specifiers.len(), 1);
+        assert_eq!(twice.specifiers[0].head.phonetic_form, "kakvo");
+        assert_eq!(twice.first_feature(), Some(&Feature::Categorial("C".to_string())));
+        assert_eq!(parser.linearize(&twice), vec!["koj", "kakvo", "kupil"]);
+        
+        // Without multiple specifiers C projects again for the second mover
+        let (once, twice, parser) = derive(vec![MovementStrategy::Standard]);
+        assert_eq!(twice.depth(), once.depth() + 1);
+        assert!(twice.specifiers.is_empty());
+        assert_eq!(parser.linearize(&twice), vec!["kakvo", "koj", "kupil"]);
+    }
+    
     #[test]
     fn test_feature_registry() {
         let registry = FeatureTypeRegistry::default();
@@ -1123,6 +1732,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_sideward_movement() {
+        let mut parser = setup_test_parser();
+        parser.config.enable_parallel_workspaces = true;
+        
+        // "file ... without reading paper": the object of the adjunct
+        // moves sideways to the main verb
+        let paper = LexicalItem::new("paper", vec![Feature::Categorial("D".to_string())]);
+        let reading = LexicalItem::new("reading", vec![
+            Feature::Selector("D".to_string()),
+            Feature::Categorial("V".to_string()),
+        ]);
+        let file = LexicalItem::new("file", vec![
+            Feature::Selector("D".to_string()),
+            Feature::Categorial("V".to_string()),
+        ]);
+        
+        let adjunct = parser.apply_merge(&DerivationTree::leaf(paper.clone(), 0), &DerivationTree::leaf(reading, 1)).unwrap();
+        let source = parser.workspaces.new_workspace();
+        let target = parser.workspaces.new_workspace();
+        parser.workspaces.add_tree(source, adjunct);
+        parser.workspaces.add_tree(target, DerivationTree::leaf(file, 2));
+        
+        let result = parser.sideward_move(source, target, Chain::new(paper), SidewardMovementType::NunesStyle).unwrap();
+        assert_eq!(parser.linearize(&result), vec!["file", "paper"]);
+        assert_eq!(parser.workspaces.get_tree(target), Some(&result));
+        
+        // The source keeps a trace of the moved object, linked into its chain
+        let source_tree = parser.workspaces.get_tree(source).unwrap();
+        assert_eq!(parser.linearize(source_tree), vec!["reading"]);
+        
+        let (copy, _) = result.children.as_ref().unwrap();
+        assert_eq!(copy.chain.head.phonetic_form, "paper");
+        assert_eq!(copy.chain.tail, vec![0]);
+    }
+    
     #[test]
     fn test_linearization() {
         let parser = setup_test_parser();
@@ -1184,4 +1829,538 @@ mod tests {
         // "the cat chases the dog"
         assert_eq!(linearized, vec!["the", "cat", "the", "dog", "chases"]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_linearization_follows_structure() {
+        let parser = setup_test_parser();
+
+        // Create a complex structure to test linearization
+        let det = LexicalItem::new("the", vec![
+            Feature::Categorial("D".to_string()),
+        ]);
+
+        let noun = LexicalItem::new("cat", vec![
+            Feature::Categorial("N".to_string()),
+        ]);
+
+        let verb = LexicalItem::new("chases", vec![
+            Feature::Categorial("V".to_string()),
+        ]);
+
+        let det2 = LexicalItem::new("the", vec![
+            Feature::Categorial("D".to_string()),
+        ]);
+
+        let noun2 = LexicalItem::new("dog", vec![
+            Feature::Categorial("N".to_string()),
+        ]);
+
+        // Create the phrase structure, with each determiner heading its DP
+        let dp1 = DerivationTree::merge(
+            DerivationTree::leaf(noun, 1),
+            DerivationTree::leaf(det, 0),
+            vec![Feature::Categorial("DP".to_string())],
+            2
+        );
+
+        let dp2 = DerivationTree::merge(
+            DerivationTree::leaf(noun2, 4),
+            DerivationTree::leaf(det2, 3),
+            vec![Feature::Categorial("DP".to_string())],
+            5
+        );
+
+        // The object is the complement of the verb, the subject its specifier
+        let vp = DerivationTree::merge(
+            dp2,
+            DerivationTree::leaf(verb, 6),
+            vec![Feature::Categorial("VP".to_string())],
+            7
+        );
+
+        let full_sentence = DerivationTree::merge(
+            dp1,
+            vp,
+            vec![Feature::Categorial("S".to_string())],
+            8
+        );
+
+        // Linearize
+        let linearized = parser.linearize(&full_sentence);
+
+        // The linearization follows the structure, not the index order
+        assert_eq!(linearized, vec!["the", "cat", "chases", "the", "dog"]);
+    }
+
+    // Build an embedded clause [CP spec [C' dat [TP ... T]]] and transfer it
+    fn embedded_clause(subject_at_edge: bool) -> DerivationTree {
+        let subject = LexicalItem::with_agreement("Marie", vec![
+            Feature::Categorial("D".to_string()),
+        ], FeatureStructure::with_feature("num", FeatureValue::Atomic("sg".to_string())));
+        
+        let t = LexicalItem::new("", vec![Feature::Categorial("T".to_string())]);
+        
+        let c = LexicalItem::new("dat", vec![
+            Feature::Categorial("C".to_string()),
+            Feature::Phase("C".to_string()),
+        ]);
+        
+        let adverb = LexicalItem::new("gisteren", vec![Feature::Categorial("Adv".to_string())]);
+        
+        let (tp, spec) = if subject_at_edge {
+            (DerivationTree::leaf(t, 1), DerivationTree::leaf(subject, 0))
+        } else {
+            let tp = DerivationTree::merge(
+                DerivationTree::leaf(subject, 0),
+                DerivationTree::leaf(t, 1),
+                vec![Feature::Categorial("T".to_string())],
+                5
+            );
+            (tp, DerivationTree::leaf(adverb, 6))
+        };
+        
+        let c_bar = DerivationTree::merge(tp, DerivationTree::leaf(c, 2), vec![Feature::Categorial("C".to_string())], 3);
+        let mut cp = DerivationTree::merge(spec, c_bar, vec![
+            Feature::Categorial("C".to_string()),
+            Feature::Phase("C".to_string()),
+        ], 4);
+        cp.complete_phase();
+        cp
+    }
+    
+    #[test]
+    fn test_agree_respects_phase_edge() {
+        let parser = MinimalistParser::new();
+        
+        // A matrix complementizer with unvalued number probes into the embedded CP
+        let probe = LexicalItem::with_agreement("da", vec![
+            Feature::Categorial("C".to_string()),
+        ], FeatureStructure::with_feature("num", FeatureValue::Unspecified));
+        
+        let at_edge = DerivationTree::merge(
+            embedded_clause(true),
+            DerivationTree::leaf(probe.clone(), 10),
+            vec![Feature::Categorial("C".to_string())],
+            11
+        );
+        
+        let agreed = parser.apply_agree(&at_edge).expect("the subject at the CP edge should be visible");
+        assert_eq!(agreed.chain.agreement.as_ref().unwrap().get("num"),
+                   Some(&FeatureValue::Atomic("sg".to_string())));
+        
+        // The same subject inside the transferred TP is out of reach
+        let internal = DerivationTree::merge(
+            embedded_clause(false),
+            DerivationTree::leaf(probe, 10),
+            vec![Feature::Categorial("C".to_string())],
+            11
+        );
+        
+        assert!(parser.apply_agree(&internal).is_none());
+    }
+
+    #[test]
+    fn test_parse_all_ambiguity() {
+        let mut parser = MinimalistParser::new();
+        parser.config.max_derivation_depth = 2000;
+        parser.config.merge_strategies.push(MergeStrategy::PairMerge);
+        
+        parser.add_to_lexicon("John", LexicalItem::new("John", vec![
+            Feature::Categorial("D".to_string()),
+        ]));
+        parser.add_to_lexicon("sleeps", LexicalItem::new("sleeps", vec![
+            Feature::Categorial("V".to_string()),
+        ]));
+        
+        // "often" can adjoin to either the verb phrase or the whole clause,
+        // following what it modifies
+        parser.add_to_lexicon("often", LexicalItem::new("often", vec![
+            Feature::AdjunctSelector("V".to_string()),
+            Feature::Categorial("V".to_string()),
+        ]));
+        parser.add_to_lexicon("often", LexicalItem::new("often", vec![
+            Feature::AdjunctSelector("T".to_string()),
+            Feature::Categorial("T".to_string()),
+        ]));
+        
+        let derivations = parser.parse_all("John sleeps often");
+        assert_eq!(derivations.len(), 2);
+        
+        for derivation in &derivations {
+            assert_eq!(parser.linearize(derivation), vec!["John", "sleeps", "often"]);
+        }
+        
+        // The single-parse interface still returns one of them
+        assert!(parser.parse("John sleeps often").is_some());
+    }
+
+    #[test]
+    fn test_parse_best() {
+        let mut parser = MinimalistParser::new();
+        parser.config.max_derivation_depth = 2000;
+        parser.config.merge_cost = 1.0;
+        
+        parser.add_to_lexicon("John", LexicalItem::new("John", vec![
+            Feature::Categorial("D".to_string()),
+        ]));
+        parser.add_to_lexicon("sleeps", LexicalItem::new("sleeps", vec![
+            Feature::Categorial("V".to_string()),
+        ]));
+        let tense_adverb = LexicalItem::new("often", vec![
+            Feature::Selector("T".to_string()),
+            Feature::Categorial("T".to_string()),
+        ]);
+        let clause_adverb = LexicalItem::new("often", vec![
+            Feature::Selector("C".to_string()),
+            Feature::Categorial("C".to_string()),
+        ]);
+        
+        // Four merges plus the weight of the cheaper adverb, which heads the clause
+        let mut clause = parser.clone();
+        clause.add_to_lexicon("often", tense_adverb.clone().with_weight(2.0));
+        clause.add_to_lexicon("often", clause_adverb.clone().with_weight(0.5));
+        let (tree, cost) = clause.parse_best("often John sleeps").unwrap();
+        assert_eq!(cost, 4.5);
+        assert_eq!(tree.chain.head.phonetic_form, "often");
+        
+        parser.add_to_lexicon("often", tense_adverb.with_weight(0.5));
+        parser.add_to_lexicon("often", clause_adverb.with_weight(2.0));
+        let (tree, cost) = parser.parse_best("often John sleeps").unwrap();
+        assert_eq!(cost, 4.5);
+        assert_eq!(parser.linearize(&tree), vec!["often", "John", "sleeps"]);
+        
+        // Adjoined to the tense phrase, the adverb sits below the null complementizer
+        assert_eq!(tree.chain.head.phonetic_form, "");
+    }
+    
+    #[test]
+    fn test_parse_result() {
+        let mut parser = MinimalistParser::new();
+        parser.add_to_lexicon("John", LexicalItem::new("John", vec![
+            Feature::Categorial("D".to_string()),
+        ]));
+        parser.add_to_lexicon("sleeps", LexicalItem::new("sleeps", vec![
+            Feature::Categorial("V".to_string()),
+        ]));
+        
+        assert!(parser.parse_result("John sleeps").is_ok());
+        assert!(matches!(
+            parser.parse_result("John snores"),
+            Err(Error::UnknownWord { word, position: 1 }) if word == "snores"
+        ));
+        assert!(matches!(
+            parser.parse_result("John John"),
+            Err(Error::NoParse { .. })
+        ));
+    }
+    
+    #[test]
+    fn test_parse_within() {
+        let mut parser = MinimalistParser::new();
+        parser.add_to_lexicon("John", LexicalItem::new("John", vec![
+            Feature::Categorial("D".to_string()),
+        ]));
+        parser.add_to_lexicon("sleeps", LexicalItem::new("sleeps", vec![
+            Feature::Categorial("V".to_string()),
+        ]));
+        
+        assert!(parser.parse_within("John sleeps", StepBudget::new(1000)).is_complete());
+        assert_eq!(parser.parse_within("John John", StepBudget::new(1000)), ParseOutcome::Exhausted);
+        assert!(matches!(
+            parser.parse_within("John sleeps", StepBudget::new(1)),
+            ParseOutcome::BudgetExceeded { best_partial: Some(_) }
+        ));
+    }
+    
+    #[test]
+    fn test_lexicon_enumeration() {
+        let mut parser = setup_test_parser();
+        let pronoun = LexicalItem::new("her", vec![Feature::Categorial("D".to_string())]);
+        let possessive = LexicalItem::new("her", vec![
+            Feature::Categorial("D".to_string()),
+            Feature::Selector("N".to_string()),
+        ]);
+        parser.add_to_lexicon("her", pronoun.clone());
+        parser.add_to_lexicon("her", possessive.clone());
+        
+        assert_eq!(parser.lexicon_words(), vec!["cat", "chases", "dog", "her", "sleeps", "the"]);
+        assert_eq!(parser.lexicon_entries("her"), vec![pronoun, possessive]);
+        assert!(parser.lexicon_entries("unicorn").is_empty());
+    }
+    
+    #[test]
+    fn test_to_conll() {
+        let mut parser = MinimalistParser::new();
+        parser.config.max_derivation_depth = 5000;
+        parser.add_to_lexicon("the", LexicalItem::new("the", vec![
+            Feature::Selector("N".to_string()),
+            Feature::Categorial("D".to_string()),
+        ]));
+        for noun in ["dog", "cat"] {
+            parser.add_to_lexicon(noun, LexicalItem::new(noun, vec![
+                Feature::Categorial("N".to_string()),
+            ]));
+        }
+        parser.add_to_lexicon("chases", LexicalItem::new("chases", vec![
+            Feature::Selector("D".to_string()),
+            Feature::Categorial("V".to_string()),
+        ]));
+        
+        // The determiners head their noun phrases and the verb heads the clause
+        let tree = parser.parse("the dog chases the cat").unwrap();
+        let expected = [
+            "1\tthe\tD\t3\tspec",
+            "2\tdog\t_\t1\tcomp",
+            "3\tchases\tV\t0\troot",
+            "4\tthe\tD\t3\tcomp",
+            "5\tcat\t_\t4\tcomp",
+        ];
+        assert_eq!(tree.to_conll(), format!("{}\n", expected.join("\n")));
+    }
+    
+    #[test]
+    fn test_start_category() {
+        let config = ParserConfig {
+            start_category: "D".to_string(),
+            ..ParserConfig::default()
+        };
+        let mut parser = MinimalistParser::with_config(config);
+        parser.add_to_lexicon("the", LexicalItem::new("the", vec![
+            Feature::Selector("N".to_string()),
+            Feature::Categorial("D".to_string()),
+        ]));
+        parser.add_to_lexicon("cat", LexicalItem::new("cat", vec![
+            Feature::Categorial("N".to_string()),
+        ]));
+        
+        let tree = parser.parse("the cat").expect("bare DP should parse");
+        assert_eq!(tree.first_feature(), Some(&Feature::Categorial("D".to_string())));
+        
+        let mut clausal = parser.clone();
+        clausal.config.start_category = "C".to_string();
+        assert!(clausal.parse("the cat").is_none());
+        
+        parser.config.start_category = "X".to_string();
+        assert!(matches!(parser.parse_result("the cat"), Err(Error::UnregisteredType(category)) if category == "X"));
+    }
+    
+    #[test]
+    fn test_linear_precedence() {
+        let config = ParserConfig {
+            start_category: "V".to_string(),
+            ..ParserConfig::default()
+        };
+        let mut parser = MinimalistParser::with_config(config);
+        parser.add_to_lexicon("eats", LexicalItem::new("eats", vec![
+            Feature::Selector("D".to_string()),
+            Feature::Categorial("V".to_string()),
+        ]));
+        parser.add_to_lexicon("apples", LexicalItem::new("apples", vec![
+            Feature::Categorial("D".to_string()),
+        ]));
+        
+        assert!(parser.parse("eats apples").is_some());
+        assert!(parser.parse("apples eats").is_none());
+        
+        // With the verb and its object unordered, both orders match one derivation
+        parser.config.lp_rules.add_unordered("V", "D");
+        let verb_object = parser.parse("eats apples").expect("verb object should parse");
+        let object_verb = parser.parse("apples eats").expect("object verb should parse");
+        assert_eq!(verb_object, object_verb);
+        assert_eq!(parser.linearizations(&verb_object).len(), 2);
+        
+        // A fixed order overrides the structural one
+        parser.config.lp_rules = LinearPrecedence::new();
+        parser.config.lp_rules.add_precedes("D", "V");
+        assert_eq!(parser.linearize(&verb_object), vec!["apples", "eats"]);
+        assert!(parser.parse("eats apples").is_none());
+    }
+    
+    #[test]
+    fn test_linear_precedence_homographs() {
+        let config = ParserConfig {
+            start_category: "D".to_string(),
+            ..ParserConfig::default()
+        };
+        let mut parser = MinimalistParser::with_config(config);
+        parser.add_to_lexicon("the", LexicalItem::new("the", vec![
+            Feature::Selector("N".to_string()),
+            Feature::Categorial("D".to_string()),
+        ]));
+        parser.add_to_lexicon("fish", LexicalItem::new("fish", vec![
+            Feature::Categorial("N".to_string()),
+        ]));
+        parser.add_to_lexicon("fish", LexicalItem::new("fish", vec![
+            Feature::Selector("D".to_string()),
+            Feature::Categorial("V".to_string()),
+        ]));
+        
+        // The noun is ordered as a noun, not by its verbal homograph
+        parser.config.lp_rules.add_precedes("V", "D");
+        let tree = parser.parse("the fish").expect("the noun phrase should parse");
+        assert_eq!(parser.linearize(&tree), vec!["the", "fish"]);
+        assert!(parser.parse("fish the").is_none());
+    }
+    
+    #[test]
+    fn test_public_operations() {
+        let parser = MinimalistParser::new();
+        let mut index = 0;
+        
+        let sees = DerivationTree::leaf(LexicalItem::new("sees", vec![
+            Feature::Selector("D".to_string()),
+            Feature::Licensor("wh".to_string()),
+            Feature::Categorial("V".to_string()),
+        ]), next_index(&mut index));
+        let what = DerivationTree::leaf(LexicalItem::new("what", vec![
+            Feature::Categorial("D".to_string()),
+            Feature::Licensee("wh".to_string()),
+        ]), next_index(&mut index));
+        
+        // Mismatched features neither merge nor use up an index
+        assert!(parser.try_merge(&sees, &what, &mut index).is_none());
+        assert!(parser.try_move(&sees, &mut index).is_none());
+        assert_eq!(index, 2);
+        
+        let merged = parser.try_merge(&what, &sees, &mut index).unwrap();
+        assert_eq!(merged.index, 2);
+        assert_eq!(merged.first_feature(), Some(&Feature::Licensor("wh".to_string())));
+        
+        let moved = parser.try_move(&merged, &mut index).unwrap();
+        assert_eq!(moved.index, 3);
+        assert_eq!(index, 4);
+        assert_eq!(moved.first_feature(), Some(&Feature::Categorial("V".to_string())));
+        assert_eq!(parser.linearize(&moved), vec!["what", "sees"]);
+    }
+    
+    #[test]
+    fn test_logical_form() {
+        let parser = MinimalistParser::new();
+        let mut index = 0;
+        
+        let what = DerivationTree::leaf(LexicalItem::new("what", vec![
+            Feature::Categorial("D".to_string()),
+            Feature::Licensee("wh".to_string()),
+        ]), next_index(&mut index));
+        let john = DerivationTree::leaf(LexicalItem::new("John", vec![
+            Feature::Categorial("D".to_string()),
+        ]).with_semantics(LambdaTerm::constant("john")), next_index(&mut index));
+        let sees = DerivationTree::leaf(LexicalItem::new("sees", vec![
+            Feature::Selector("D".to_string()),
+            Feature::Selector("D".to_string()),
+            Feature::Categorial("V".to_string()),
+        ]).with_semantics(LambdaTerm::constant("see")), next_index(&mut index));
+        let complementizer = DerivationTree::leaf(LexicalItem::new("", vec![
+            Feature::Selector("V".to_string()),
+            Feature::Licensor("wh".to_string()),
+            Feature::Categorial("C".to_string()),
+        ]), next_index(&mut index));
+        
+        // Wh-movement abstracts over the object position
+        let vp = parser.try_merge(&what, &sees, &mut index).unwrap();
+        let vp = parser.try_merge(&john, &vp, &mut index).unwrap();
+        let cp = parser.try_merge(&vp, &complementizer, &mut index).unwrap();
+        let cp = parser.try_move(&cp, &mut index).unwrap();
+        assert_eq!(parser.linearize(&cp), vec!["what", "John", "sees"]);
+        assert_eq!(cp.logical_form().unwrap().to_string(), "λx.see(x)(john)");
+        
+        // A mover with a meaning takes the abstraction as its argument
+        let sees = DerivationTree::leaf(LexicalItem::new("sees", vec![
+            Feature::Selector("D".to_string()),
+            Feature::Licensor("wh".to_string()),
+            Feature::Categorial("V".to_string()),
+        ]).with_semantics(LambdaTerm::constant("see")), next_index(&mut index));
+        let object = DerivationTree::leaf(LexicalItem::new("what", vec![
+            Feature::Categorial("D".to_string()),
+            Feature::Licensee("wh".to_string()),
+        ]).with_semantics(LambdaTerm::constant("which")), next_index(&mut index));
+        let merged = parser.try_merge(&object, &sees, &mut index).unwrap();
+        let moved = parser.try_move(&merged, &mut index).unwrap();
+        assert_eq!(moved.logical_form().unwrap().to_string(), "which(λx.see(x))");
+        assert_eq!(DerivationTree::leaf(LexicalItem::empty(), 0).logical_form(), None);
+        
+        // Movement does not bind a variable free in a lexical meaning
+        let sees = DerivationTree::leaf(LexicalItem::new("sees", vec![
+            Feature::Selector("D".to_string()),
+            Feature::Selector("D".to_string()),
+            Feature::Categorial("V".to_string()),
+        ]).with_semantics(LambdaTerm::constant("see")), next_index(&mut index));
+        let he = DerivationTree::leaf(LexicalItem::new("he", vec![
+            Feature::Categorial("D".to_string()),
+        ]).with_semantics(LambdaTerm::var("x")), next_index(&mut index));
+        let vp = parser.try_merge(&what, &sees, &mut index).unwrap();
+        let vp = parser.try_merge(&he, &vp, &mut index).unwrap();
+        let cp = parser.try_merge(&vp, &complementizer, &mut index).unwrap();
+        let cp = parser.try_move(&cp, &mut index).unwrap();
+        assert_eq!(cp.logical_form().unwrap().to_string(), "λy.see(y)(x)");
+    }
+    
+    #[test]
+    fn test_pronounce_copies() {
+        let sees = LexicalItem::new("sees", vec![
+            Feature::Selector("D".to_string()),
+            Feature::Licensor("wh".to_string()),
+            Feature::Categorial("V".to_string()),
+        ]);
+        let what = LexicalItem::new("what", vec![
+            Feature::Categorial("D".to_string()),
+            Feature::Licensee("wh".to_string()),
+        ]);
+        
+        let surface = |pronounce: PronounceCopy| {
+            let mut parser = MinimalistParser::new();
+            parser.config.pronounce = pronounce;
+            let mut index = 0;
+            let head = DerivationTree::leaf(sees.clone(), next_index(&mut index));
+            let spec = DerivationTree::leaf(what.clone(), next_index(&mut index));
+            let merged = parser.try_merge(&spec, &head, &mut index).unwrap();
+            let moved = parser.try_move(&merged, &mut index).unwrap();
+            assert_eq!(moved.chain.copies[0].phonetic_form, "what");
+            
+            // The exported words are pronounced as in the linearization
+            let exported: Vec<String> = moved.to_conll().lines()
+                .map(|line| line.split('\t').nth(1).unwrap().to_string())
+                .collect();
+            assert_eq!(exported, parser.linearize(&moved));
+            parser.linearize(&moved)
+        };
+        
+        assert_eq!(surface(PronounceCopy::Highest), vec!["what", "sees"]);
+        assert_eq!(surface(PronounceCopy::Lowest), vec!["sees", "what"]);
+        // Pronouncing every copy gives a resumptive-style string
+        assert_eq!(surface(PronounceCopy::All), vec!["what", "sees", "what"]);
+    }
+    
+    #[test]
+    fn test_validate_grammar() {
+        // Without a verb and a noun phrase the functional heads cannot build C
+        let warnings = MinimalistParser::new().validate_grammar();
+        assert!(warnings.contains(&GrammarWarning::UnreachableStart("C".to_string())));
+        
+        let mut parser = MinimalistParser::new();
+        parser.add_to_lexicon("John", LexicalItem::new("John", vec![
+            Feature::Categorial("D".to_string()),
+        ]));
+        parser.add_to_lexicon("sees", LexicalItem::new("sees", vec![
+            Feature::Selector("D".to_string()),
+            Feature::Categorial("V".to_string()),
+        ]));
+        assert!(parser.validate_grammar().is_empty());
+        
+        // Nothing can check -wh, and nothing selects or bears P
+        parser.add_to_lexicon("what", LexicalItem::new("what", vec![
+            Feature::Categorial("D".to_string()),
+            Feature::Licensee("wh".to_string()),
+        ]));
+        parser.add_to_lexicon("on", LexicalItem::new("on", vec![
+            Feature::Selector("N".to_string()),
+            Feature::Categorial("P".to_string()),
+        ]));
+        
+        assert_eq!(parser.validate_grammar(), vec![
+            GrammarWarning::UnselectedCategory("P".to_string()),
+            GrammarWarning::MissingCategory("N".to_string()),
+            GrammarWarning::OrphanLicensee("wh".to_string()),
+        ]);
+    }
+}
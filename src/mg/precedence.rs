@@ -0,0 +1,77 @@
+//! Linear precedence statements for Minimalist Grammar
+
+/// The order a linear precedence statement imposes on two categories
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precedence {
+    /// The first category precedes the second
+    Precedes,
+    /// Sisters of the two categories may come in either order
+    Unordered,
+}
+
+/// Linear precedence rules separating word order from dominance
+///
+/// Linearization is head-parameterized by default: complements follow
+/// lexical heads and specifiers precede them. A statement about two sister
+/// categories overrides that order, either fixing it or leaving it free, so
+/// a derivation can match several surface strings.
+#[derive(Debug, Clone, Default)]
+pub struct LinearPrecedence {
+    rules: Vec<(String, String, Precedence)>,
+}
+
+impl LinearPrecedence {
+    /// Create an empty rule set, which keeps the structural order
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// State that sisters of category `first` precede sisters of category `second`
+    pub fn add_precedes(&mut self, first: &str, second: &str) {
+        self.rules.push((first.to_string(), second.to_string(), Precedence::Precedes));
+    }
+
+    /// State that sisters of the two categories may come in either order
+    pub fn add_unordered(&mut self, first: &str, second: &str) {
+        self.rules.push((first.to_string(), second.to_string(), Precedence::Unordered));
+    }
+
+    /// Check if there are no rules
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Check if sisters of the two categories are unordered
+    pub fn is_unordered(&self, a: &str, b: &str) -> bool {
+        self.rules.iter().any(|(first, second, precedence)| {
+            *precedence == Precedence::Unordered
+                && ((first == a && second == b) || (first == b && second == a))
+        })
+    }
+
+    /// Check if a sister of category `a` must precede one of category `b`
+    pub fn precedes(&self, a: &str, b: &str) -> bool {
+        self.rules.iter().any(|(first, second, precedence)| {
+            *precedence == Precedence::Precedes && first == a && second == b
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_precedence_rules() {
+        let mut lp = LinearPrecedence::new();
+        assert!(lp.is_empty());
+
+        lp.add_precedes("D", "V");
+        lp.add_unordered("V", "P");
+
+        assert!(lp.precedes("D", "V"));
+        assert!(!lp.precedes("V", "D"));
+        assert!(lp.is_unordered("P", "V"));
+        assert!(!lp.is_unordered("D", "V"));
+    }
+}
@@ -3,6 +3,8 @@
 use std::fmt;
 use std::collections::HashMap;
 use crate::tlg::logical_type::LogicalType;
+use crate::common::LambdaTerm;
+use crate::common::lexicon::group_tokens;
 
 /// Lexical item in Type-Logical Grammar
 #[derive(Debug, Clone)]
@@ -13,6 +15,8 @@ pub struct LexicalItem {
     pub logical_type: LogicalType,
     /// Phonological form for prosodic interpretation
     pub phonological_form: Option<String>,
+    /// Lexical meaning as a lambda term
+    pub semantics: Option<LambdaTerm>,
 }
 
 impl fmt::Display for LexicalItem {
@@ -32,6 +36,7 @@ impl LexicalItem {
             word: word.to_string(),
             logical_type,
             phonological_form: None,
+            semantics: None,
         }
     }
     
@@ -41,6 +46,17 @@ impl LexicalItem {
             word: word.to_string(),
             logical_type,
             phonological_form: Some(phon.to_string()),
+            semantics: None,
+        }
+    }
+    
+    /// Create a new lexical item with a lambda term as its meaning
+    pub fn with_semantics(word: &str, logical_type: LogicalType, semantics: LambdaTerm) -> Self {
+        Self {
+            word: word.to_string(),
+            logical_type,
+            phonological_form: None,
+            semantics: Some(semantics),
         }
     }
 }
@@ -49,6 +65,8 @@ impl LexicalItem {
 #[derive(Debug, Clone)]
 pub struct Lexicon {
     entries: HashMap<String, Vec<LexicalItem>>,
+    /// The number of words in the longest entry
+    max_phrase_len: usize,
 }
 
 impl Lexicon {
@@ -56,23 +74,42 @@ impl Lexicon {
     pub fn new() -> Self {
         Lexicon {
             entries: HashMap::new(),
+            max_phrase_len: 0,
         }
     }
+    
+    /// Get the items stored for a word, creating the entry if needed
+    fn entry(&mut self, word: &str) -> &mut Vec<LexicalItem> {
+        self.max_phrase_len = self.max_phrase_len.max(word.split(' ').count());
+        self.entries.entry(word.to_string()).or_default()
+    }
 
     /// Add a word with its logical type to the lexicon
     pub fn add(&mut self, word: &str, logical_type: LogicalType) {
-        self.entries
-            .entry(word.to_string())
-            .or_insert_with(Vec::new)
-            .push(LexicalItem::new(word, logical_type));
+        self.entry(word).push(LexicalItem::new(word, logical_type));
+    }
+    
+    /// Add a multi-word entry with its logical type, stored with its words separated by single spaces
+    pub fn add_phrase(&mut self, words: &[&str], logical_type: LogicalType) {
+        self.add(&words.join(" "), logical_type);
     }
     
     /// Add a word with its logical type and phonological form to the lexicon
     pub fn add_with_phonology(&mut self, word: &str, logical_type: LogicalType, phon: &str) {
-        self.entries
-            .entry(word.to_string())
-            .or_insert_with(Vec::new)
-            .push(LexicalItem::with_phonology(word, logical_type, phon));
+        self.entry(word).push(LexicalItem::with_phonology(word, logical_type, phon));
+    }
+
+    /// Add a word with its logical type and lexical meaning to the lexicon
+    pub fn add_with_semantics(&mut self, word: &str, logical_type: LogicalType, semantics: LambdaTerm) {
+        self.entry(word).push(LexicalItem::with_semantics(word, logical_type, semantics));
+    }
+    
+    /// Group tokens into the multi-word entries of the lexicon, with the tokens each covers
+    ///
+    /// Longer entries are preferred, and each group comes with its
+    /// `[start, end)` span of token indices.
+    pub fn group_phrases_with_spans(&self, tokens: &[String]) -> Vec<(String, (usize, usize))> {
+        group_tokens(tokens, self.max_phrase_len, |phrase| self.contains(phrase))
     }
 
     /// Get all possible lexical items for a word
@@ -187,6 +224,19 @@ mod tests {
         assert!(words.contains(&"runs".to_string()));
     }
     
+    #[test]
+    fn test_lexicon_phrases() {
+        let mut lexicon = Lexicon::new();
+        lexicon.add("York", LogicalType::n());
+        lexicon.add_phrase(&["New", "York"], LogicalType::np());
+        
+        let tokens: Vec<String> = ["I", "love", "New", "York"].iter().map(|t| t.to_string()).collect();
+        let grouped = lexicon.group_phrases_with_spans(&tokens);
+        assert_eq!(grouped.len(), 3);
+        assert_eq!(grouped[2], ("New York".to_string(), (2, 4)));
+        assert_eq!(lexicon.get_types("New York"), vec![LogicalType::np()]);
+    }
+    
     #[test]
     fn test_lexicon_with_multiple_types() {
         let mut lexicon = Lexicon::new();
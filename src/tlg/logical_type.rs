@@ -2,7 +2,7 @@
 
 use std::fmt;
 use std::hash::Hash;
-use crate::common::FeatureStructure;
+use crate::common::{Bindings, FeatureStructure};
 use crate::tlg::modality::Modality;
 
 /// Types of structural properties for modalities in Type-Logical Grammar
@@ -18,6 +18,12 @@ pub enum StructuralProperty {
     Contraction,
     /// Permutation: captures non-peripheral extraction
     Permutation,
+    /// Reflexivity (T): □A → A
+    Reflexivity,
+    /// Transitivity (4): ◇◇A → ◇A
+    Transitivity,
+    /// Distributivity (K): □(A → B) → (□A → □B)
+    Distributivity,
 }
 
 /// Types of logical formula in Type-Logical Grammar
@@ -25,6 +31,8 @@ pub enum StructuralProperty {
 pub enum LogicalType {
     /// Atomic types like s, np, n
     Atomic(String, FeatureStructure),
+    /// The unit type 1, the identity for ⊗, provable from no input
+    Unit,
     /// Right implication A → B
     RightImplication(Box<LogicalType>, Box<LogicalType>, Option<Modality>),
     /// Left implication A ← B
@@ -55,6 +63,7 @@ impl fmt::Display for LogicalType {
                 }
                 Ok(())
             },
+            LogicalType::Unit => write!(f, "1"),
             LogicalType::RightImplication(a, b, modality) => {
                 let mod_str = if let Some(m) = modality {
                     format!("{}", m)
@@ -75,10 +84,13 @@ impl fmt::Display for LogicalType {
                     "".to_string()
                 };
                 
+                // A complex result is bracketed too, so (s ← np) ← np reads unambiguously
+                let a_str = if Self::is_complex(a) { format!("({})", a) } else { a.to_string() };
+                
                 if Self::is_complex(b) {
-                    write!(f, "{} ←{} ({})", a, mod_str, b)
+                    write!(f, "{} ←{} ({})", a_str, mod_str, b)
                 } else {
-                    write!(f, "{} ←{} {}", a, mod_str, b)
+                    write!(f, "{} ←{} {}", a_str, mod_str, b)
                 }
             },
             LogicalType::Product(a, b, modality) => {
@@ -119,7 +131,7 @@ impl fmt::Display for LogicalType {
 impl LogicalType {
     /// Helper to determine if a type needs parentheses in display
     fn is_complex(t: &LogicalType) -> bool {
-        !matches!(t, LogicalType::Atomic(_, _) | LogicalType::Diamond(_, _) | LogicalType::Box(_, _))
+        !matches!(t, LogicalType::Atomic(_, _) | LogicalType::Unit | LogicalType::Diamond(_, _) | LogicalType::Box(_, _))
     }
 
     /// Helper to create atomic types
@@ -147,6 +159,11 @@ impl LogicalType {
         Self::atomic("n")
     }
 
+    /// Convenience method for creating the unit type
+    pub fn unit() -> Self {
+        LogicalType::Unit
+    }
+
     /// Helper to create right implication
     pub fn right_impl(left: LogicalType, right: LogicalType) -> Self {
         LogicalType::RightImplication(Box::new(left), Box::new(right), None)
@@ -215,120 +232,155 @@ impl LogicalType {
         }
     }
     
+    /// Add features to the atomic type this type ultimately yields
+    ///
+    /// Returns `None` if the features conflict with those already there.
+    /// Types without a unique result (products, quantifiers) are unchanged.
+    pub fn with_result_features(&self, features: &FeatureStructure) -> Option<LogicalType> {
+        match self {
+            LogicalType::Atomic(name, own) => {
+                own.unify(features).map(|unified| LogicalType::Atomic(name.clone(), unified))
+            },
+            LogicalType::RightImplication(a, b, modality) => b.with_result_features(features)
+                .map(|b| LogicalType::RightImplication(a.clone(), Box::new(b), modality.clone())),
+            LogicalType::LeftImplication(a, b, modality) => a.with_result_features(features)
+                .map(|a| LogicalType::LeftImplication(Box::new(a), b.clone(), modality.clone())),
+            LogicalType::Diamond(inner, modality) => inner.with_result_features(features)
+                .map(|inner| LogicalType::Diamond(Box::new(inner), modality.clone())),
+            LogicalType::Box(inner, modality) => inner.with_result_features(features)
+                .map(|inner| LogicalType::Box(Box::new(inner), modality.clone())),
+            LogicalType::UpArrow(a, b, index) => a.with_result_features(features)
+                .map(|a| LogicalType::UpArrow(Box::new(a), b.clone(), *index)),
+            LogicalType::DownArrow(a, b, index) => a.with_result_features(features)
+                .map(|a| LogicalType::DownArrow(Box::new(a), b.clone(), *index)),
+            _ => Some(self.clone()),
+        }
+    }
+    
     /// Unify this type with another if they are compatible
     pub fn unify(&self, other: &LogicalType) -> Option<LogicalType> {
+        let mut bindings = Bindings::new();
+        self.unify_with_bindings(other, &mut bindings)
+            .map(|unified| unified.apply_bindings(&bindings))
+    }
+    
+    /// Unify this type with another, recording the feature variable bindings
+    /// that make them equal
+    pub fn unify_with_bindings(&self, other: &LogicalType, bindings: &mut Bindings) -> Option<LogicalType> {
         match (self, other) {
             (LogicalType::Atomic(s1, f1), LogicalType::Atomic(s2, f2)) => {
                 if s1 != s2 {
                     return None;
                 }
                 
-                if let Some(unified_features) = f1.unify(f2) {
-                    Some(LogicalType::Atomic(s1.clone(), unified_features))
-                } else {
-                    None
-                }
+                f1.unify_with_bindings(f2, bindings)
+                    .map(|unified_features| LogicalType::Atomic(s1.clone(), unified_features))
             },
+            (LogicalType::Unit, LogicalType::Unit) => Some(LogicalType::Unit),
             (LogicalType::RightImplication(a1, b1, m1), LogicalType::RightImplication(a2, b2, m2)) => {
                 if m1 != m2 {
                     return None;
                 }
                 
-                if let (Some(unified_a), Some(unified_b)) = (a1.unify(a2), b1.unify(b2)) {
-                    Some(LogicalType::RightImplication(
-                        Box::new(unified_a),
-                        Box::new(unified_b),
-                        m1.clone()
-                    ))
-                } else {
-                    None
-                }
+                let unified_a = a1.unify_with_bindings(a2, bindings)?;
+                let unified_b = b1.unify_with_bindings(b2, bindings)?;
+                Some(LogicalType::RightImplication(Box::new(unified_a), Box::new(unified_b), m1.clone()))
             },
             (LogicalType::LeftImplication(a1, b1, m1), LogicalType::LeftImplication(a2, b2, m2)) => {
                 if m1 != m2 {
                     return None;
                 }
                 
-                if let (Some(unified_a), Some(unified_b)) = (a1.unify(a2), b1.unify(b2)) {
-                    Some(LogicalType::LeftImplication(
-                        Box::new(unified_a),
-                        Box::new(unified_b),
-                        m1.clone()
-                    ))
-                } else {
-                    None
-                }
+                let unified_a = a1.unify_with_bindings(a2, bindings)?;
+                let unified_b = b1.unify_with_bindings(b2, bindings)?;
+                Some(LogicalType::LeftImplication(Box::new(unified_a), Box::new(unified_b), m1.clone()))
             },
             (LogicalType::Product(a1, b1, m1), LogicalType::Product(a2, b2, m2)) => {
                 if m1 != m2 {
                     return None;
                 }
                 
-                if let (Some(unified_a), Some(unified_b)) = (a1.unify(a2), b1.unify(b2)) {
-                    Some(LogicalType::Product(
-                        Box::new(unified_a),
-                        Box::new(unified_b),
-                        m1.clone()
-                    ))
-                } else {
-                    None
-                }
+                let unified_a = a1.unify_with_bindings(a2, bindings)?;
+                let unified_b = b1.unify_with_bindings(b2, bindings)?;
+                Some(LogicalType::Product(Box::new(unified_a), Box::new(unified_b), m1.clone()))
             },
             (LogicalType::Diamond(a1, m1), LogicalType::Diamond(a2, m2)) => {
                 if m1 != m2 {
                     return None;
                 }
                 
-                if let Some(unified_a) = a1.unify(a2) {
-                    Some(LogicalType::Diamond(Box::new(unified_a), m1.clone()))
-                } else {
-                    None
-                }
+                a1.unify_with_bindings(a2, bindings)
+                    .map(|unified_a| LogicalType::Diamond(Box::new(unified_a), m1.clone()))
             },
             (LogicalType::Box(a1, m1), LogicalType::Box(a2, m2)) => {
                 if m1 != m2 {
                     return None;
                 }
                 
-                if let Some(unified_a) = a1.unify(a2) {
-                    Some(LogicalType::Box(Box::new(unified_a), m1.clone()))
-                } else {
-                    None
-                }
+                a1.unify_with_bindings(a2, bindings)
+                    .map(|unified_a| LogicalType::Box(Box::new(unified_a), m1.clone()))
             },
             (LogicalType::UpArrow(a1, b1, i1), LogicalType::UpArrow(a2, b2, i2)) => {
                 if i1 != i2 {
                     return None;
                 }
                 
-                if let (Some(unified_a), Some(unified_b)) = (a1.unify(a2), b1.unify(b2)) {
-                    Some(LogicalType::UpArrow(
-                        Box::new(unified_a),
-                        Box::new(unified_b),
-                        *i1
-                    ))
-                } else {
-                    None
-                }
+                let unified_a = a1.unify_with_bindings(a2, bindings)?;
+                let unified_b = b1.unify_with_bindings(b2, bindings)?;
+                Some(LogicalType::UpArrow(Box::new(unified_a), Box::new(unified_b), *i1))
             },
             (LogicalType::DownArrow(a1, b1, i1), LogicalType::DownArrow(a2, b2, i2)) => {
                 if i1 != i2 {
                     return None;
                 }
                 
-                if let (Some(unified_a), Some(unified_b)) = (a1.unify(a2), b1.unify(b2)) {
-                    Some(LogicalType::DownArrow(
-                        Box::new(unified_a),
-                        Box::new(unified_b),
-                        *i1
-                    ))
-                } else {
-                    None
-                }
+                let unified_a = a1.unify_with_bindings(a2, bindings)?;
+                let unified_b = b1.unify_with_bindings(b2, bindings)?;
+                Some(LogicalType::DownArrow(Box::new(unified_a), Box::new(unified_b), *i1))
             },
             _ => None, // Different type constructors don't unify
         }
     }
+    
+    /// Apply feature variable bindings throughout this type
+    pub fn apply_bindings(&self, bindings: &Bindings) -> LogicalType {
+        let apply = |t: &LogicalType| Box::new(t.apply_bindings(bindings));
+        
+        match self {
+            LogicalType::Atomic(name, features) => LogicalType::Atomic(name.clone(), features.apply_bindings(bindings)),
+            LogicalType::Unit => LogicalType::Unit,
+            LogicalType::RightImplication(a, b, m) => LogicalType::RightImplication(apply(a), apply(b), m.clone()),
+            LogicalType::LeftImplication(a, b, m) => LogicalType::LeftImplication(apply(a), apply(b), m.clone()),
+            LogicalType::Product(a, b, m) => LogicalType::Product(apply(a), apply(b), m.clone()),
+            LogicalType::Diamond(a, m) => LogicalType::Diamond(apply(a), m.clone()),
+            LogicalType::Box(a, m) => LogicalType::Box(apply(a), m.clone()),
+            LogicalType::Universal(var, a) => LogicalType::Universal(var.clone(), apply(a)),
+            LogicalType::Existential(var, a) => LogicalType::Existential(var.clone(), apply(a)),
+            LogicalType::UpArrow(a, b, i) => LogicalType::UpArrow(apply(a), apply(b), *i),
+            LogicalType::DownArrow(a, b, i) => LogicalType::DownArrow(apply(a), apply(b), *i),
+        }
+    }
+    
+    /// Get the names of the atomic types occurring in this type, left to right
+    pub fn atoms(&self) -> Vec<String> {
+        match self {
+            LogicalType::Atomic(name, _) => vec![name.clone()],
+            LogicalType::Unit => Vec::new(),
+            LogicalType::RightImplication(a, b, _) |
+            LogicalType::LeftImplication(a, b, _) |
+            LogicalType::Product(a, b, _) |
+            LogicalType::UpArrow(a, b, _) |
+            LogicalType::DownArrow(a, b, _) => {
+                let mut atoms = a.atoms();
+                atoms.extend(b.atoms());
+                atoms
+            },
+            LogicalType::Diamond(a, _) |
+            LogicalType::Box(a, _) |
+            LogicalType::Universal(_, a) |
+            LogicalType::Existential(_, a) => a.atoms(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -349,6 +401,9 @@ mod tests {
         
         let diamond_np = LogicalType::diamond(np.clone());
         assert_eq!(diamond_np.to_string(), "◇np");
+        
+        let optional = LogicalType::left_impl(verb_type.clone(), LogicalType::unit());
+        assert_eq!(optional.to_string(), "(s ← np) ← 1");
     }
     
     #[test]
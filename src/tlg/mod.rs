@@ -10,10 +10,11 @@ pub mod lexicon;
 
 pub use logical_type::{LogicalType, StructuralProperty};
 pub use parser::{TLGParser, ParserConfig};
-pub use proof::{ProofNode, ProofSearchState};
+pub use proof::{ProofNode, ProofSearchState, StateKey};
 pub use modality::Modality;
 pub use proof_net::ProofNet;
 pub use lexicon::Lexicon;
+pub use crate::common::LambdaTerm;
 pub use registry::AtomicTypeRegistry;
 
 use crate::common::Category as CategoryTrait;
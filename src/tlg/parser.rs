@@ -3,16 +3,46 @@
 //! This module provides the main parser for Type-Logical Grammar, using
 //! either natural deduction or proof nets to derive semantic representations.
 
-use std::collections::VecDeque;
-use crate::common::{FeatureRegistry, FeatureValue, FeatureStructure};
-use crate::tlg::logical_type::LogicalType;
+use std::cell::Cell;
+use std::sync::Arc;
+use crate::common::{Agenda, Bindings, Error, FeatureRegistry, FeatureValue, FeatureStructure, Morphology, ParseOutcome, SimpleTokenizer, StepBudget, StepKind, TraceHandle, Tokenizer, Tracer};
+use crate::tlg::logical_type::{LogicalType, StructuralProperty};
 use crate::tlg::modality::Modality;
 use crate::tlg::proof::{ProofNode, ProofSearchState};
 use crate::tlg::proof_net::ProofNet;
 use crate::tlg::registry::AtomicTypeRegistry;
-use crate::tlg::lexicon::Lexicon;
+use crate::tlg::lexicon::{Lexicon, LexicalItem};
 use crate::common::Parser as ParserTrait;
 
+/// The deepest state reached by a proof search that failed
+struct SearchFailure {
+    /// The items left in the deepest state
+    remaining: Vec<ProofNode>,
+    /// Whether the search ran out of states before it finished
+    out_of_states: bool,
+}
+
+impl SearchFailure {
+    /// Describe the failure to prove a goal
+    fn into_error(self, goal: &LogicalType) -> Error {
+        let unconsumed = self.remaining.iter()
+            .filter(|item| item.children.is_empty())
+            .map(|item| item.label.clone())
+            .collect();
+        let dangling = self.remaining.iter()
+            .flat_map(|item| item.logical_type.atoms())
+            .collect();
+        
+        Error::NoProof {
+            goal: goal.to_string(),
+            remaining: self.remaining.iter().map(|item| format!("{} : {}", item.label, item.logical_type)).collect(),
+            unconsumed,
+            dangling,
+            out_of_states: self.out_of_states,
+        }
+    }
+}
+
 /// Configuration options for the parser
 #[derive(Debug, Clone)]
 pub struct ParserConfig {
@@ -26,6 +56,10 @@ pub struct ParserConfig {
     pub use_quantifiers: bool,
     /// Whether to use strict linear logic (no resource duplication)
     pub strict_linear: bool,
+    /// Whether implications take their argument from a fixed side
+    /// (`A → B` from the left, `A ← B` from the right) rather than from
+    /// either neighbour
+    pub directional: bool,
     /// Logic variant to use (e.g., "NL", "L", "NL(3)", etc.)
     pub logic_variant: String,
     /// Whether to use proof nets for parsing (more efficient)
@@ -36,6 +70,8 @@ pub struct ParserConfig {
     pub use_features: bool,
     /// Available modalities for multi-modal system
     pub modalities: Vec<Modality>,
+    /// Whether to explore search states sharing a canonical key only once
+    pub memoize_states: bool,
 }
 
 impl Default for ParserConfig {
@@ -46,11 +82,13 @@ impl Default for ParserConfig {
             use_modalities: false,
             use_quantifiers: false,
             strict_linear: true,
+            directional: false,
             logic_variant: "NL".to_string(), // Non-associative Lambek calculus by default
             use_proof_nets: false,
             use_displacement: false,
             use_features: true,
             modalities: vec![],
+            memoize_states: true,
         }
     }
 }
@@ -65,6 +103,14 @@ pub struct TLGParser {
     pub config: ParserConfig,
     /// Registry for linguistic features
     pub feature_registry: FeatureRegistry,
+    /// Analyzer consulted for words missing from the lexicon
+    pub morphology: Option<Box<dyn Morphology>>,
+    /// Tokenizer splitting sentences into words
+    pub tokenizer: Arc<dyn Tokenizer>,
+    /// The number of search states explored by the last proof search
+    explored_states: Cell<usize>,
+    /// The tracer told about each lexical axiom and search state
+    tracer: TraceHandle,
 }
 
 impl TLGParser {
@@ -75,6 +121,10 @@ impl TLGParser {
             atomic_types: AtomicTypeRegistry::default(),
             config: ParserConfig::default(),
             feature_registry: FeatureRegistry::new(),
+            morphology: None,
+            tokenizer: Arc::new(SimpleTokenizer::default()),
+            explored_states: Cell::new(0),
+            tracer: TraceHandle::default(),
         };
         
         // Populate the lexicon with some basic entries
@@ -90,6 +140,16 @@ impl TLGParser {
         parser
     }
     
+    /// Get the number of search states explored by the last proof search
+    pub fn explored_states(&self) -> usize {
+        self.explored_states.get()
+    }
+    
+    /// Attach a tracer told about each lexical axiom and each rule application in the proof search
+    pub fn set_tracer(&mut self, tracer: Box<dyn Tracer>) {
+        self.tracer.set(tracer);
+    }
+    
     /// Register a new atomic type
     pub fn register_atomic_type(&mut self, type_name: &str) {
         self.atomic_types.register(type_name);
@@ -322,6 +382,8 @@ impl TLGParser {
                 
                 true
             },
+            // The unit is not an atomic type and needs no registration
+            LogicalType::Unit => true,
             LogicalType::RightImplication(a, b, modality) |
             LogicalType::LeftImplication(a, b, modality) |
             LogicalType::Product(a, b, modality) => {
@@ -375,30 +437,146 @@ impl TLGParser {
     
     /// Parse a sentence using natural deduction for Type-Logical Grammar
     pub fn parse_with_natural_deduction(&self, sentence: &str) -> Option<ProofNode> {
-        let words: Vec<&str> = sentence.split_whitespace().collect();
+        self.parse_with_natural_deduction_to(sentence, &LogicalType::s())
+    }
+    
+    /// Parse a sentence as a proof of the given goal type by natural deduction
+    fn parse_with_natural_deduction_to(&self, sentence: &str, goal: &LogicalType) -> Option<ProofNode> {
+        self.parse_explained_to(sentence, goal)
+            .map_err(|error| eprintln!("{}", error))
+            .ok()
+    }
+    
+    /// Parse a sentence as a proof of the given goal type
+    ///
+    /// Proof nets are tried first if they are enabled. Returns `None` without
+    /// searching if the goal has unregistered atomic types.
+    pub fn parse_to(&self, sentence: &str, goal: &LogicalType) -> Option<ProofNode> {
+        if !self.validate_type(goal) {
+            eprintln!("Warning: Invalid goal type {}.", goal);
+            return None;
+        }
         
-        // Create axioms from lexical entries
-        let mut axioms = Vec::new();
-        for word in &words {
-            let items = self.lexicon.get_items(word);
-            
+        if self.config.use_proof_nets {
+            self.parse_with_proof_nets_to(sentence, goal)
+        } else {
+            self.parse_with_natural_deduction_to(sentence, goal)
+        }
+    }
+    
+    /// Parse a sentence, explaining the failure if no proof is found
+    pub fn parse_explained(&self, sentence: &str) -> Result<ProofNode, Error> {
+        self.parse_explained_to(sentence, &LogicalType::s())
+    }
+    
+    /// Parse a sentence as a proof of a goal type, explaining the failure if no proof is found
+    pub fn parse_explained_to(&self, sentence: &str, goal: &LogicalType) -> Result<ProofNode, Error> {
+        let lexical = self.lexical_axioms(sentence)?;
+        
+        self.prove_sentence(&lexical, goal, false, self.config.max_depth)
+            .map(|mut proofs| proofs.remove(0))
+            .map_err(|failure| failure.into_error(goal))
+    }
+    
+    /// Find every distinct reading of a sentence
+    ///
+    /// The search continues past the first proof, up to `max_depth` search
+    /// states, trying every lexical entry of each word. Each proof is
+    /// normalized once, and proofs whose normal forms are the same up to the
+    /// names of their hypotheses share a proof net, so only the first is kept.
+    pub fn parse_all(&self, sentence: &str) -> Vec<ProofNode> {
+        let mut normal_forms: Vec<ProofNode> = Vec::new();
+        self.complete_proofs(sentence).into_iter()
+            .filter(|proof| {
+                let normal = proof.normalize();
+                let fresh = !normal_forms.iter().any(|kept| kept.is_alpha_equivalent(&normal));
+                if fresh {
+                    normal_forms.push(normal);
+                }
+                fresh
+            })
+            .collect()
+    }
+    
+    /// Get every complete proof of a sentence found by the search
+    fn complete_proofs(&self, sentence: &str) -> Vec<ProofNode> {
+        match self.lexical_axioms(sentence) {
+            Ok(lexical) => self.prove_sentence(&lexical, &LogicalType::s(), true, self.config.max_depth).unwrap_or_default(),
+            Err(_) => Vec::new(),
+        }
+    }
+    
+    /// Count the distinct readings of a sentence
+    pub fn count_proofs(&self, sentence: &str) -> usize {
+        self.parse_all(sentence).len()
+    }
+    
+    /// Create the alternative axioms for each token of a sentence from its lexical entries
+    ///
+    /// Multi-word entries of the lexicon form a single token, whose axioms
+    /// cover the positions of all the words it spans.
+    fn lexical_axioms(&self, sentence: &str) -> Result<Vec<Vec<ProofNode>>, Error> {
+        let tokens = self.lexicon.group_phrases_with_spans(&self.tokenizer.tokenize(sentence));
+        let mut lexical = Vec::new();
+        for (word, (start, end)) in &tokens {
+            let mut items = self.lexicon.get_items(word);
             if items.is_empty() {
-                eprintln!("Unknown word: {}", word);
-                return None;
+                items = self.morphological_items(word);
             }
             
-            for item in items {
-                axioms.push(ProofNode::axiom(word, item.logical_type));
+            if items.is_empty() {
+                return Err(Error::UnknownWord { word: word.to_string(), position: *start });
             }
+            
+            let axioms = items.into_iter().map(|item| {
+                self.tracer.step(StepKind::Lexical, || format!("{} := {}", word, item.logical_type));
+                let axiom = match item.semantics {
+                    Some(semantics) => ProofNode::axiom_with_semantics(word, item.logical_type, semantics),
+                    None => ProofNode::axiom(word, item.logical_type),
+                };
+                ProofNode { positions: (*start..*end).collect(), ..axiom }
+            }).collect();
+            
+            lexical.push(axioms);
         }
         
-        // Try to derive a complete proof
-        self.prove_sentence(&axioms, &LogicalType::s())
+        Ok(lexical)
+    }
+    
+    /// Get lexical items for an unknown word from the entries of its lemmas
+    ///
+    /// Inflectional features are added to the result type when features
+    /// are enabled; otherwise the lemma's types are used as is.
+    fn morphological_items(&self, word: &str) -> Vec<LexicalItem> {
+        let morphology = match &self.morphology {
+            Some(morphology) => morphology,
+            None => return Vec::new(),
+        };
+        
+        let mut items = Vec::new();
+        for (lemma, features) in morphology.analyze(word) {
+            for mut item in self.lexicon.get_items(&lemma) {
+                if self.config.use_features {
+                    match item.logical_type.with_result_features(&features) {
+                        Some(logical_type) => item.logical_type = logical_type,
+                        None => continue,
+                    }
+                }
+                items.push(item);
+            }
+        }
+        items
     }
     
     /// Parse using proof nets for efficiency
     pub fn parse_with_proof_nets(&self, sentence: &str) -> Option<ProofNode> {
-        let words: Vec<&str> = sentence.split_whitespace().collect();
+        self.parse_with_proof_nets_to(sentence, &LogicalType::s())
+    }
+    
+    /// Parse a sentence as a proof of the given goal type using proof nets
+    fn parse_with_proof_nets_to(&self, sentence: &str, goal: &LogicalType) -> Option<ProofNode> {
+        let tokens = self.lexicon.group_phrases_with_spans(&self.tokenizer.tokenize(sentence));
+        let words: Vec<&str> = tokens.iter().map(|(word, _)| word.as_str()).collect();
         
         // For each word, create all possible proof nets from its lexical types
         let mut word_nets = Vec::new();
@@ -424,48 +602,157 @@ impl TLGParser {
         // Now try to link these nets together
         // This is a simplified approach; a real implementation would be more complex
         
-        // For example, try to link the first word to the goal type (sentence)
-        let goal_type = LogicalType::s();
-        let _goal_net = ProofNet::from_type(&goal_type, false);
+        // For example, try to link the first word to the goal type
+        let _goal_net = ProofNet::from_type(goal, false);
         
         // Try to find a combination that works
         for (_word, net) in &word_nets[0] {
             // Try to link this net to the goal
             // In a real implementation, we would try all possible combinations
             
-            // For now, just convert the first one to a proof tree
+            // For now, a single word is proved by its own net if it concludes in the goal
             if let Some(proof_tree) = net.to_proof_tree() {
-                return Some(proof_tree);
+                if words.len() == 1 && proof_tree.logical_type.unify(goal).is_some() {
+                    return Some(proof_tree);
+                }
             }
         }
         
         // If no proof net is valid, fall back to natural deduction
-        self.parse_with_natural_deduction(sentence)
+        self.parse_with_natural_deduction_to(sentence, goal)
     }
     
     /// Try to derive a proof for the sentence with the goal type
-    fn prove_sentence(&self, axioms: &[ProofNode], goal: &LogicalType) -> Option<ProofNode> {
-        // Queue for breadth-first search
-        let mut queue = VecDeque::new();
+    ///
+    /// `lexical` holds the alternative axioms for each word; every choice of
+    /// one axiom per word seeds its own search state. With `all` set the
+    /// search collects every complete proof instead of stopping at the
+    /// first. At most `max_states` states are explored. On failure the
+    /// deepest state reached is returned.
+    fn prove_sentence(&self, lexical: &[Vec<ProofNode>], goal: &LogicalType, all: bool, max_states: usize) -> Result<Vec<ProofNode>, SearchFailure> {
+        // Breadth-first agenda; states with the same canonical key, or the
+        // same items without memoization, are only explored once. Collecting
+        // every proof keeps the derivations of the items apart.
+        let memoize = self.config.memoize_states;
+        let mut agenda = Agenda::new(max_states, move |state: &ProofSearchState| {
+            if memoize {
+                (Some(state.canonical_key(all)), Vec::new())
+            } else {
+                (None, state.items.clone())
+            }
+        });
         
-        // Initial state: individual axioms
-        queue.push_back(ProofSearchState::new(axioms.to_vec()));
+        // Initial states: one lexical assignment each
+        let mut assignments: Vec<Vec<ProofNode>> = vec![vec![]];
+        for alternatives in lexical {
+            assignments = assignments.into_iter()
+                .flat_map(|assignment| alternatives.iter().map(move |axiom| {
+                    let mut extended = assignment.clone();
+                    extended.push(axiom.clone());
+                    extended
+                }))
+                .collect();
+        }
+        for assignment in assignments {
+            // Gaps of type ◇□A extracted by a lexical item enter as hypotheses
+            let mut gaps = Vec::new();
+            if self.config.use_modalities {
+                for axiom in &assignment {
+                    collect_gap_types(&axiom.logical_type, &mut gaps);
+                }
+            }
+            
+            // A displaced argument B of a type A↑B leaves a gap of type B
+            let mut displaced = Vec::new();
+            if self.config.use_displacement {
+                for axiom in &assignment {
+                    if let LogicalType::UpArrow(_, b, _) = &axiom.logical_type {
+                        displaced.push((**b).clone());
+                    }
+                }
+            }
+            
+            let mut state = ProofSearchState::new(assignment);
+            if self.config.use_product {
+                state = state.decompose_products();
+            }
+            let count = gaps.len();
+            for (k, gap) in gaps.into_iter().enumerate() {
+                state.items.extend(ProofNode::hypothesis(&format!("z{}", k), gap));
+            }
+            for (k, gap) in displaced.into_iter().enumerate() {
+                state.items.push(ProofNode::assumption(&format!("z{}", count + k), gap));
+            }
+            agenda.push(state, 0.0);
+        }
+        
+        // The deepest state reached, for diagnostics
+        let mut deepest: Option<ProofSearchState> = None;
+        let mut proofs = Vec::new();
         
         // BFS for derivation
-        for _ in 0..self.config.max_depth {
-            if queue.is_empty() {
-                break;
+        while let Some(current_state) = agenda.pop() {
+            if let Some(rule) = current_state.rule_history.last() {
+                self.tracer.step(StepKind::Rule, || {
+                    let items: Vec<String> = current_state.items.iter().map(|item| item.logical_type.to_string()).collect();
+                    format!("{} => [{}]", rule, items.join(", "))
+                });
             }
             
-            let current_state = queue.pop_front().unwrap();
-            
             // Check if this is a complete proof
             if current_state.is_complete(goal) {
-                return current_state.get_proof();
+                if let Some(proof) = current_state.get_proof() {
+                    proofs.push(proof);
+                    if !all {
+                        self.explored_states.set(agenda.steps());
+                        return Ok(proofs);
+                    }
+                }
+                continue;
+            }
+            
+            if deepest.as_ref().is_none_or(|state| current_state.depth > state.depth) {
+                deepest = Some(current_state.clone());
             }
             
             // Try to apply logical rules to combine items
             for i in 0..current_state.items.len() {
+                // Modal postulates rewrite a single item
+                if self.config.use_modalities {
+                    for (rule, result_type) in self.modal_postulates(&current_state.items[i].logical_type) {
+                        let new_proof = ProofNode::infer(
+                            result_type,
+                            vec![current_state.items[i].clone()],
+                            rule
+                        );
+                        
+                        agenda.push(current_state.apply_rule(rule, new_proof, vec![i]), 0.0);
+                    }
+                    
+                    // Discharge a gap hypothesis where another item takes the abstraction
+                    for (rule, abstraction) in self.discharges(&current_state, i) {
+                        agenda.push(current_state.apply_rule(rule, abstraction, vec![i]), 0.0);
+                    }
+                }
+                
+                // An argument of the unit type is proven from no input
+                if let Some((rule, result_type)) = unit_application(&current_state.items[i].logical_type) {
+                    let new_proof = ProofNode::infer(
+                        result_type.clone(),
+                        vec![current_state.items[i].clone(), ProofNode::unit()],
+                        rule
+                    );
+                    
+                    agenda.push(current_state.apply_rule(rule, new_proof, vec![i]), 0.0);
+                }
+                
+                // Product elimination, once both components have been consumed
+                for k in 0..current_state.products.len() {
+                    if let Some(state) = current_state.eliminate_product(k, i) {
+                        agenda.push(state, 0.0);
+                    }
+                }
+                
                 for j in 0..current_state.items.len() {
                     if i == j && self.config.strict_linear {
                         continue; // Skip same item (unless we allow contraction)
@@ -474,13 +761,36 @@ impl TLGParser {
                     // Try different rules based on the logic variant
                     let mut new_states = Vec::new();
                     
+                    // A unit is absorbed by a neighbouring word, which fixes
+                    // where it is eliminated
+                    let word = current_state.items[j].rule.is_none() && !current_state.items[j].hypothesis;
+                    if current_state.items[i].logical_type == LogicalType::Unit && i != j && word {
+                        let adjacent = current_state.items[i].precedes(&current_state.items[j])
+                            || current_state.items[j].precedes(&current_state.items[i]);
+                        if adjacent {
+                            let new_proof = ProofNode::infer(
+                                current_state.items[j].logical_type.clone(),
+                                vec![current_state.items[i].clone(), current_state.items[j].clone()],
+                                "1E"
+                            );
+                            
+                            new_states.push(current_state.apply_rule("1E", new_proof, vec![i, j]));
+                        }
+                    }
+                    
                     // Right implication elimination (function application)
                     match &current_state.items[i].logical_type {
-                        LogicalType::RightImplication(a, b, _modality_i) => {
+                        LogicalType::RightImplication(a, b, modality_i) => {
+                            // The argument must be next to the functor, immediately
+                            // preceding it in the directional calculus
+                            let adjacent = current_state.items[j].precedes(&current_state.items[i])
+                                || self.allows_reordering(modality_i, &current_state.items[i], &current_state.items[j])
+                                || self.is_mobile(&current_state.items[j]);
+                            
                             // Check if j matches the argument type
-                            if self.types_match(a, &current_state.items[j].logical_type) {
-                                // Apply the rule
-                                let result_type = (**b).clone();
+                            if let Some(bindings) = self.match_types(a, &current_state.items[j].logical_type).filter(|_| adjacent) {
+                                // Apply the rule, carrying over what unification learned
+                                let result_type = b.apply_bindings(&bindings);
                                 
                                 let new_proof = ProofNode::infer(
                                     result_type,
@@ -497,11 +807,17 @@ impl TLGParser {
                                 new_states.push(new_state);
                             }
                         },
-                        LogicalType::LeftImplication(a, b, _modality_i) => {
+                        LogicalType::LeftImplication(a, b, modality_i) => {
+                            // The argument must be next to the functor, immediately
+                            // following it in the directional calculus
+                            let adjacent = current_state.items[i].precedes(&current_state.items[j])
+                                || self.allows_reordering(modality_i, &current_state.items[i], &current_state.items[j])
+                                || self.is_mobile(&current_state.items[j]);
+                            
                             // Check if j matches the argument type
-                            if self.types_match(b, &current_state.items[j].logical_type) {
-                                // Apply the rule
-                                let result_type = (**a).clone();
+                            if let Some(bindings) = self.match_types(b, &current_state.items[j].logical_type).filter(|_| adjacent) {
+                                // Apply the rule, carrying over what unification learned
+                                let result_type = a.apply_bindings(&bindings);
                                 
                                 let new_proof = ProofNode::infer(
                                     result_type,
@@ -521,85 +837,13 @@ impl TLGParser {
                         _ => {}
                     }
                     
-                    // Apply product rules if enabled
-                    if self.config.use_product {
-                        // Product elimination
-                        if let LogicalType::Product(a, b, _modality) = &current_state.items[i].logical_type {
-                            // Create hypotheses for the components of the product
-                            let hyp_a = ProofNode::axiom("x", (**a).clone());
-                            let _hyp_b = ProofNode::axiom("y", (**b).clone());
-                            
-                            // This is a simplified implementation - in reality we'd need
-                            // to track hypotheses and handle proper discharge
-                            
-                            let new_proof = ProofNode::infer(
-                                LogicalType::s(), // Example goal
-                                vec![
-                                    hyp_a.clone(),
-                                    current_state.items[i].clone(),
-                                ],
-                                "⊗E"
-                            );
-                            
-                            let new_state = current_state.apply_rule(
-                                "⊗E",
-                                new_proof,
-                                vec![i]
-                            );
-                            
-                            new_states.push(new_state);
-                        }
-                    }
-                    
-                    // Apply modal rules if enabled
-                    if self.config.use_modalities {
-                        // Diamond elimination
-                        if let LogicalType::Diamond(a, _modality) = &current_state.items[i].logical_type {
-                            let hyp = ProofNode::axiom("x", (**a).clone());
-                            
-                            let new_proof = ProofNode::infer(
-                                LogicalType::s(), // Example goal
-                                vec![
-                                    hyp.clone(),
-                                    current_state.items[i].clone(),
-                                ],
-                                "◇E"
-                            );
-                            
-                            let new_state = current_state.apply_rule(
-                                "◇E",
-                                new_proof,
-                                vec![i]
-                            );
-                            
-                            new_states.push(new_state);
-                        }
-                        
-                        // Box elimination
-                        if let LogicalType::Box(a, _modality) = &current_state.items[i].logical_type {
-                            let new_proof = ProofNode::infer(
-                                (**a).clone(),
-                                vec![current_state.items[i].clone()],
-                                "□E"
-                            );
-                            
-                            let new_state = current_state.apply_rule(
-                                "□E",
-                                new_proof,
-                                vec![i]
-                            );
-                            
-                            new_states.push(new_state);
-                        }
-                    }
-                    
                     // Apply displacement rules if enabled
                     if self.config.use_displacement {
                         // Up arrow elimination
                         if let LogicalType::UpArrow(a, b, index) = &current_state.items[i].logical_type {
-                            if self.types_match(b, &current_state.items[j].logical_type) {
+                            if let Some(bindings) = self.match_types(b, &current_state.items[j].logical_type) {
                                 // Apply the rule
-                                let result_type = (**a).clone();
+                                let result_type = a.apply_bindings(&bindings);
                                 
                                 let new_proof = ProofNode::infer(
                                     result_type,
@@ -615,13 +859,33 @@ impl TLGParser {
                                 
                                 new_states.push(new_state);
                             }
+                            
+                            // Or it binds its gap in a neighbouring proof of A
+                            let adjacent = current_state.items[i].precedes(&current_state.items[j])
+                                || current_state.items[j].precedes(&current_state.items[i]);
+                            if let Some(bindings) = self.match_types(a, &current_state.items[j].logical_type).filter(|_| adjacent) {
+                                let gap = current_state.items[j].open_hypotheses().into_iter()
+                                    .find(|hyp| hyp.rule.is_none() && self.match_types(b, &hyp.logical_type).is_some())
+                                    .cloned();
+                                
+                                if let Some(gap) = gap {
+                                    let rule = format!("↑{}E", index);
+                                    let new_proof = ProofNode::infer(
+                                        a.apply_bindings(&bindings),
+                                        vec![current_state.items[i].clone(), gap, current_state.items[j].clone()],
+                                        &rule
+                                    );
+                                    
+                                    new_states.push(current_state.apply_rule(&rule, new_proof, vec![i, j]));
+                                }
+                            }
                         }
                         
                         // Down arrow elimination
                         if let LogicalType::DownArrow(a, b, index) = &current_state.items[i].logical_type {
-                            if self.types_match(b, &current_state.items[j].logical_type) {
+                            if let Some(bindings) = self.match_types(b, &current_state.items[j].logical_type) {
                                 // Apply the rule
-                                let result_type = (**a).clone();
+                                let result_type = a.apply_bindings(&bindings);
                                 
                                 let new_proof = ProofNode::infer(
                                     result_type,
@@ -640,34 +904,215 @@ impl TLGParser {
                         }
                     }
                     
-                    // Add new states to the queue
+                    // Add new states to the agenda
                     for state in new_states {
-                        queue.push_back(state);
+                        agenda.push(state, 0.0);
                     }
                 }
             }
         }
+        self.explored_states.set(agenda.steps());
+        
+        if !proofs.is_empty() {
+            return Ok(proofs);
+        }
         
         // No proof found
-        eprintln!("No valid proof found for sentence with goal type: {}", goal);
-        None
+        Err(SearchFailure {
+            remaining: deepest.map(|state| state.items).unwrap_or_default(),
+            out_of_states: agenda.is_exhausted() && !agenda.is_empty(),
+        })
     }
     
-    /// Check if two types match, handling features if enabled
-    fn types_match(&self, type1: &LogicalType, type2: &LogicalType) -> bool {
-        if self.config.use_features {
-            // Try unification
-            if let Some(_) = type1.unify(type2) {
-                return true;
+    /// Get the modal postulates applicable to a type, with their results
+    ///
+    /// Residuation (`◇□A ⊢ A`) holds for every modality. The remaining
+    /// postulates are only available when the modality is registered with
+    /// the corresponding structural property: reflexivity licenses T
+    /// (`□A ⊢ A`), transitivity licenses 4 (`◇◇A ⊢ ◇A`) and distributivity
+    /// licenses K (`□(A ← B) ⊢ □A ← □B`). Modalities only interact with
+    /// operators of the same index.
+    fn modal_postulates(&self, logical_type: &LogicalType) -> Vec<(&'static str, LogicalType)> {
+        let mut results = Vec::new();
+        
+        match logical_type {
+            LogicalType::Diamond(inner, modality) => {
+                match &**inner {
+                    LogicalType::Box(a, inner_modality) if same_index(modality, inner_modality) => {
+                        results.push(("◇□E", (**a).clone()));
+                    },
+                    LogicalType::Diamond(a, inner_modality) if same_index(modality, inner_modality)
+                        && self.modality_has(modality, &StructuralProperty::Transitivity) => {
+                        results.push(("4", LogicalType::Diamond(a.clone(), modality.clone())));
+                    },
+                    _ => {}
+                }
+            },
+            LogicalType::Box(inner, modality) => {
+                if self.modality_has(modality, &StructuralProperty::Reflexivity) {
+                    results.push(("T", (**inner).clone()));
+                }
+                
+                if self.modality_has(modality, &StructuralProperty::Distributivity) {
+                    let boxed = |t: &LogicalType| LogicalType::Box(Box::new(t.clone()), modality.clone());
+                    
+                    match &**inner {
+                        LogicalType::LeftImplication(a, b, m) => {
+                            results.push(("K", LogicalType::LeftImplication(Box::new(boxed(a)), Box::new(boxed(b)), m.clone())));
+                        },
+                        LogicalType::RightImplication(a, b, m) => {
+                            results.push(("K", LogicalType::RightImplication(Box::new(boxed(a)), Box::new(boxed(b)), m.clone())));
+                        },
+                        _ => {}
+                    }
+                }
+            },
+            _ => {}
+        }
+        
+        results
+    }
+    
+    /// Check whether a functor may take its argument from the other side
+    ///
+    /// Without the directional calculus, and for implications with a
+    /// commutative modality, the argument may be on either side, as long as
+    /// the two items are next to each other.
+    fn allows_reordering(&self, modality: &Option<Modality>, functor: &ProofNode, argument: &ProofNode) -> bool {
+        (!self.config.directional
+            || self.modality_has(modality, &StructuralProperty::Commutativity)
+            || self.modality_has(modality, &StructuralProperty::Permutation))
+            && (functor.precedes(argument) || argument.precedes(functor))
+    }
+    
+    /// Check whether an item is a bracketed hypothesis free to occur anywhere
+    ///
+    /// A bracket of a commutative modality licenses the structural rules that
+    /// move its contents past its neighbours, so a gap inside one can be
+    /// consumed in medial position and still be discharged at the edge. The
+    /// gap left by a displaced argument may be anywhere as well.
+    fn is_mobile(&self, item: &ProofNode) -> bool {
+        if item.hypothesis && self.config.use_displacement {
+            return true;
+        }
+        
+        item.positions.is_empty() && item.brackets.iter().any(|&index| {
+            let modality = Some(Modality::new(index));
+            self.modality_has(&modality, &StructuralProperty::Commutativity)
+                || self.modality_has(&modality, &StructuralProperty::Permutation)
+        })
+    }
+    
+    /// Get the abstractions over the open gap hypotheses of an item
+    ///
+    /// Discharging a hypothesis of type `◇□A` from a proof of `B` gives
+    /// `B ← ◇□A` (`←I`) or `◇□A → B` (`→I`). An abstraction is only built
+    /// when another item of the state takes it as its argument.
+    fn discharges(&self, state: &ProofSearchState, i: usize) -> Vec<(&'static str, ProofNode)> {
+        let item = &state.items[i];
+        let mut results = Vec::new();
+        
+        for hyp in item.open_hypotheses() {
+            let gap = hyp.logical_type.clone();
+            let candidates = [
+                ("←I", LogicalType::LeftImplication(Box::new(item.logical_type.clone()), Box::new(gap.clone()), None)),
+                ("→I", LogicalType::RightImplication(Box::new(gap), Box::new(item.logical_type.clone()), None)),
+            ];
+            
+            for (rule, abstraction) in candidates {
+                let wanted = state.items.iter().enumerate().any(|(k, other)| {
+                    k != i && argument_type(&other.logical_type)
+                        .is_some_and(|argument| self.match_types(argument, &abstraction).is_some())
+                });
+                
+                if wanted {
+                    results.push((rule, ProofNode::infer(abstraction, vec![hyp.clone(), item.clone()], rule)));
+                }
             }
-            false
+        }
+        
+        results
+    }
+    
+    /// Check whether a modality has a structural property
+    ///
+    /// The properties of the registered modality with the same index take
+    /// precedence over those carried by the type itself.
+    fn modality_has(&self, modality: &Option<Modality>, property: &StructuralProperty) -> bool {
+        match modality {
+            Some(m) => self.config.modalities.iter()
+                .find(|registered| registered.index == m.index)
+                .unwrap_or(m)
+                .has_property(property),
+            None => false,
+        }
+    }
+    
+    /// Match an expected argument type against an actual one
+    ///
+    /// With features enabled this unifies the two types and returns the
+    /// feature variable bindings, so they can be applied to the result of the
+    /// rule; otherwise the types must be identical.
+    fn match_types(&self, expected: &LogicalType, actual: &LogicalType) -> Option<Bindings<'_>> {
+        if self.config.use_features {
+            let mut bindings = Bindings::with_registry(&self.feature_registry);
+            expected.unify_with_bindings(actual, &mut bindings).map(|_| bindings)
+        } else if expected == actual {
+            Some(Bindings::new())
         } else {
-            // Simple equality
-            type1 == type2
+            None
         }
     }
 }
 
+/// Get the argument type of an implication
+fn argument_type(logical_type: &LogicalType) -> Option<&LogicalType> {
+    match logical_type {
+        LogicalType::RightImplication(a, _, _) => Some(a),
+        LogicalType::LeftImplication(_, b, _) => Some(b),
+        _ => None,
+    }
+}
+
+/// Get the rule and result for applying an implication to the unit
+fn unit_application(logical_type: &LogicalType) -> Option<(&'static str, &LogicalType)> {
+    match logical_type {
+        LogicalType::RightImplication(a, b, None) if **a == LogicalType::Unit => Some(("→E", b)),
+        LogicalType::LeftImplication(a, b, None) if **b == LogicalType::Unit => Some(("←E", a)),
+        _ => None,
+    }
+}
+
+/// Collect the gap types `◇□A` that a type abstracts over in its arguments
+fn collect_gap_types(logical_type: &LogicalType, gaps: &mut Vec<LogicalType>) {
+    let is_gap = |t: &LogicalType| matches!(t, LogicalType::Diamond(inner, Some(d))
+        if matches!(&**inner, LogicalType::Box(_, Some(b)) if b.index == d.index));
+    
+    match logical_type {
+        LogicalType::RightImplication(a, b, _) | LogicalType::LeftImplication(b, a, _) => {
+            match &**a {
+                LogicalType::LeftImplication(_, gap, _) | LogicalType::RightImplication(gap, _, _) if is_gap(gap) => {
+                    gaps.push((**gap).clone());
+                },
+                _ => {}
+            }
+            collect_gap_types(b, gaps);
+        },
+        _ => {}
+    }
+}
+
+/// Check whether two optional modalities have the same index
+fn same_index(a: &Option<Modality>, b: &Option<Modality>) -> bool {
+    a.as_ref().map(|m| m.index) == b.as_ref().map(|m| m.index)
+}
+
+impl Default for TLGParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ParserTrait for TLGParser {
     type Cat = LogicalType;
     type Node = ProofNode;
@@ -683,6 +1128,44 @@ impl ParserTrait for TLGParser {
         }
     }
     
+    fn parse_result(&self, sentence: &str) -> Result<Self::Node, Error> {
+        let no_parse = || Error::NoParse { sentence: sentence.to_string() };
+        if self.config.use_proof_nets {
+            return self.parse_with_proof_nets(sentence).ok_or_else(no_parse);
+        }
+        
+        self.parse_explained(sentence).map_err(|error| match error {
+            Error::NoProof { .. } => no_parse(),
+            error => error,
+        })
+    }
+    
+    /// Parse a sentence, exploring at most `budget` search states
+    ///
+    /// When the budget runs out, the best partial proof is the item of the
+    /// deepest state reached that covers the most words.
+    fn parse_within(&self, sentence: &str, budget: StepBudget) -> ParseOutcome<Self::Node> {
+        let lexical = match self.lexical_axioms(sentence) {
+            Ok(lexical) => lexical,
+            Err(_) => return ParseOutcome::Exhausted,
+        };
+        
+        match self.prove_sentence(&lexical, &LogicalType::s(), false, budget.max_steps) {
+            Ok(mut proofs) => ParseOutcome::Complete(proofs.remove(0)),
+            Err(SearchFailure { remaining, out_of_states: true }) => {
+                let best_partial = remaining.into_iter()
+                    .rev()
+                    .max_by_key(|item| item.positions.len());
+                ParseOutcome::BudgetExceeded { best_partial }
+            },
+            Err(_) => ParseOutcome::Exhausted,
+        }
+    }
+    
+    fn parse_all(&self, sentence: &str) -> Vec<Self::Node> {
+        TLGParser::parse_all(self, sentence)
+    }
+    
     fn add_to_lexicon(&mut self, word: &str, category: Self::Cat) {
         if self.validate_type(&category) {
             self.lexicon.add(word, category);
@@ -691,6 +1174,16 @@ impl ParserTrait for TLGParser {
         }
     }
     
+    fn lexicon_words(&self) -> Vec<String> {
+        let mut words = self.lexicon.get_words();
+        words.sort();
+        words
+    }
+    
+    fn lexicon_entries(&self, word: &str) -> Vec<Self::Cat> {
+        self.lexicon.get_types(word)
+    }
+    
     fn config(&self) -> &Self::Config {
         &self.config
     }
@@ -734,6 +1227,7 @@ impl ParserTrait for TLGParser {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::LambdaTerm;
     
     // Helper to create a simple parser for testing
     fn setup_test_parser() -> TLGParser {
@@ -894,4 +1388,490 @@ mod tests {
         let result = parser.parse("what John sees");
         assert!(result.is_some());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_unified_features_propagate() {
+        let mut parser = TLGParser::new();
+        parser.lexicon = Lexicon::new();
+        parser.register_feature("num", &["sg", "pl"]);
+        
+        let sg = FeatureStructure::with_feature("num", FeatureValue::Atomic("sg".to_string()));
+        let pl = FeatureStructure::with_feature("num", FeatureValue::Atomic("pl".to_string()));
+        let any_num = FeatureStructure::with_feature("num", FeatureValue::Variable("n".to_string()));
+        
+        // "slept" is underspecified for number and passes its subject's number on
+        let slept = LogicalType::right_impl(
+            LogicalType::atomic_with_features("np", &any_num),
+            LogicalType::atomic_with_features("s", &any_num),
+        );
+        
+        // "together" only continues a plural clause
+        let together = LogicalType::right_impl(LogicalType::atomic_with_features("s", &pl), LogicalType::s());
+        
+        parser.add_to_lexicon("it", LogicalType::atomic_with_features("np", &sg));
+        parser.add_to_lexicon("they", LogicalType::atomic_with_features("np", &pl));
+        parser.add_to_lexicon("slept", slept);
+        parser.add_to_lexicon("together", together);
+        
+        assert!(parser.parse("they slept together").is_some());
+        
+        // The verb becomes singular through its subject, which blocks "together"
+        assert!(parser.parse("it slept together").is_none());
+    }
+
+    /// Set up a parser with modalities enabled and an empty lexicon
+    fn setup_modal_parser() -> TLGParser {
+        let mut parser = TLGParser::new();
+        parser.lexicon = Lexicon::new();
+        parser.config.use_modalities = true;
+        
+        // Modality 1 marks islands; modality 2 is transparent
+        parser.register_modality(1, vec![]);
+        parser.register_modality(2, vec![
+            StructuralProperty::Reflexivity,
+            StructuralProperty::Transitivity,
+            StructuralProperty::Distributivity,
+        ]);
+        
+        parser
+    }
+    
+    #[test]
+    fn test_modal_postulates() {
+        let parser = setup_modal_parser();
+        let np = LogicalType::np();
+        let island = Modality::new(1);
+        let transparent = Modality::new(2);
+        
+        // Residuation holds for every modality, but only within an index
+        let residuated = LogicalType::diamond_with_modality(
+            LogicalType::boxed_with_modality(np.clone(), island.clone()),
+            island.clone(),
+        );
+        assert_eq!(parser.modal_postulates(&residuated), vec![("◇□E", np.clone())]);
+        
+        let mixed = LogicalType::diamond_with_modality(
+            LogicalType::boxed_with_modality(np.clone(), transparent.clone()),
+            island.clone(),
+        );
+        assert!(parser.modal_postulates(&mixed).is_empty());
+        
+        // T requires reflexivity
+        let boxed_island = LogicalType::boxed_with_modality(np.clone(), island.clone());
+        assert!(parser.modal_postulates(&boxed_island).is_empty());
+        
+        let boxed_transparent = LogicalType::boxed_with_modality(np.clone(), transparent.clone());
+        assert_eq!(parser.modal_postulates(&boxed_transparent), vec![("T", np.clone())]);
+        
+        // 4 collapses stacked diamonds of a transitive modality
+        let stacked = LogicalType::diamond_with_modality(
+            LogicalType::diamond_with_modality(np.clone(), transparent.clone()),
+            transparent.clone(),
+        );
+        assert_eq!(
+            parser.modal_postulates(&stacked),
+            vec![("4", LogicalType::diamond_with_modality(np.clone(), transparent.clone()))]
+        );
+        
+        // K distributes the box over implications
+        let iv = LogicalType::left_impl(LogicalType::s(), np.clone());
+        let boxed_iv = LogicalType::boxed_with_modality(iv, transparent.clone());
+        let distributed = LogicalType::left_impl(
+            LogicalType::boxed_with_modality(LogicalType::s(), transparent.clone()),
+            LogicalType::boxed_with_modality(np, transparent),
+        );
+        assert!(parser.modal_postulates(&boxed_iv).contains(&("K", distributed)));
+    }
+    
+    #[test]
+    fn test_modal_extraction_island() {
+        let s = LogicalType::s();
+        let np = LogicalType::np();
+        let tv = LogicalType::left_impl(LogicalType::left_impl(s.clone(), np.clone()), np.clone());
+        
+        // "what" looks for a clause missing an np
+        let wh = LogicalType::left_impl(s.clone(), LogicalType::left_impl(s.clone(), np.clone()));
+        
+        for (index, extractable) in [(1, false), (2, true)] {
+            let mut parser = setup_modal_parser();
+            parser.add_to_lexicon("what", wh.clone());
+            parser.add_to_lexicon("John", np.clone());
+            parser.add_to_lexicon("reads", LogicalType::boxed_with_modality(tv.clone(), Modality::new(index)));
+            
+            assert_eq!(parser.parse("what John reads").is_some(), extractable);
+        }
+        
+        // Outside an island the same extraction is allowed
+        let mut parser = setup_modal_parser();
+        parser.add_to_lexicon("what", wh);
+        parser.add_to_lexicon("John", np);
+        parser.add_to_lexicon("reads", tv);
+        assert!(parser.parse("what John reads").is_some());
+    }
+
+    #[test]
+    fn test_lambda_term() {
+        let mut parser = setup_modal_parser();
+        let np = LogicalType::np();
+        let n = LogicalType::n();
+        
+        // the := λx.the(x), so the derivation contains a redex
+        let the = LambdaTerm::abs("x", LambdaTerm::app(LambdaTerm::constant("the"), LambdaTerm::var("x")));
+        parser.lexicon.add_with_semantics("the", LogicalType::left_impl(np.clone(), n.clone()), the);
+        parser.add_to_lexicon("cat", n);
+        parser.add_to_lexicon("sleeps", LogicalType::right_impl(np, LogicalType::s()));
+        
+        let proof = parser.parse("the cat sleeps").unwrap();
+        assert_eq!(proof.lambda_term().to_string(), "sleeps((λx.the(x))(cat))");
+        assert_eq!(proof.lambda_term().beta_normalize().to_string(), "sleeps(the(cat))");
+    }
+    
+    #[test]
+    fn test_parse_explained() {
+        let parser = setup_test_parser();
+        
+        assert!(parser.parse_explained("the cat sleeps").is_ok());
+        
+        match parser.parse_explained("the cat sleeps the") {
+            Err(Error::NoProof { remaining, unconsumed, dangling, .. }) => {
+                // The clause is built but the final determiner is left over
+                assert_eq!(remaining.len(), 2);
+                assert_eq!(unconsumed, vec!["the".to_string()]);
+                assert!(dangling.contains(&"n".to_string()));
+            },
+            other => panic!("Expected a failed proof search, got {:?}", other),
+        }
+        
+        assert!(matches!(
+            parser.parse_explained("the unicorn sleeps"),
+            Err(Error::UnknownWord { word, position: 1 }) if word == "unicorn"
+        ));
+    }
+    
+    #[test]
+    fn test_parse_result() {
+        let parser = setup_test_parser();
+        
+        assert!(parser.parse_result("the cat sleeps").is_ok());
+        assert!(matches!(
+            parser.parse_result("the unicorn sleeps"),
+            Err(Error::UnknownWord { word, position: 1 }) if word == "unicorn"
+        ));
+        assert!(matches!(
+            parser.parse_result("the cat sleeps the"),
+            Err(Error::NoParse { .. })
+        ));
+    }
+    
+    #[test]
+    fn test_lexicon_enumeration() {
+        let mut parser = TLGParser::new();
+        parser.lexicon = Lexicon::new();
+        parser.add_to_lexicon("the", LogicalType::left_impl(LogicalType::np(), LogicalType::n()));
+        parser.add_to_lexicon("sleeps", LogicalType::right_impl(LogicalType::np(), LogicalType::s()));
+        
+        assert_eq!(parser.lexicon_words(), vec!["sleeps", "the"]);
+        assert!(setup_test_parser().lexicon_words().contains(&"cat".to_string()));
+        assert_eq!(parser.lexicon_entries("sleeps"), vec![LogicalType::right_impl(LogicalType::np(), LogicalType::s())]);
+        assert!(parser.lexicon_entries("unicorn").is_empty());
+    }
+    
+    #[test]
+    fn test_parse_to() {
+        let mut parser = setup_test_parser();
+        
+        let proof = parser.parse_to("the cat", &LogicalType::np()).unwrap();
+        assert_eq!(proof.logical_type, LogicalType::np());
+        assert_eq!(proof.positions, vec![0, 1]);
+        
+        // The default goal is a sentence
+        assert!(parser.parse("the cat").is_none());
+        assert!(parser.parse_to("the cat sleeps", &LogicalType::np()).is_none());
+        
+        // Goals must be built from registered atomic types
+        assert!(parser.parse_to("the cat", &LogicalType::atomic("pp")).is_none());
+        
+        // Proof nets are searched for the same goal
+        parser.config.use_proof_nets = true;
+        assert_eq!(parser.parse_to("the cat", &LogicalType::np()).unwrap().logical_type, LogicalType::np());
+        assert!(parser.parse_to("the cat sleeps", &LogicalType::np()).is_none());
+        assert!(parser.parse("the cat").is_none());
+    }
+    
+    #[test]
+    fn test_product_elimination() {
+        let mut parser = TLGParser::new();
+        parser.config.directional = true;
+        let s = LogicalType::s();
+        let np = LogicalType::np();
+        
+        // A clitic cluster supplies both objects of the verb that follows it
+        parser.add_to_lexicon("Juan", np.clone());
+        parser.add_to_lexicon("selo", LogicalType::product(np.clone(), np.clone()));
+        parser.add_to_lexicon("dio", LogicalType::right_impl(
+            np.clone(),
+            LogicalType::right_impl(np.clone(), LogicalType::right_impl(np.clone(), s.clone())),
+        ));
+        
+        let proof = parser.parse("Juan selo dio").unwrap();
+        assert!(proof.uses_rule("⊗E"));
+        assert!(proof.open_hypotheses().is_empty());
+        assert_eq!(proof.positions, vec![0, 1, 2]);
+        
+        // Both components of the cluster stand at its position
+        fn hypotheses<'a>(node: &'a ProofNode, found: &mut Vec<&'a ProofNode>) {
+            if node.hypothesis {
+                found.push(node);
+            }
+            node.children.iter().for_each(|child| hypotheses(child, found));
+        }
+        let mut found = Vec::new();
+        hypotheses(&proof, &mut found);
+        assert!(!found.is_empty());
+        assert!(found.iter().all(|hypothesis| hypothesis.positions == vec![1]));
+        
+        // The verb takes the projections of the cluster in order
+        assert_eq!(proof.lambda_term().beta_normalize().to_string(), "dio(π2(selo))(π1(selo))(Juan)");
+        
+        // The components stand where the cluster is, so it cannot follow the verb
+        assert!(parser.parse("Juan dio selo").is_none());
+        
+        // Without product elimination the cluster cannot be taken apart
+        let mut config = parser.config.clone();
+        config.use_product = false;
+        parser.set_config(config);
+        assert!(parser.parse("Juan selo dio").is_none());
+    }
+    
+    #[test]
+    fn test_count_proofs() {
+        let mut parser = setup_modal_parser();
+        parser.register_modality(3, vec![StructuralProperty::Commutativity]);
+        parser.register_feature("case", &["nom", "acc"]);
+        
+        let s = LogicalType::s();
+        let case = |value: &str| LogicalType::atomic_with_features(
+            "np", &FeatureStructure::with_feature("case", FeatureValue::Atomic(value.to_string())));
+        let gap = |np: LogicalType| LogicalType::diamond_with_modality(
+            LogicalType::boxed_with_modality(np, Modality::new(3)),
+            Modality::new(3),
+        );
+        
+        // One entry per word: each quantifier binds a gap of its own case
+        parser.add_to_lexicon("loves", LogicalType::left_impl(LogicalType::right_impl(case("nom"), s.clone()), case("acc")));
+        parser.add_to_lexicon("everyone", LogicalType::left_impl(s.clone(), LogicalType::right_impl(gap(case("nom")), s.clone())));
+        parser.add_to_lexicon("someone", LogicalType::right_impl(LogicalType::left_impl(s.clone(), gap(case("acc"))), s.clone()));
+        parser.add_to_lexicon("john", case("nom"));
+        
+        assert_eq!(parser.count_proofs("everyone loves someone"), 2);
+        assert_eq!(parser.count_proofs("john loves someone"), 1);
+        assert_eq!(parser.count_proofs("everyone loves"), 0);
+        
+        // The readings differ in which quantifier takes scope over the other
+        let mut readings: Vec<String> = parser.parse_all("everyone loves someone").iter()
+            .map(|proof| proof.lambda_term().beta_normalize().to_string())
+            .collect();
+        readings.sort();
+        assert_eq!(readings, vec![
+            "everyone(λz0.someone(λz1.loves(z1)(z0)))",
+            "someone(λz1.everyone(λz0.loves(z1)(z0)))",
+        ]);
+    }
+    
+    #[test]
+    fn test_pp_attachment() {
+        let mut parser = TLGParser::new();
+        parser.lexicon = Lexicon::new();
+        parser.config.directional = true;
+        parser.config.max_depth = 100_000;
+        let np = LogicalType::np();
+        let n = LogicalType::n();
+        let vp = LogicalType::right_impl(np.clone(), LogicalType::s());
+        
+        parser.add_to_lexicon("john", np.clone());
+        parser.add_to_lexicon("saw", LogicalType::left_impl(vp.clone(), np.clone()));
+        parser.add_to_lexicon("the", LogicalType::left_impl(np.clone(), n.clone()));
+        parser.add_to_lexicon("man", n.clone());
+        parser.add_to_lexicon("telescope", n.clone());
+        parser.add_to_lexicon("with", LogicalType::left_impl(LogicalType::right_impl(n.clone(), n), np.clone()));
+        parser.add_to_lexicon("with", LogicalType::left_impl(LogicalType::right_impl(vp.clone(), vp), np));
+        
+        // Noun modification and verb phrase modification
+        let proofs = parser.parse_all("john saw the man with the telescope");
+        assert_eq!(proofs.len(), 2);
+        assert!(!proofs[0].is_equivalent(&proofs[1]));
+        assert_eq!(parser.parse_all("john saw the man").len(), 1);
+    }
+    
+    #[test]
+    fn test_unit_optional_argument() {
+        let mut parser = TLGParser::new();
+        parser.lexicon = Lexicon::new();
+        parser.config.directional = true;
+        let np = LogicalType::np();
+        let vp = LogicalType::right_impl(np.clone(), LogicalType::s());
+        
+        // The object of "eats" is optional: without it the verb applies to the unit
+        parser.add_to_lexicon("john", np.clone());
+        parser.add_to_lexicon("apples", np.clone());
+        parser.add_to_lexicon("eats", LogicalType::left_impl(vp.clone(), np));
+        parser.add_to_lexicon("eats", LogicalType::left_impl(vp.clone(), LogicalType::unit()));
+        assert_eq!(parser.lexicon.get_items("eats").len(), 2);
+        
+        let transitive = parser.parse_all("john eats apples");
+        assert_eq!(transitive.len(), 1);
+        
+        let intransitive = parser.parse_all("john eats");
+        assert_eq!(intransitive.len(), 1);
+        assert_eq!(intransitive[0].lambda_term().to_string(), transitive[0].lambda_term().to_string().replace("apples", "()"));
+        assert!(parser.parse("eats").is_none());
+        
+        // An empty operator of the unit type is absorbed by its neighbour
+        parser.add_to_lexicon("sleeps", vp);
+        parser.add_to_lexicon("indeed", LogicalType::unit());
+        let absorbed = parser.parse_all("john sleeps indeed");
+        assert_eq!(absorbed.len(), 1);
+        assert_eq!(absorbed[0].positions, vec![0, 1, 2]);
+    }
+    
+    #[test]
+    fn test_lexicon_phrases() {
+        let mut parser = setup_test_parser();
+        parser.add_to_lexicon("New York", LogicalType::np());
+        
+        // The two words are one axiom covering both of their positions
+        let proof = parser.parse("New York sleeps").unwrap();
+        assert_eq!(proof.positions, vec![0, 1, 2]);
+        assert!(proof.children.iter().any(|child| child.label == "New York" && child.positions == vec![0, 1]));
+        
+        assert!(parser.parse("New sleeps").is_none());
+        
+        // Positions count the words of the input, not its lexical tokens
+        assert!(matches!(
+            parser.parse_result("New York sleeps soundly"),
+            Err(Error::UnknownWord { word, position: 3 }) if word == "soundly"
+        ));
+    }
+    
+    #[test]
+    fn test_parse_within() {
+        let parser = setup_test_parser();
+        
+        assert!(parser.parse_within("the cat sleeps", StepBudget::new(1000)).is_complete());
+        assert_eq!(parser.parse_within("the sleeps cat", StepBudget::new(1000)), ParseOutcome::Exhausted);
+        assert!(matches!(
+            parser.parse_within("the cat sleeps", StepBudget::new(1)),
+            ParseOutcome::BudgetExceeded { best_partial: Some(_) }
+        ));
+    }
+    
+    #[test]
+    fn test_memoized_states() {
+        let mut parser = setup_test_parser();
+        parser.config.max_depth = 100_000;
+        let np = LogicalType::np();
+        parser.add_to_lexicon("dog", LogicalType::n());
+        parser.add_to_lexicon("sees", LogicalType::left_impl(LogicalType::right_impl(np.clone(), LogicalType::s()), np));
+        
+        let sentence = "the cat sees the dog";
+        let memoized = parser.parse_all(sentence).len();
+        let memoized_all = parser.explored_states();
+        assert!(parser.parse(sentence).is_some());
+        let memoized_first = parser.explored_states();
+        
+        parser.config.memoize_states = false;
+        let unmemoized = parser.parse_all(sentence).len();
+        let unmemoized_all = parser.explored_states();
+        assert!(parser.parse(sentence).is_some());
+        let unmemoized_first = parser.explored_states();
+        
+        // Combining the two noun phrases in either order reaches the same state
+        assert_eq!(memoized, unmemoized);
+        assert!(memoized_all < unmemoized_all);
+        assert!(memoized_first < unmemoized_first);
+    }
+    
+    #[test]
+    fn test_morphological_fallback() {
+        let mut parser = setup_test_parser();
+        parser.add_to_lexicon("walk", LogicalType::right_impl(LogicalType::np(), LogicalType::s()));
+        assert!(parser.parse_with_natural_deduction("the cat walked").is_none());
+        
+        parser.morphology = Some(Box::new(crate::common::EnglishSuffixAnalyzer::new()));
+        let proof = parser.parse_with_natural_deduction("the cat walked").unwrap();
+        assert_eq!(
+            proof.logical_type.get_features().and_then(|fs| fs.get("tense")),
+            Some(&FeatureValue::Atomic("past".to_string()))
+        );
+        assert!(parser.parse_with_natural_deduction("the cat walking").is_some());
+        assert!(parser.parse_with_natural_deduction("the cat sleeping").is_some());
+    }
+    
+    #[test]
+    fn test_underspecified_feature_values() {
+        let mut parser = TLGParser::new();
+        parser.register_feature("num", &["sg", "pl"]);
+        parser.feature_registry.register_subsumption("num", "any", "sg");
+        parser.feature_registry.register_subsumption("num", "any", "pl");
+        
+        let np = LogicalType::np();
+        let s = LogicalType::s();
+        let n_sg = parser.create_category_with_features("n", &[("num", "sg")]).unwrap();
+        let n_pl = parser.create_category_with_features("n", &[("num", "pl")]).unwrap();
+        let n_any = parser.create_category_with_features("n", &[("num", "any")]).unwrap();
+        
+        parser.add_to_lexicon("this", LogicalType::left_impl(np.clone(), n_sg));
+        parser.add_to_lexicon("these", LogicalType::left_impl(np.clone(), n_pl.clone()));
+        parser.add_to_lexicon("cats", n_pl);
+        parser.add_to_lexicon("sheep", n_any);
+        parser.add_to_lexicon("sleep", LogicalType::right_impl(np, s));
+        
+        // Number is underspecified for "sheep", so either determiner fits
+        assert!(parser.parse_with_natural_deduction("this sheep sleep").is_some());
+        assert!(parser.parse_with_natural_deduction("these sheep sleep").is_some());
+        assert!(parser.parse_with_natural_deduction("these cats sleep").is_some());
+        assert!(parser.parse_with_natural_deduction("this cats sleep").is_none());
+    }
+    
+    #[test]
+    fn test_medial_extraction_with_brackets() {
+        let s = LogicalType::s();
+        let np = LogicalType::np();
+        let pp = LogicalType::atomic("pp");
+        
+        // put: takes its object, then a pp, then its subject
+        let put = LogicalType::left_impl(
+            LogicalType::left_impl(LogicalType::right_impl(np.clone(), s.clone()), pp.clone()),
+            np.clone(),
+        );
+        
+        // The gap of "what" is medial, so it must move past "there"
+        let gap = |index| LogicalType::diamond_with_modality(
+            LogicalType::boxed_with_modality(np.clone(), Modality::new(index)),
+            Modality::new(index),
+        );
+        let cases = [
+            (LogicalType::left_impl(s.clone(), LogicalType::left_impl(s.clone(), np.clone())), false),
+            (LogicalType::left_impl(s.clone(), LogicalType::left_impl(s.clone(), gap(1))), false),
+            (LogicalType::left_impl(s.clone(), LogicalType::left_impl(s.clone(), gap(3))), true),
+        ];
+        
+        for (what, extractable) in cases {
+            let mut parser = setup_modal_parser();
+            parser.register_modality(3, vec![StructuralProperty::Commutativity]);
+            parser.register_atomic_type("pp");
+            parser.add_to_lexicon("what", what);
+            parser.add_to_lexicon("John", np.clone());
+            parser.add_to_lexicon("put", put.clone());
+            parser.add_to_lexicon("there", pp.clone());
+            
+            let proof = parser.parse("what John put there");
+            assert_eq!(proof.is_some(), extractable);
+            
+            if let Some(proof) = proof {
+                assert!(proof.uses_rule("←I"));
+                assert!(proof.open_hypotheses().is_empty());
+            }
+        }
+    }
+}
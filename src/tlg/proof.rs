@@ -2,10 +2,11 @@
 
 use std::fmt;
 use crate::tlg::logical_type::LogicalType;
-use crate::common::ParseNode;
+use crate::common::LambdaTerm;
+use crate::common::{ParseNode, Rebuild};
 
 /// Labeled natural deduction proof node for Type-Logical Grammar
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ProofNode {
     /// The logical type
     pub logical_type: LogicalType,
@@ -15,6 +16,14 @@ pub struct ProofNode {
     pub children: Vec<ProofNode>,
     /// The inference rule used
     pub rule: Option<String>,
+    /// Lexical semantics for axioms drawn from the lexicon
+    pub semantics: Option<LambdaTerm>,
+    /// The input positions covered by this node, in order
+    pub positions: Vec<usize>,
+    /// The modality indices of the brackets around this node's structure
+    pub brackets: Vec<usize>,
+    /// Whether this leaf is a hypothesis rather than a lexical axiom
+    pub hypothesis: bool,
 }
 
 impl ProofNode {
@@ -25,19 +34,121 @@ impl ProofNode {
             label: label.to_string(),
             children: vec![],
             rule: None,
+            semantics: None,
+            positions: vec![],
+            brackets: vec![],
+            hypothesis: false,
         }
     }
+    
+    /// Create a hypothesis, to be discharged by a rule higher in the proof
+    pub fn assumption(label: &str, logical_type: LogicalType) -> Self {
+        let mut node = Self::axiom(label, logical_type);
+        node.hypothesis = true;
+        node
+    }
+    
+    /// Create a new axiom carrying the lexical meaning of its word
+    pub fn axiom_with_semantics(label: &str, logical_type: LogicalType, semantics: LambdaTerm) -> Self {
+        let mut node = Self::axiom(label, logical_type);
+        node.semantics = Some(semantics);
+        node
+    }
 
     /// Create a new internal node in the proof tree
     pub fn infer(logical_type: LogicalType, children: Vec<ProofNode>, rule: &str) -> Self {
         // For non-axioms, generate a composite label derived from children
         let label = Self::generate_label(&children, rule);
         
+        let mut positions: Vec<usize> = children.iter()
+            .flat_map(|child| child.positions.iter().copied())
+            .collect();
+        positions.sort_unstable();
+        
         ProofNode {
             logical_type,
             label,
             children,
             rule: Some(rule.to_string()),
+            semantics: None,
+            positions,
+            brackets: vec![],
+            hypothesis: false,
+        }
+    }
+    
+    /// Create the proof of the unit type from no input (`1I`)
+    pub fn unit() -> Self {
+        Self::infer(LogicalType::Unit, vec![], "1I")
+    }
+    
+    /// Create a bracketed hypothesis for a gap of type `◇□A`
+    ///
+    /// The `◇` of the hypothesis introduces a bracket around it, which the
+    /// `□` consumes, leaving a bracketed item of type `A` that covers no
+    /// input. Returns `None` unless both operators share an index.
+    pub fn hypothesis(label: &str, gap_type: LogicalType) -> Option<Self> {
+        let (inner, index) = match &gap_type {
+            LogicalType::Diamond(boxed, Some(diamond)) => match &**boxed {
+                LogicalType::Box(inner, Some(modality)) if modality.index == diamond.index => {
+                    ((**inner).clone(), diamond.index)
+                },
+                _ => return None,
+            },
+            _ => return None,
+        };
+        
+        let mut node = Self::infer(inner, vec![Self::assumption(label, gap_type)], "◇□E");
+        node.brackets.push(index);
+        Some(node)
+    }
+    
+    /// Get the hypotheses used in this proof that have not been discharged
+    ///
+    /// `→I` and `←I` discharge the hypothesis given as their first child,
+    /// `↑E` the gap following the displaced item, and `⊗E` the two
+    /// hypotheses following the product it eliminates.
+    pub fn open_hypotheses(&self) -> Vec<&ProofNode> {
+        match (self.rule.as_deref(), self.children.as_slice()) {
+            (None, _) if self.hypothesis => vec![self],
+            (Some("→I" | "←I"), [hyp, body]) => body.open_hypotheses().into_iter()
+                .filter(|open| open.label != hyp.label)
+                .collect(),
+            (Some(rule), [functor, hyp, body]) if rule.starts_with('↑') => {
+                let mut open = functor.open_hypotheses();
+                open.extend(body.open_hypotheses().into_iter().filter(|open| open.label != hyp.label));
+                open
+            },
+            (Some("⊗E"), [product, hyp_a, hyp_b, body]) => {
+                let mut open = product.open_hypotheses();
+                open.extend(body.open_hypotheses().into_iter()
+                    .filter(|open| open.label != hyp_a.label && open.label != hyp_b.label));
+                open
+            },
+            _ => self.children.iter().flat_map(|child| child.open_hypotheses()).collect(),
+        }
+    }
+    
+    /// Set the input position of a lexical axiom
+    pub fn at_position(mut self, position: usize) -> Self {
+        self.positions = vec![position];
+        self
+    }
+    
+    /// Map the input positions covered by this proof to the words at them
+    fn with_words(&self, words: &[usize]) -> ProofNode {
+        let mut node = self.clone();
+        node.positions = self.positions.iter().map(|&position| words.get(position).copied().unwrap_or(position)).collect();
+        node.positions.dedup();
+        node.children = self.children.iter().map(|child| child.with_words(words)).collect();
+        node
+    }
+    
+    /// Check whether this node covers the input immediately before another node
+    pub fn precedes(&self, other: &ProofNode) -> bool {
+        match (self.positions.last(), other.positions.first()) {
+            (Some(last), Some(first)) => last + 1 == *first,
+            _ => false,
         }
     }
 
@@ -52,23 +163,22 @@ impl ProofNode {
                     "invalid".to_string()
                 }
             },
-            "→I" => {
-                // Lambda abstraction: λx.M
-                if !children.is_empty() {
-                    let var = children[0].label.chars().next().unwrap_or('x');
-                    format!("λ{}.{}", var, children[0].label)
+            "→I" | "←I" => {
+                // Lambda abstraction over the discharged hypothesis: λx.M
+                if children.len() == 2 {
+                    format!("λ{}.{}", children[0].label, children[1].label)
                 } else {
                     "invalid".to_string()
                 }
             },
             "⊗E" => {
                 // Product elimination: pair destructuring
-                if children.len() >= 2 {
-                    format!("let ({},{}) = {} in {}", 
-                           children[0].label.chars().next().unwrap_or('x'),
-                           children[0].label.chars().nth(1).unwrap_or('y'),
+                if children.len() == 4 {
+                    format!("let ({},{}) = {} in {}",
                            children[1].label,
-                           children[0].label)
+                           children[2].label,
+                           children[0].label,
+                           children[3].label)
                 } else {
                     "invalid".to_string()
                 }
@@ -116,6 +226,15 @@ impl ProofNode {
                     "invalid".to_string()
                 }
             },
+            "1I" => "()".to_string(),
+            "1E" => {
+                // Unit elimination: the unit is absorbed
+                if children.len() == 2 {
+                    children[1].label.clone()
+                } else {
+                    "invalid".to_string()
+                }
+            },
             "←E" => {
                 // Backward application: combine function and argument labels
                 if children.len() == 2 {
@@ -142,6 +261,254 @@ impl ProofNode {
         }
     }
     
+    /// Extract the lambda term denoted by this proof
+    ///
+    /// Eliminations of `→`/`←` become application, introductions become
+    /// abstraction over the discharged hypothesis (the first child), and `⊗I`
+    /// becomes pairing. A `⊗E` node with children `[product, hyp_a, hyp_b, body]`
+    /// substitutes the projections of the product for its hypotheses. Axioms
+    /// use their lexical semantics, or their label as a constant.
+    pub fn lambda_term(&self) -> LambdaTerm {
+        self.build_term(&mut Vec::new())
+    }
+    
+    fn build_term(&self, bound: &mut Vec<String>) -> LambdaTerm {
+        let rule = match &self.rule {
+            Some(rule) => rule.as_str(),
+            None => {
+                if bound.contains(&self.label) {
+                    return LambdaTerm::Var(self.label.clone());
+                }
+                return self.semantics.clone()
+                    .unwrap_or_else(|| LambdaTerm::Const(self.label.clone()));
+            },
+        };
+        
+        match (rule, self.children.as_slice()) {
+            ("→I" | "←I", [hyp, body]) => {
+                bound.push(hyp.label.clone());
+                let body = body.build_term(bound);
+                bound.pop();
+                LambdaTerm::abs(&hyp.label, body)
+            },
+            ("⊗I", [first, second]) => {
+                LambdaTerm::pair(first.build_term(bound), second.build_term(bound))
+            },
+            ("1E", [_, body]) => body.build_term(bound),
+            (rule, [functor, hyp, body]) if rule.starts_with('↑') => {
+                bound.push(hyp.label.clone());
+                let body = body.build_term(bound);
+                bound.pop();
+                LambdaTerm::app(functor.build_term(bound), LambdaTerm::abs(&hyp.label, body))
+            },
+            ("⊗E", [product, hyp_a, hyp_b, body]) => {
+                let product = product.build_term(bound);
+                bound.push(hyp_a.label.clone());
+                bound.push(hyp_b.label.clone());
+                let body = body.build_term(bound);
+                bound.truncate(bound.len() - 2);
+                body.substitute(&hyp_a.label, &LambdaTerm::Fst(Box::new(product.clone())))
+                    .substitute(&hyp_b.label, &LambdaTerm::Snd(Box::new(product)))
+            },
+            ("→E" | "←E", [fun, arg]) => LambdaTerm::app(fun.build_term(bound), arg.build_term(bound)),
+            (rule, [fun, arg]) if (rule.starts_with('↑') || rule.starts_with('↓')) && rule.ends_with('E') => {
+                LambdaTerm::app(fun.build_term(bound), arg.build_term(bound))
+            },
+            // Unary structural and modal rules are semantically transparent
+            (_, [child]) => child.build_term(bound),
+            _ => LambdaTerm::Const(self.label.clone()),
+        }
+    }
+    
+    /// Normalize this proof by eliminating detours
+    ///
+    /// An elimination whose major premise was just introduced is a detour:
+    /// `→E`/`←E` applied to an `→I`/`←I` is replaced by the body with the
+    /// argument's proof in place of the hypothesis, `⊗E` of a `⊗I` by the body
+    /// with the two components in place of its hypotheses, and `□E` of `□I`
+    /// by the proof under both. This is β-reduction of the proof's lambda term.
+    pub fn normalize(&self) -> ProofNode {
+        let children: Vec<ProofNode> = self.children.iter().map(|child| child.normalize()).collect();
+        
+        let reduced = match (self.rule.as_deref(), children.as_slice()) {
+            (Some("→E" | "←E"), [functor, argument]) => match (functor.rule.as_deref(), functor.children.as_slice()) {
+                (Some("→I" | "←I"), [hyp, body]) => Some(body.discharge(&[(&hyp.label, argument)])),
+                _ => None,
+            },
+            (Some("⊗E"), [product, hyp_a, hyp_b, body]) => match (product.rule.as_deref(), product.children.as_slice()) {
+                (Some("⊗I"), [first, second]) => Some(body.discharge(&[(&hyp_a.label, first), (&hyp_b.label, second)])),
+                _ => None,
+            },
+            (Some("□E"), [boxed]) => match (boxed.rule.as_deref(), boxed.children.as_slice()) {
+                (Some("□I"), [proof]) => Some(proof.clone()),
+                _ => None,
+            },
+            _ => None,
+        };
+        
+        match reduced {
+            // The substitution may create new detours
+            Some(proof) => proof.normalize(),
+            None => self.rebuild(children),
+        }
+    }
+    
+    /// Check whether two proofs have the same normal form
+    ///
+    /// Discharged hypotheses are compared up to renaming.
+    pub fn is_equivalent(&self, other: &ProofNode) -> bool {
+        self.normalize().is_alpha_equivalent(&other.normalize())
+    }
+    
+    /// Check whether two proofs are the same up to renaming discharged hypotheses
+    ///
+    /// Unlike [`ProofNode::is_equivalent`], neither proof is normalized first.
+    pub fn is_alpha_equivalent(&self, other: &ProofNode) -> bool {
+        self.same_proof(other, &mut Vec::new())
+    }
+    
+    /// Replace the named hypotheses by proofs of them
+    fn discharge(&self, substitutions: &[(&str, &ProofNode)]) -> ProofNode {
+        if self.rule.is_none() {
+            return match substitutions.iter().find(|(label, _)| self.hypothesis && *label == self.label) {
+                Some((_, proof)) => (*proof).clone(),
+                None => self.clone(),
+            };
+        }
+        
+        self.rebuild(self.children.iter().map(|child| child.discharge(substitutions)).collect())
+    }
+    
+    /// Rebuild an inference with new children, keeping its type, rule and brackets
+    fn rebuild(&self, children: Vec<ProofNode>) -> ProofNode {
+        match &self.rule {
+            Some(rule) => {
+                let mut node = Self::infer(self.logical_type.clone(), children, rule);
+                node.brackets = self.brackets.clone();
+                node
+            },
+            None => self.clone(),
+        }
+    }
+    
+    /// Compare two proofs, pairing the hypotheses each discharges
+    fn same_proof<'a>(&'a self, other: &'a ProofNode, bound: &mut Vec<(&'a str, &'a str)>) -> bool {
+        if self.logical_type != other.logical_type
+            || self.rule != other.rule
+            || self.hypothesis != other.hypothesis
+            || self.positions != other.positions
+            || self.brackets != other.brackets
+            || self.children.len() != other.children.len()
+        {
+            return false;
+        }
+        
+        if self.rule.is_none() {
+            if !self.hypothesis {
+                return self.label == other.label && self.semantics == other.semantics;
+            }
+            // Bound hypotheses must be paired; free ones keep their names
+            return match bound.iter().rev().find(|(left, right)| *left == self.label || *right == other.label) {
+                Some((left, right)) => *left == self.label && *right == other.label,
+                None => self.label == other.label,
+            };
+        }
+        
+        let discharged = match (self.rule.as_deref(), self.children.as_slice(), other.children.as_slice()) {
+            (Some("→I" | "←I"), [hyp, _], [other_hyp, _]) => vec![(hyp, other_hyp)],
+            (Some("⊗E"), [_, a, b, _], [_, other_a, other_b, _]) => vec![(a, other_a), (b, other_b)],
+            _ => vec![],
+        };
+        
+        let depth = bound.len();
+        bound.extend(discharged.iter().map(|(hyp, other_hyp)| (hyp.label.as_str(), other_hyp.label.as_str())));
+        let same = self.children.iter().zip(&other.children).all(|(child, other_child)| child.same_proof(other_child, bound));
+        bound.truncate(depth);
+        same
+    }
+    
+    /// Render this proof as numbered Fitch-style natural deduction lines
+    ///
+    /// Each line gives a term and its type, followed by the lines it uses and
+    /// the rule applied. A hypothesis discharged by `→I`, `←I` or `⊗E` opens
+    /// a subproof, marked by a bar, that closes at the discharging rule.
+    /// Lexical axioms are justified by `Lex` and other hypotheses by `Hyp`.
+    pub fn to_fitch(&self) -> String {
+        let mut lines = Vec::new();
+        self.fitch_lines(0, &mut Vec::new(), &mut lines);
+        
+        let number_width = lines.len().to_string().len();
+        let width = lines.iter()
+            .map(|line| 2 * line.depth + line.text.chars().count())
+            .max()
+            .unwrap_or(0);
+        
+        lines.iter().enumerate()
+            .map(|(i, line)| {
+                let body = format!("{}{}", "│ ".repeat(line.depth), line.text);
+                format!("{:>number_width$}  {:<width$}  {}\n", i + 1, body, line.justification)
+            })
+            .collect()
+    }
+    
+    /// Add the lines proving this node, returning the number of its conclusion
+    ///
+    /// `scope` maps the hypotheses of the enclosing subproofs to their lines.
+    fn fitch_lines<'a>(&'a self, depth: usize, scope: &mut Vec<(&'a str, usize)>, lines: &mut Vec<FitchLine>) -> usize {
+        let rule = match &self.rule {
+            Some(rule) => rule.as_str(),
+            None => {
+                if let Some(&(_, line)) = scope.iter().rev().find(|(label, _)| *label == self.label) {
+                    return line;
+                }
+                let justification = if self.hypothesis { "Hyp" } else { "Lex" };
+                return self.fitch_line(depth, justification.to_string(), lines);
+            },
+        };
+        
+        let references = match (rule, self.children.as_slice()) {
+            ("→I" | "←I", [hyp, body]) => Self::fitch_subproof(&[hyp], body, depth, scope, lines),
+            ("⊗E", [product, hyp_a, hyp_b, body]) => {
+                let product = product.fitch_lines(depth, scope, lines);
+                format!("{}, {}", product, Self::fitch_subproof(&[hyp_a, hyp_b], body, depth, scope, lines))
+            },
+            (_, children) => children.iter()
+                .map(|child| child.fitch_lines(depth, scope, lines).to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        };
+        
+        let justification = if references.is_empty() {
+            rule.to_string()
+        } else {
+            format!("{} {}", references, rule)
+        };
+        self.fitch_line(depth, justification, lines)
+    }
+    
+    /// Add a subproof from hypotheses to a body, returning its line range
+    fn fitch_subproof<'a>(hyps: &[&'a ProofNode], body: &'a ProofNode, depth: usize, scope: &mut Vec<(&'a str, usize)>, lines: &mut Vec<FitchLine>) -> String {
+        let outer = scope.len();
+        let start = lines.len() + 1;
+        for hyp in hyps {
+            let line = hyp.fitch_line(depth + 1, "Hyp".to_string(), lines);
+            scope.push((&hyp.label, line));
+        }
+        let end = body.fitch_lines(depth + 1, scope, lines);
+        scope.truncate(outer);
+        format!("{}–{}", start, end)
+    }
+    
+    /// Add a line concluding this node, returning its number
+    fn fitch_line(&self, depth: usize, justification: String, lines: &mut Vec<FitchLine>) -> usize {
+        lines.push(FitchLine {
+            depth,
+            text: format!("{} : {}", self.label, self.logical_type),
+            justification,
+        });
+        lines.len()
+    }
+    
     /// Get the depth of this proof tree
     pub fn depth(&self) -> usize {
         if self.children.is_empty() {
@@ -174,6 +541,16 @@ impl ProofNode {
     }
 }
 
+/// A line of a Fitch-style proof
+struct FitchLine {
+    /// The number of subproofs the line is in
+    depth: usize,
+    /// The term and type established
+    text: String,
+    /// The lines used and the rule applied
+    justification: String,
+}
+
 impl fmt::Display for ProofNode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fn print_tree(node: &ProofNode, indent: usize, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -198,6 +575,20 @@ impl fmt::Display for ProofNode {
     }
 }
 
+/// An item of a search state as far as the rest of the search can tell
+type ItemKey = (Vec<usize>, String, LogicalType, Vec<usize>, bool, Vec<String>);
+
+/// A search state up to the order of its items and the way they were derived
+///
+/// Items are identified by their positions, term, type, brackets and open
+/// hypotheses, and pending products by the hypotheses standing in for them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StateKey {
+    items: Vec<ItemKey>,
+    proofs: Vec<ProofNode>,
+    products: Vec<(String, String)>,
+}
+
 /// A state in the proof search for Type-Logical Grammar
 #[derive(Debug, Clone)]
 pub struct ProofSearchState {
@@ -207,16 +598,101 @@ pub struct ProofSearchState {
     pub rule_history: Vec<String>,
     /// The depth of the search
     pub depth: usize,
+    /// The number of input positions the proof must cover
+    pub input_len: usize,
+    /// Products awaiting elimination, each with the hypotheses standing in for its two components
+    pub products: Vec<(ProofNode, ProofNode, ProofNode)>,
+    /// The word of the input at each position
+    pub words: Vec<usize>,
 }
 
 impl ProofSearchState {
     /// Create a new initial search state
+    ///
+    /// The axioms are taken to be the words of the input in order; axioms
+    /// without a position are assigned their index. The input extends to the
+    /// last position covered.
     pub fn new(axioms: Vec<ProofNode>) -> Self {
+        let input_len = axioms.iter()
+            .enumerate()
+            .map(|(i, axiom)| axiom.positions.last().map_or(i, |&last| last) + 1)
+            .max()
+            .unwrap_or(0);
+        let items = axioms.into_iter()
+            .enumerate()
+            .map(|(i, axiom)| if axiom.positions.is_empty() { axiom.at_position(i) } else { axiom })
+            .collect();
+        
         Self {
-            items: axioms,
+            items,
             rule_history: vec![],
             depth: 0,
+            input_len,
+            products: vec![],
+            words: (0..input_len).collect(),
+        }
+    }
+    
+    /// Replace each product-typed axiom by hypotheses for its components
+    ///
+    /// A product `A⊗B` is equivalent to `A` followed by `B`, so its
+    /// hypotheses take its place in the input, covering one position each;
+    /// the positions of the other axioms are shifted to make room, and
+    /// `words` maps both hypotheses back to the product's word. The
+    /// products wait to be eliminated by `⊗E` once both of their
+    /// hypotheses have been consumed.
+    pub fn decompose_products(mut self) -> Self {
+        let mut items = Vec::new();
+        let mut words = Vec::new();
+        let mut position = 0;
+        for mut item in self.items {
+            let covered: Vec<usize> = item.positions.iter().map(|&position| self.words[position]).collect();
+            match &item.logical_type {
+                LogicalType::Product(a, b, _) if !item.hypothesis => {
+                    let (first, last) = (covered[0], covered[covered.len() - 1]);
+                    let hyp_a = ProofNode::assumption(&format!("x{}", first), (**a).clone()).at_position(position);
+                    let hyp_b = ProofNode::assumption(&format!("y{}", first), (**b).clone()).at_position(position + 1);
+                    items.push(hyp_a.clone());
+                    items.push(hyp_b.clone());
+                    self.products.push((item.at_position(position), hyp_a, hyp_b));
+                    words.extend([first, last]);
+                    position += 2;
+                },
+                _ => {
+                    item.positions = (position..position + covered.len()).collect();
+                    position += covered.len();
+                    words.extend(covered);
+                    items.push(item);
+                },
+            }
+        }
+        
+        self.items = items;
+        self.words = words;
+        self.input_len = position;
+        self
+    }
+    
+    /// Eliminate a pending product into an item that has consumed both of its hypotheses
+    pub fn eliminate_product(&self, product: usize, item: usize) -> Option<ProofSearchState> {
+        let (node, hyp_a, hyp_b) = &self.products[product];
+        let body = &self.items[item];
+        let open = body.open_hypotheses();
+        if !open.iter().any(|hyp| hyp.label == hyp_a.label) || !open.iter().any(|hyp| hyp.label == hyp_b.label) {
+            return None;
         }
+        
+        // The hypotheses already cover the product's positions
+        let mut eliminated = ProofNode::infer(
+            body.logical_type.clone(),
+            vec![node.clone(), hyp_a.clone(), hyp_b.clone(), body.clone()],
+            "⊗E",
+        );
+        eliminated.positions = body.positions.clone();
+        
+        let mut state = self.apply_rule("⊗E", eliminated, vec![item]);
+        state.products.remove(product);
+        Some(state)
     }
     
     /// Apply a rule and generate a new state
@@ -245,18 +721,67 @@ impl ProofSearchState {
             items: new_items,
             rule_history: new_history,
             depth: self.depth + 1,
+            input_len: self.input_len,
+            products: self.products.clone(),
+            words: self.words.clone(),
         }
     }
     
     /// Check if this state is a complete proof with the target logical type
+    ///
+    /// A proof is complete when a single item remains, its type unifies with
+    /// the target, it discharges all its hypotheses and eliminates every
+    /// product, and it covers every input position exactly once, in order.
     pub fn is_complete(&self, target: &LogicalType) -> bool {
-        self.items.len() == 1 && &self.items[0].logical_type == target
+        if self.items.len() != 1 || !self.products.is_empty() || self.items[0].logical_type.unify(target).is_none() {
+            return false;
+        }
+        
+        if !self.items[0].open_hypotheses().is_empty() {
+            return false;
+        }
+        
+        self.items[0].positions.iter().copied().eq(0..self.input_len)
+    }
+    
+    /// Get the canonical key of this state
+    ///
+    /// States that reach the same items by applying rules in a different
+    /// order, or by different derivations of the same terms, share a key.
+    /// With `derivations` set the proofs of the items are part of the key,
+    /// so only states differing in the order of their items are identified.
+    pub fn canonical_key(&self, derivations: bool) -> StateKey {
+        let mut items: Vec<(ItemKey, &ProofNode)> = self.items.iter()
+            .map(|item| ((
+                item.positions.clone(),
+                item.label.clone(),
+                item.logical_type.clone(),
+                item.brackets.clone(),
+                item.hypothesis,
+                item.open_hypotheses().into_iter().map(|hyp| hyp.label.clone()).collect(),
+            ), item))
+            .collect();
+        items.sort_by(|(a, _), (b, _)| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+        let proofs = if derivations {
+            items.iter().map(|(_, item)| (*item).clone()).collect()
+        } else {
+            Vec::new()
+        };
+        let items = items.into_iter().map(|(key, _)| key).collect();
+        
+        let products = self.products.iter()
+            .map(|(_, hyp_a, hyp_b)| (hyp_a.label.clone(), hyp_b.label.clone()))
+            .collect();
+        
+        StateKey { items, proofs, products }
     }
     
     /// Get the current proof if this state is complete
+    ///
+    /// The positions of the proof are those of the words of the input.
     pub fn get_proof(&self) -> Option<ProofNode> {
         if self.items.len() == 1 {
-            Some(self.items[0].clone())
+            Some(self.items[0].with_words(&self.words))
         } else {
             None
         }
@@ -284,6 +809,12 @@ impl ParseNode for ProofNode {
     }
 }
 
+impl Rebuild for ProofNode {
+    fn with_children(&self, children: Vec<Self>) -> Self {
+        ProofNode { children, ..self.clone() }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -348,4 +879,139 @@ mod tests {
         assert!(new_state.is_complete(&s));
         assert!(new_state.get_proof().is_some());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_lambda_term_abstraction() {
+        let np = LogicalType::np();
+        let s = LogicalType::s();
+        
+        // From a hypothesis x : np, sees(x)(john) : s gives λx.sees(x)(john)
+        let hyp = ProofNode::axiom("x", np.clone());
+        let sees = ProofNode::axiom("sees", LogicalType::left_impl(
+            LogicalType::right_impl(np.clone(), s.clone()), np.clone()));
+        let vp = ProofNode::infer(LogicalType::right_impl(np.clone(), s.clone()), vec![sees, hyp.clone()], "←E");
+        let body = ProofNode::infer(s.clone(), vec![vp, ProofNode::axiom("john", np.clone())], "→E");
+        let abstraction = ProofNode::infer(LogicalType::left_impl(s, np), vec![hyp, body], "←I");
+        
+        let term = abstraction.lambda_term();
+        assert_eq!(term.to_string(), "λx.sees(x)(john)");
+        assert!(term.free_vars().is_empty());
+    }
+
+    #[test]
+    fn test_to_fitch() {
+        let np = LogicalType::np();
+        let s = LogicalType::s();
+        
+        // Abstracting over the object gives a subproof from x : np
+        let hyp = ProofNode::assumption("x", np.clone());
+        let sees = ProofNode::axiom("sees", LogicalType::left_impl(
+            LogicalType::right_impl(np.clone(), s.clone()), np.clone()));
+        let vp = ProofNode::infer(LogicalType::right_impl(np.clone(), s.clone()), vec![sees, hyp.clone()], "←E");
+        let body = ProofNode::infer(s.clone(), vec![vp, ProofNode::axiom("john", np.clone())], "→E");
+        let abstraction = ProofNode::infer(LogicalType::left_impl(s, np), vec![hyp, body], "←I");
+        
+        assert_eq!(abstraction.to_fitch(), concat!(
+            "1  │ x : np                   Hyp\n",
+            "2  │ sees : (np → s) ← np     Lex\n",
+            "3  │ sees(x) : np → s         2,1 ←E\n",
+            "4  │ john : np                Lex\n",
+            "5  │ sees(x)(john) : s        3,4 →E\n",
+            "6  λx.sees(x)(john) : s ← np  1–5 ←I\n",
+        ));
+    }
+    
+    #[test]
+    fn test_normalize_implication_detour() {
+        let np = LogicalType::np();
+        let s = LogicalType::s();
+        let vp = LogicalType::right_impl(np.clone(), s.clone());
+        
+        let john = ProofNode::axiom("john", np.clone()).at_position(0);
+        let sleeps = ProofNode::axiom("sleeps", vp.clone()).at_position(1);
+        let direct = ProofNode::infer(s.clone(), vec![sleeps.clone(), john.clone()], "→E");
+        
+        // Abstract over the subject and apply the abstraction to it again
+        let detour = |variable: &str| {
+            let hyp = ProofNode::assumption(variable, np.clone());
+            let body = ProofNode::infer(s.clone(), vec![sleeps.clone(), hyp.clone()], "→E");
+            let abstraction = ProofNode::infer(vp.clone(), vec![hyp, body], "→I");
+            ProofNode::infer(s.clone(), vec![abstraction, john.clone()], "→E")
+        };
+        
+        assert_ne!(detour("x"), direct);
+        assert_eq!(detour("x").normalize(), direct);
+        assert_eq!(direct.normalize(), direct);
+        
+        // Proofs differing only in their hypotheses' names are equivalent
+        let hyp = ProofNode::assumption("y", np.clone());
+        let body = ProofNode::infer(s.clone(), vec![sleeps.clone(), hyp.clone()], "→E");
+        let abstraction = ProofNode::infer(vp.clone(), vec![hyp, body], "→I");
+        assert!(detour("x").is_equivalent(&direct));
+        assert!(abstraction.is_equivalent(&detour("x").children[0]));
+        
+        let mary = ProofNode::axiom("mary", np.clone()).at_position(0);
+        assert!(!detour("x").is_equivalent(&ProofNode::infer(s, vec![sleeps, mary], "→E")));
+    }
+    
+    #[test]
+    fn test_normalize_product_detour() {
+        let np = LogicalType::np();
+        let s = LogicalType::s();
+        let dio = ProofNode::axiom("dio", LogicalType::right_impl(np.clone(), LogicalType::right_impl(np.clone(), s.clone()))).at_position(2);
+        let juan = ProofNode::axiom("juan", np.clone()).at_position(0);
+        let maria = ProofNode::axiom("maria", np.clone()).at_position(1);
+        
+        let apply = |args: [ProofNode; 2]| {
+            let [first, second] = args;
+            let partial = ProofNode::infer(LogicalType::right_impl(np.clone(), s.clone()), vec![dio.clone(), second], "→E");
+            ProofNode::infer(s.clone(), vec![partial, first], "→E")
+        };
+        
+        // Pair the two arguments only to take the pair apart again
+        let pair = ProofNode::infer(LogicalType::product(np.clone(), np.clone()), vec![juan.clone(), maria.clone()], "⊗I");
+        let (x, y) = (ProofNode::assumption("x", np.clone()), ProofNode::assumption("y", np.clone()));
+        let body = apply([x.clone(), y.clone()]);
+        let detour = ProofNode::infer(s.clone(), vec![pair, x, y, body], "⊗E");
+        
+        let direct = apply([juan, maria]);
+        assert_eq!(detour.normalize(), direct);
+        assert!(detour.is_equivalent(&direct));
+        assert_eq!(detour.lambda_term().beta_normalize(), direct.lambda_term());
+    }
+
+    #[test]
+    fn test_incomplete_coverage() {
+        let np = LogicalType::np();
+        let s = LogicalType::s();
+        let verb_type = LogicalType::right_impl(np.clone(), s.clone());
+        
+        // "john sleeps john": the last word is never consumed
+        let state = ProofSearchState::new(vec![
+            ProofNode::axiom("john", np.clone()),
+            ProofNode::axiom("sleeps", verb_type),
+            ProofNode::axiom("john", np.clone()),
+        ]);
+        
+        let combined = ProofNode::infer(
+            s.clone(),
+            vec![state.items[1].clone(), state.items[0].clone()],
+            "→E"
+        );
+        assert_eq!(combined.positions, vec![0, 1]);
+        
+        let new_state = state.apply_rule("→E", combined.clone(), vec![0, 1]);
+        assert!(!new_state.is_complete(&s));
+        
+        // A single item that skips a position is not complete either
+        let gapped = ProofSearchState {
+            items: vec![combined],
+            rule_history: vec![],
+            depth: 1,
+            input_len: 3,
+            products: vec![],
+            words: vec![0, 1, 2],
+        };
+        assert!(!gapped.is_complete(&s));
+    }
+}
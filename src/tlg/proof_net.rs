@@ -25,6 +25,8 @@ pub struct ProofNet {
 pub enum ProofNetNode {
     /// Atomic formula
     Atom(String, FeatureStructure, bool), // name, features, polarity (true=positive, false=negative)
+    /// Unit formula: 1 when positive, ⊥ when negative
+    One(bool), // polarity
     /// Tensor (⊗) node
     Tensor(usize, usize, Option<Modality>), // left child, right child, modality
     /// Par (⅋) node
@@ -77,6 +79,11 @@ impl ProofNet {
                 nodes.push(ProofNetNode::Atom(name.clone(), features.clone(), polarity));
                 index
             },
+            LogicalType::Unit => {
+                let index = nodes.len();
+                nodes.push(ProofNetNode::One(polarity));
+                index
+            },
             LogicalType::RightImplication(a, b, modality) => {
                 if polarity {
                     // A → B with positive polarity becomes A⊥ ⅋ B
@@ -327,6 +334,7 @@ impl ProofNet {
                 let atomic_type = LogicalType::atomic_with_features(name, &features);
                 Some(ProofNode::axiom(&format!("{}_{}", name, node_index), atomic_type))
             },
+            ProofNetNode::One(_) => Some(ProofNode::unit()),
             ProofNetNode::Tensor(left, right, modality) => {
                 if let (Some(left_tree), Some(right_tree)) = (
                     self.build_proof_tree(*left),